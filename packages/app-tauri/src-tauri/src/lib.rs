@@ -1,15 +1,25 @@
 #![cfg_attr(all(not(debug_assertions), target_os = "windows"), windows_subsystem = "windows")]
 
-use backend_core::entity::{entry, hierarchy_report, Presentation, ReadRoot};
-use backend_core::{init, Error};
+use backend_core::actor::Actor;
+use backend_core::entity::{
+  account_balance, account_register, entry, hierarchy_report, history, journal, reconciliation,
+  rule, trial_balance, Presentation, ReadRoot,
+};
+use backend_core::import::{self, ImportResult};
+use backend_core::{backup, export, init, undo, Error};
 use futures::TryFutureExt;
 use sea_orm::{DbConn, TransactionError, TransactionTrait};
 use std::collections::HashSet;
 use tauri::{Emitter, Manager};
 use uuid::Uuid;
 
+/// The only user of the desktop app, recorded as the actor of the history
+const ACTOR: &str = "desktop";
+
+type UndoSession = futures::lock::Mutex<undo::Session>;
+
 macro_rules! generate_handlers {
-  ($entity: ident) => {
+  (@find $entity: ident) => {
     paste::paste! {
       #[tauri::command]
       async fn [<$entity _find_by_id>](
@@ -50,14 +60,19 @@ macro_rules! generate_handlers {
           })
           .await
       }
+    }
+  };
+  ($entity: ident) => {
+    generate_handlers!(@find $entity);
 
+    paste::paste! {
       #[tauri::command]
       async fn [<$entity _handle_command>](
         db: ::tauri::State<'_, DbConn>,
         command: ::backend_core::entity::$entity::Command,
       ) -> ::backend_core::Result<Vec<::backend_core::entity::$entity::Root>> {
         db.inner()
-          .transaction(|tx| Box::pin(async move { ::backend_core::entity::$entity::Root::handle(tx, command).await }))
+          .transaction(|tx| Box::pin(async move { ::backend_core::entity::$entity::Root::handle(tx, &Actor::system(ACTOR), command).await }))
           .map_err(|err| match err {
             TransactionError::Connection(err) => err.into(),
             TransactionError::Transaction(err) => err,
@@ -66,10 +81,258 @@ macro_rules! generate_handlers {
       }
     }
   };
+  ($entity: ident, $actor: expr) => {
+    generate_handlers!(@find $entity);
+
+    paste::paste! {
+      #[tauri::command]
+      async fn [<$entity _handle_command>](
+        db: ::tauri::State<'_, DbConn>,
+        session: ::tauri::State<'_, UndoSession>,
+        command: ::backend_core::entity::$entity::Command,
+      ) -> ::backend_core::Result<Vec<::backend_core::entity::$entity::Root>> {
+        let (roots, inverse) = db.inner()
+          .transaction(|tx| Box::pin(async move { ::backend_core::entity::$entity::Root::handle_with_inverse(tx, &Actor::system($actor), command).await }))
+          .map_err(|err| match err {
+            TransactionError::Connection(err) => err.into(),
+            TransactionError::Transaction(err) => err,
+          })
+          .await?;
+        session.lock().await.push(inverse);
+        Ok(roots)
+      }
+    }
+  };
+}
+
+macro_rules! generate_report_handlers {
+  ($report: ident) => {
+    paste::paste! {
+      #[tauri::command]
+      async fn [<$report _find_all>](
+        db: ::tauri::State<'_, DbConn>,
+        query: Option<::backend_core::entity::$report::Query>,
+      ) -> ::backend_core::Result<Vec<::backend_core::entity::$report::Root>> {
+        db.inner()
+          .transaction(|tx| Box::pin(::backend_core::entity::$report::Root::find_all(tx, query, None, None)))
+          .map_err(|err| match err {
+            TransactionError::Connection(err) => err.into(),
+            TransactionError::Transaction(err) => err,
+          })
+          .await
+      }
+    }
+  };
+}
+
+generate_handlers!(journal, ACTOR);
+generate_handlers!(account, ACTOR);
+generate_handlers!(reconciliation);
+generate_handlers!(csv_profile);
+generate_handlers!(rule);
+generate_handlers!(payee);
+
+generate_report_handlers!(income_statement);
+generate_report_handlers!(balance_sheet);
+generate_report_handlers!(cash_flow_statement);
+generate_report_handlers!(time_series_report);
+generate_report_handlers!(trial_balance);
+generate_report_handlers!(payee_report);
+
+#[tauri::command]
+async fn account_register_find_all(
+  db: tauri::State<'_, DbConn>,
+  query: Option<account_register::Query>,
+  size: Option<u64>,
+) -> backend_core::Result<Vec<account_register::Root>> {
+  db.inner()
+    .transaction(|tx| Box::pin(account_register::Root::find_all(tx, query, size, None)))
+    .map_err(|err| match err {
+      TransactionError::Connection(err) => err.into(),
+      TransactionError::Transaction(err) => err,
+    })
+    .await
+}
+
+#[tauri::command]
+async fn history_find_all(
+  db: tauri::State<'_, DbConn>,
+  query: Option<history::Query>,
+  size: Option<u64>,
+  sort: Option<history::Sort>,
+) -> backend_core::Result<Vec<history::Root>> {
+  db.inner()
+    .transaction(|tx| Box::pin(history::Root::find_all(tx, query, size, sort)))
+    .map_err(|err| match err {
+      TransactionError::Connection(err) => err.into(),
+      TransactionError::Transaction(err) => err,
+    })
+    .await
+}
+
+#[tauri::command]
+async fn account_balance_rebuild(
+  db: tauri::State<'_, DbConn>,
+  journal_ids: HashSet<Uuid>,
+) -> backend_core::Result<()> {
+  db.inner()
+    .transaction(|tx| Box::pin(account_balance::rebuild(tx, journal_ids)))
+    .map_err(|err| match err {
+      TransactionError::Connection(err) => err.into(),
+      TransactionError::Transaction(err) => err,
+    })
+    .await
+}
+
+#[tauri::command]
+async fn reconciliation_propose(
+  db: tauri::State<'_, DbConn>,
+  statement: reconciliation::Statement,
+) -> backend_core::Result<reconciliation::Proposal> {
+  db.inner()
+    .transaction(|tx| Box::pin(reconciliation::Root::propose(tx, statement)))
+    .map_err(|err| match err {
+      TransactionError::Connection(err) => err.into(),
+      TransactionError::Transaction(err) => err,
+    })
+    .await
+}
+
+#[tauri::command]
+async fn rule_apply(
+  db: tauri::State<'_, DbConn>,
+  command: rule::CommandApply,
+) -> backend_core::Result<Vec<rule::Change>> {
+  db.inner()
+    .transaction(|tx| {
+      Box::pin(async move { rule::Root::apply(tx, &Actor::system(ACTOR), command).await })
+    })
+    .map_err(|err| match err {
+      TransactionError::Connection(err) => err.into(),
+      TransactionError::Transaction(err) => err,
+    })
+    .await
+}
+
+#[tauri::command]
+async fn import_csv(
+  db: tauri::State<'_, DbConn>,
+  import: import::csv::Import,
+) -> backend_core::Result<ImportResult> {
+  db.inner()
+    .transaction(|tx| Box::pin(import::csv::import(tx, import)))
+    .map_err(|err| match err {
+      TransactionError::Connection(err) => err.into(),
+      TransactionError::Transaction(err) => err,
+    })
+    .await
+}
+
+#[tauri::command]
+async fn import_ofx(
+  db: tauri::State<'_, DbConn>,
+  import: import::ofx::Import,
+) -> backend_core::Result<ImportResult> {
+  db.inner()
+    .transaction(|tx| Box::pin(import::ofx::import(tx, import)))
+    .map_err(|err| match err {
+      TransactionError::Connection(err) => err.into(),
+      TransactionError::Transaction(err) => err,
+    })
+    .await
 }
 
-generate_handlers!(journal);
-generate_handlers!(account);
+#[tauri::command]
+async fn import_plain_text(
+  db: tauri::State<'_, DbConn>,
+  import: import::plain_text::Import,
+) -> backend_core::Result<import::ImportResultWithAccounts> {
+  db.inner()
+    .transaction(|tx| Box::pin(import::plain_text::import(tx, import)))
+    .map_err(|err| match err {
+      TransactionError::Connection(err) => err.into(),
+      TransactionError::Transaction(err) => err,
+    })
+    .await
+}
+
+#[tauri::command]
+async fn import_qif(
+  db: tauri::State<'_, DbConn>,
+  import: import::qif::Import,
+) -> backend_core::Result<import::ImportResultWithAccounts> {
+  db.inner()
+    .transaction(|tx| Box::pin(import::qif::import(tx, import)))
+    .map_err(|err| match err {
+      TransactionError::Connection(err) => err.into(),
+      TransactionError::Transaction(err) => err,
+    })
+    .await
+}
+
+#[tauri::command]
+async fn export_plain_text(
+  db: tauri::State<'_, DbConn>,
+  export: export::plain_text::Export,
+) -> backend_core::Result<String> {
+  db.inner()
+    .transaction(|tx| Box::pin(export::plain_text::export(tx, export)))
+    .map_err(|err| match err {
+      TransactionError::Connection(err) => err.into(),
+      TransactionError::Transaction(err) => err,
+    })
+    .await
+}
+
+#[tauri::command]
+async fn journal_backup(
+  db: tauri::State<'_, DbConn>,
+  journal_id: Uuid,
+) -> backend_core::Result<backup::Backup> {
+  db.inner()
+    .transaction(|tx| Box::pin(backup::backup(tx, journal_id)))
+    .map_err(|err| match err {
+      TransactionError::Connection(err) => err.into(),
+      TransactionError::Transaction(err) => err,
+    })
+    .await
+}
+
+#[tauri::command]
+async fn journal_restore(
+  db: tauri::State<'_, DbConn>,
+  restore: backup::Restore,
+) -> backend_core::Result<journal::Root> {
+  db.inner()
+    .transaction(|tx| Box::pin(backup::restore(tx, restore)))
+    .map_err(|err| match err {
+      TransactionError::Connection(err) => err.into(),
+      TransactionError::Transaction(err) => err,
+    })
+    .await
+}
+
+#[tauri::command]
+async fn trial_balance_export_csv(
+  db: tauri::State<'_, DbConn>,
+  query: Option<trial_balance::Query>,
+) -> backend_core::Result<Vec<String>> {
+  db.inner()
+    .transaction(|tx| {
+      Box::pin(async move {
+        trial_balance::Root::find_all(tx, query, None, None)
+          .await?
+          .iter()
+          .map(trial_balance::Root::to_csv)
+          .collect()
+      })
+    })
+    .map_err(|err| match err {
+      TransactionError::Connection(err) => err.into(),
+      TransactionError::Transaction(err) => err,
+    })
+    .await
+}
 
 #[tauri::command]
 async fn entry_find_by_id(
@@ -123,20 +386,43 @@ async fn entry_find_all(
 #[tauri::command]
 async fn entry_handle_command(
   db: tauri::State<'_, DbConn>,
+  session: tauri::State<'_, UndoSession>,
   command: entry::Command,
 ) -> backend_core::Result<Vec<entry::Presentation>> {
-  db.inner()
+  let (presentations, inverse) = db
+    .inner()
     .transaction(|tx| {
       Box::pin(async move {
-        let roots = entry::Root::handle(tx, command).await?;
-        entry::Presentation::from_roots(tx, roots).await
+        let (roots, inverse) =
+          entry::Root::handle_with_inverse(tx, &Actor::system(ACTOR), command).await?;
+        Ok::<_, Error>((entry::Presentation::from_roots(tx, roots).await?, inverse))
       })
     })
     .map_err(|err| match err {
       TransactionError::Connection(err) => err.into(),
       TransactionError::Transaction(err) => err,
     })
-    .await
+    .await?;
+  session.lock().await.push(inverse);
+  Ok(presentations)
+}
+
+/// Undo the last command of the journals, the accounts or the entries, and return if there is any
+#[tauri::command]
+async fn undo(
+  db: tauri::State<'_, DbConn>,
+  session: tauri::State<'_, UndoSession>,
+) -> backend_core::Result<bool> {
+  session.lock().await.undo(db.inner(), &Actor::system(ACTOR)).await
+}
+
+/// Redo the last undone command, and return if there is any
+#[tauri::command]
+async fn redo(
+  db: tauri::State<'_, DbConn>,
+  session: tauri::State<'_, UndoSession>,
+) -> backend_core::Result<bool> {
+  session.lock().await.redo(db.inner(), &Actor::system(ACTOR)).await
 }
 
 #[tauri::command]
@@ -162,9 +448,11 @@ async fn hierarchy_report_find_by_id(
 async fn hierarchy_report_find_all(
   db: tauri::State<'_, DbConn>,
   query: Option<hierarchy_report::Query>,
+  size: Option<u64>,
+  sort: Option<hierarchy_report::Sort>,
 ) -> backend_core::Result<Vec<hierarchy_report::Root>> {
   db.inner()
-    .transaction(|tx| Box::pin(hierarchy_report::Root::find_all(tx, query, None, None)))
+    .transaction(|tx| Box::pin(hierarchy_report::Root::find_all(tx, query, size, sort)))
     .map_err(|err| match err {
       TransactionError::Connection(err) => err.into(),
       TransactionError::Transaction(err) => err,
@@ -188,6 +476,7 @@ fn setup(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
   tauri::Builder::default()
+    .manage(UndoSession::default())
     .setup(setup)
     .plugin(tauri_plugin_clipboard_manager::init())
     .plugin(tauri_plugin_global_shortcut::Builder::new().build())
@@ -210,6 +499,39 @@ pub fn run() {
       entry_handle_command,
       hierarchy_report_find_by_id,
       hierarchy_report_find_all,
+      income_statement_find_all,
+      balance_sheet_find_all,
+      cash_flow_statement_find_all,
+      time_series_report_find_all,
+      trial_balance_find_all,
+      trial_balance_export_csv,
+      account_register_find_all,
+      account_balance_rebuild,
+      history_find_all,
+      undo,
+      redo,
+      reconciliation_find_by_id,
+      reconciliation_find_all,
+      reconciliation_handle_command,
+      reconciliation_propose,
+      csv_profile_find_by_id,
+      csv_profile_find_all,
+      csv_profile_handle_command,
+      rule_find_by_id,
+      rule_find_all,
+      rule_handle_command,
+      rule_apply,
+      payee_find_by_id,
+      payee_find_all,
+      payee_handle_command,
+      payee_report_find_all,
+      import_csv,
+      import_ofx,
+      import_plain_text,
+      import_qif,
+      export_plain_text,
+      journal_backup,
+      journal_restore,
     ])
     .run(tauri::generate_context!())
     .expect("error while running tauri application");
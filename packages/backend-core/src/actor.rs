@@ -0,0 +1,104 @@
+use crate::entity::membership::{self, Role};
+use crate::entity::{journal, ReadRoot, FIELD_ID, FIELD_USER};
+use crate::error::ErrorForbidden;
+use itertools::Itertools;
+use sea_orm::{ConnectionTrait, Iterable};
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use uuid::Uuid;
+
+/// Who handles the commands and reads the roots, as recorded in the histories
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Actor {
+  /// Allowed everything, such as the single-user desktop app and the administrators of the servers
+  System(String),
+  /// Allowed the journals the user is a member of only
+  User(Uuid),
+}
+
+impl Display for Actor {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Actor::System(name) => write!(f, "{}", name),
+      Actor::User(id) => write!(f, "user:{}", id),
+    }
+  }
+}
+
+impl Actor {
+  pub fn system(name: impl ToString) -> Actor {
+    Actor::System(name.to_string())
+  }
+
+  /// The journals the actor is allowed at the role or above, or `None` for all of them
+  pub async fn find_journal_ids(
+    &self,
+    db: &impl ConnectionTrait,
+    role: Role,
+  ) -> crate::Result<Option<HashSet<Uuid>>> {
+    let Actor::User(user_id) = self else {
+      return Ok(None);
+    };
+
+    let memberships = membership::Root::find_all(
+      db,
+      Some(membership::Query {
+        user_id: HashSet::from_iter([*user_id]),
+        role: Role::iter().filter(|other| *other >= role).collect(),
+        ..Default::default()
+      }),
+      None,
+      None,
+    )
+    .await?;
+    Ok(Some(memberships.into_iter().map(|membership| membership.journal_id).collect()))
+  }
+
+  /// Check the actor is allowed all the journals at the role or above
+  pub async fn check_journals(
+    &self,
+    db: &impl ConnectionTrait,
+    journal_ids: impl IntoIterator<Item = Uuid>,
+    role: Role,
+  ) -> crate::Result<()> {
+    let Some(allowed) = self.find_journal_ids(db, role).await? else {
+      return Ok(());
+    };
+
+    let forbidden =
+      journal_ids.into_iter().filter(|id| !allowed.contains(id)).unique().sorted().join(", ");
+    if forbidden.is_empty() {
+      Ok(())
+    } else {
+      Err(crate::Error::Forbidden(ErrorForbidden {
+        actor: self.to_string(),
+        entity: journal::TYPE.to_string(),
+        values: vec![(FIELD_ID.to_string(), forbidden)],
+      }))
+    }
+  }
+
+  /// Check the actor is the user or a system one, for the entities of the users themselves
+  pub fn check_user(&self, user_id: Uuid, entity: &str) -> crate::Result<()> {
+    match self {
+      Actor::User(id) if *id != user_id => Err(crate::Error::Forbidden(ErrorForbidden {
+        actor: self.to_string(),
+        entity: entity.to_string(),
+        values: vec![(FIELD_USER.to_string(), user_id.to_string())],
+      })),
+      _ => Ok(()),
+    }
+  }
+
+  /// Check the actor is a system one, for the entities managed by the administrators only
+  pub fn check_system(&self, entity: &str) -> crate::Result<()> {
+    match self {
+      Actor::System(_) => Ok(()),
+      Actor::User(_) => Err(crate::Error::Forbidden(ErrorForbidden {
+        actor: self.to_string(),
+        entity: entity.to_string(),
+        values: Vec::default(),
+      })),
+    }
+  }
+}
@@ -0,0 +1,271 @@
+//! Backup of the complete journals as versioned JSON documents, to be restored into any database
+
+use crate::entity::{account, entry, journal, payee, ReadRoot, WriteRoot, FIELD_ID, FIELD_NAME};
+use crate::error::{ErrorExistingEntity, ErrorNotFound, ErrorOutOfRange};
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use sea_orm::{ConnectionTrait, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+pub const TYPE: &str = "Backup";
+pub const FIELD_VERSION: &str = "version";
+/// The version of the documents written by this build, the only one can be restored
+pub const VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Backup {
+  pub version: u32,
+  pub journal: journal::Root,
+  pub accounts: Vec<account::Root>,
+  #[serde(default)]
+  pub payees: Vec<payee::Root>,
+  pub entries: Vec<entry::Root>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+  /// Keep the IDs in the document, failing if any of them exists
+  #[default]
+  #[serde(rename = "preserve")]
+  Preserve,
+  /// Generate new IDs, so that the journal can be restored as a copy
+  #[serde(rename = "remap")]
+  Remap,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Restore {
+  pub backup: Backup,
+  #[serde(default)]
+  pub mode: Mode,
+  /// Rename the journal, which is required to restore a copy into the same database
+  #[serde(default)]
+  pub name: Option<String>,
+}
+
+pub async fn backup(db: &impl ConnectionTrait, journal_id: Uuid) -> crate::Result<Backup> {
+  let journal = journal::Root::find_one(
+    db,
+    Some(journal::Query { id: HashSet::from_iter([journal_id]), ..Default::default() }),
+  )
+  .await?
+  .ok_or_else(|| {
+    crate::Error::NotFound(ErrorNotFound {
+      entity: journal::TYPE.to_string(),
+      values: vec![(FIELD_ID.to_string(), journal_id.to_string())],
+    })
+  })?;
+
+  let accounts = account::Root::find_all(
+    db,
+    Some(account::Query { journal_id: HashSet::from_iter([journal.id]), ..Default::default() }),
+    None,
+    Some(account::Sort::Name),
+  )
+  .await?;
+  let payees = payee::Root::find_all(
+    db,
+    Some(payee::Query { journal_id: HashSet::from_iter([journal.id]), ..Default::default() }),
+    None,
+    Some(payee::Sort::Name),
+  )
+  .await?;
+  let mut entries = entry::Root::find_all(
+    db,
+    Some(entry::Query { journal_id: HashSet::from_iter([journal.id]), ..Default::default() }),
+    None,
+    None,
+  )
+  .await?;
+  entries.sort_by(|a, b| (a.date, &a.name, a.id).cmp(&(b.date, &b.name, b.id)));
+
+  Ok(Backup { version: VERSION, journal, accounts, payees, entries })
+}
+
+/// Restore the journal in a transaction of its own, nested in the one of the caller if any, so
+/// that nothing is left behind on any errors
+pub async fn restore(
+  db: &(impl ConnectionTrait + TransactionTrait),
+  restore: Restore,
+) -> crate::Result<journal::Root> {
+  let Restore { backup, mode, name } = restore;
+  if backup.version != VERSION {
+    return Err(crate::Error::OutOfRange(ErrorOutOfRange {
+      entity: TYPE.to_string(),
+      field: FIELD_VERSION.to_string(),
+      start: Some(VERSION.to_string()),
+      end: Some(VERSION.to_string()),
+    }));
+  }
+
+  let ids: HashMap<Uuid, Uuid> = [backup.journal.id]
+    .into_iter()
+    .chain(backup.accounts.iter().map(|account| account.id))
+    .chain(backup.payees.iter().map(|payee| payee.id))
+    .chain(backup.entries.iter().map(|entry| entry.id))
+    .map(|id| (id, if mode == Mode::Remap { Uuid::new_v4() } else { id }))
+    .collect();
+  let id = |id: &Uuid| ids.get(id).copied().unwrap_or(*id);
+  let created_at = |created_at: DateTime<Utc>| {
+    if mode == Mode::Preserve {
+      created_at
+    } else {
+      DateTime::default()
+    }
+  };
+
+  let txn = db.begin().await?;
+
+  let journal = journal::Builder::default()
+    .id(id(&backup.journal.id))
+    .name(name.unwrap_or(backup.journal.name))
+    .description(backup.journal.description)
+    .unit(backup.journal.unit)
+    .tags(backup.journal.tags)
+    .created_at(created_at(backup.journal.created_at))
+    .build()?;
+  if !journal::Root::find_all(
+    &txn,
+    Some(journal::Query { name: HashSet::from_iter([journal.name.clone()]), ..Default::default() }),
+    None,
+    None,
+  )
+  .await?
+  .is_empty()
+  {
+    return Err(crate::Error::ExistingEntity(ErrorExistingEntity {
+      entity: journal::TYPE.to_string(),
+      values: vec![(FIELD_NAME.to_string(), journal.name)],
+    }));
+  }
+
+  let accounts: Vec<_> = backup
+    .accounts
+    .into_iter()
+    .map(|account| {
+      account::Builder::default()
+        .id(id(&account.id))
+        .journal_id(journal.id)
+        .name(account.name)
+        .description(account.description)
+        .unit(account.unit)
+        .typ(account.typ)
+        .tags(account.tags)
+        .created_at(created_at(account.created_at))
+        .build()
+    })
+    .try_collect()?;
+  let duplicated = accounts.iter().map(|account| &account.name).duplicates().sorted().join(", ");
+  if !duplicated.is_empty() {
+    return Err(crate::Error::ExistingEntity(ErrorExistingEntity {
+      entity: account::TYPE.to_string(),
+      values: vec![(FIELD_NAME.to_string(), duplicated)],
+    }));
+  }
+  let accounts: HashMap<_, _> = accounts.into_iter().map(|account| (account.id, account)).collect();
+
+  let payees: Vec<_> = backup
+    .payees
+    .into_iter()
+    .map(|payee| {
+      payee::Builder::default()
+        .id(id(&payee.id))
+        .journal_id(journal.id)
+        .name(payee.name)
+        .aliases(payee.aliases)
+        .default_account_id(payee.default_account_id.map(|account_id| id(&account_id)))
+        .tags(payee.tags)
+        .build(&accounts)
+    })
+    .try_collect()?;
+
+  let entries: Vec<_> = backup
+    .entries
+    .into_iter()
+    .map(|entry| {
+      entry::Builder::default()
+        .id(id(&entry.id))
+        .journal_id(journal.id)
+        .name(entry.name)
+        .description(entry.description)
+        .typ(entry.typ)
+        .date(entry.date)
+        .tags(entry.tags)
+        .payee_id(entry.payee_id.map(|payee_id| id(&payee_id)))
+        .items(
+          entry
+            .items
+            .into_iter()
+            .map(|item| entry::Item { account: id(&item.account), ..item })
+            .collect(),
+        )
+        .created_at(created_at(entry.created_at))
+        .build(&accounts)
+    })
+    .try_collect()?;
+
+  if mode == Mode::Preserve {
+    let existings = journal::Root::find_all(
+      &txn,
+      Some(journal::Query { id: HashSet::from_iter([journal.id]), ..Default::default() }),
+      None,
+      None,
+    )
+    .await?;
+    check_ids(journal::TYPE, existings.iter().map(|root| root.id))?;
+    let existings = account::Root::find_all(
+      &txn,
+      Some(account::Query { id: accounts.keys().copied().collect(), ..Default::default() }),
+      None,
+      None,
+    )
+    .await?;
+    check_ids(account::TYPE, existings.iter().map(|root| root.id))?;
+    let existings = payee::Root::find_all(
+      &txn,
+      Some(payee::Query {
+        id: payees.iter().map(|payee| payee.id).collect(),
+        ..Default::default()
+      }),
+      None,
+      None,
+    )
+    .await?;
+    check_ids(payee::TYPE, existings.iter().map(|root| root.id))?;
+    let existings = entry::Root::find_all(
+      &txn,
+      Some(entry::Query {
+        id: entries.iter().map(|entry| entry.id).collect(),
+        ..Default::default()
+      }),
+      None,
+      None,
+    )
+    .await?;
+    check_ids(entry::TYPE, existings.iter().map(|root| root.id))?;
+  }
+
+  let journal = journal::Root::save(&txn, [journal]).await?.remove(0);
+  account::Root::save(&txn, accounts.into_values()).await?;
+  payee::Root::save(&txn, payees).await?;
+  entry::Root::save(&txn, entries).await?;
+
+  txn.commit().await?;
+  Ok(journal)
+}
+
+fn check_ids(entity: &str, existings: impl IntoIterator<Item = Uuid>) -> crate::Result<()> {
+  let existings = existings.into_iter().sorted().join(", ");
+  if existings.is_empty() {
+    Ok(())
+  } else {
+    Err(crate::Error::ExistingEntity(ErrorExistingEntity {
+      entity: entity.to_string(),
+      values: vec![(FIELD_ID.to_string(), existings)],
+    }))
+  }
+}
@@ -3,6 +3,7 @@ use crate::entity::{
   normalize_description, normalize_name, normalize_tags, normalize_unit, FIELD_JOURNAL, FIELD_TYPE,
 };
 use crate::error::ErrorRequiredField;
+use chrono::{DateTime, Utc};
 use std::collections::HashSet;
 use uuid::Uuid;
 
@@ -15,6 +16,9 @@ pub struct Builder {
   unit: String,
   typ: Option<Type>,
   tags: HashSet<String>,
+  version: i32,
+  created_at: DateTime<Utc>,
+  updated_at: DateTime<Utc>,
 }
 
 impl From<Root> for Builder {
@@ -27,6 +31,9 @@ impl From<Root> for Builder {
       unit: value.unit,
       typ: Some(value.typ),
       tags: value.tags,
+      version: value.version,
+      created_at: value.created_at,
+      updated_at: value.updated_at,
     }
   }
 }
@@ -55,6 +62,9 @@ impl Builder {
         })
       })?,
       tags,
+      version: self.version,
+      created_at: self.created_at,
+      updated_at: self.updated_at,
     })
   }
 
@@ -85,4 +95,9 @@ impl Builder {
   pub fn tags(self, tags: impl IntoIterator<Item = impl ToString>) -> Builder {
     Builder { tags: tags.into_iter().map(|s| s.to_string()).collect(), ..self }
   }
+
+  /// Keep the time the root is created at when restoring it, rather than taking the one saved at
+  pub fn created_at(self, created_at: DateTime<Utc>) -> Builder {
+    Builder { created_at, ..self }
+  }
 }
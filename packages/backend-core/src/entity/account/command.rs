@@ -1,6 +1,6 @@
 use crate::entity::account;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -16,6 +16,29 @@ pub enum Command {
   Batch(CommandBatch),
 }
 
+impl Command {
+  /// The IDs of the existing entities the command refers to
+  pub fn ids(&self) -> HashSet<Uuid> {
+    match self {
+      Command::Create(_) => HashSet::default(),
+      Command::Update(command) => HashSet::from_iter([command.id]),
+      Command::Delete(command) => command.id.clone(),
+      Command::Batch(command) => {
+        command.update.iter().map(|command| command.id).chain(command.delete.clone()).collect()
+      }
+    }
+  }
+
+  /// The IDs of the journals the new entities are created in
+  pub fn journal_ids(&self) -> HashSet<Uuid> {
+    match self {
+      Command::Create(command) => HashSet::from_iter([command.journal_id]),
+      Command::Batch(command) => command.create.iter().map(|command| command.journal_id).collect(),
+      _ => HashSet::default(),
+    }
+  }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct CommandCreate {
@@ -44,12 +67,20 @@ pub struct CommandUpdate {
   pub typ: Option<account::Type>,
   #[serde(default)]
   pub tags: Option<HashSet<String>>,
+  /// Conflicts if the entity has been saved since read at this version
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub expected_version: Option<i32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct CommandDelete {
   #[serde(default)]
   pub id: HashSet<Uuid>,
+  /// The versions the entities are expected at, keyed by the IDs. The ones missing are deleted
+  /// whatever the versions are
+  #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+  pub expected_version: HashMap<Uuid, i32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -18,6 +18,9 @@ pub struct Model {
   pub unit: String,
   #[sea_orm(indexed, column_name = "type")]
   pub typ: Type,
+  pub version: i32,
+  pub created_at: DateTimeUtc,
+  pub updated_at: DateTimeUtc,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -76,3 +79,10 @@ pub enum Type {
   #[sea_orm(string_value = "Q")]
   Equity,
 }
+
+impl Type {
+  /// If the normal balance of this type is on the debit side, that is, Assets and Expenses
+  pub fn is_debit(&self) -> bool {
+    matches!(self, Type::Asset | Type::Expense)
+  }
+}
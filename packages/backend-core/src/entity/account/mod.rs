@@ -8,10 +8,15 @@ pub use command::*;
 pub use database::*;
 pub use query::*;
 
+use crate::actor::Actor;
+use crate::entity::membership::Role;
 use crate::entity::{
-  account_tag, journal, ReadRoot, WriteRoot, FIELD_ID, FIELD_JOURNAL, FIELD_NAME,
+  account_tag, check_version, check_versions, created_at, entry, history, journal, next_version,
+  ReadRoot, WriteRoot, FIELD_ID, FIELD_JOURNAL, FIELD_NAME,
 };
-use crate::error::{ErrorExistingEntity, ErrorNotFound};
+use crate::error::{ErrorConflict, ErrorExistingEntity, ErrorNotFound};
+use crate::undo::Inverse;
+use chrono::{DateTime, Utc};
 use itertools::Itertools;
 use sea_orm::sea_query::{BinOper, Expr, OnConflict};
 use sea_orm::{
@@ -59,6 +64,14 @@ pub struct Root {
   #[serde(rename = "type")]
   pub typ: Type,
   pub tags: HashSet<String>,
+  /// Increased on every save, for the commands to expect the one they have read
+  #[serde(default)]
+  pub version: i32,
+  /// Kept as it is on the saves, unless the root is new
+  #[serde(default)]
+  pub created_at: DateTime<Utc>,
+  #[serde(default)]
+  pub updated_at: DateTime<Utc>,
 }
 
 impl ReadRoot for Root {
@@ -107,6 +120,9 @@ impl WriteRoot for Root {
         typ: model.typ,
         journal_id: model.journal_id,
         tags: HashSet::default(),
+        version: model.version,
+        created_at: model.created_at,
+        updated_at: model.updated_at,
       });
       ids.insert(model.id);
     }
@@ -138,11 +154,12 @@ impl WriteRoot for Root {
       return Ok(roots);
     }
 
+    let now = Utc::now();
     let mut model_ids = HashSet::new();
     let mut models: Vec<ActiveModel> = vec![];
     let mut tags: Vec<account_tag::ActiveModel> = vec![];
 
-    for ref root in roots {
+    for root in &roots {
       model_ids.insert(root.id);
       models.push(
         Model {
@@ -152,6 +169,9 @@ impl WriteRoot for Root {
           unit: root.unit.to_string(),
           typ: root.typ,
           journal_id: root.journal_id,
+          version: root.version + 1,
+          created_at: created_at(root.created_at, now),
+          updated_at: now,
         }
         .into_active_model(),
       );
@@ -174,7 +194,21 @@ impl WriteRoot for Root {
       Column::Unit,
       Column::Typ,
       Column::JournalId,
+      Column::Version,
+      Column::UpdatedAt,
     ]);
+    on_conflict.action_and_where(next_version(Entity, Column::Version));
+
+    let currents: HashMap<Uuid, i32> = Entity::find()
+      .select_only()
+      .columns([Column::Id, Column::Version])
+      .filter(Column::Id.is_in(model_ids.clone()))
+      .into_tuple()
+      .all(db)
+      .await?
+      .into_iter()
+      .collect();
+    check_versions(TYPE, &roots, |root| (root.id, root.version), &currents)?;
 
     // Update unique column name to temp value
     Entity::update_many()
@@ -186,7 +220,14 @@ impl WriteRoot for Root {
       .exec(db)
       .await?;
 
-    Entity::insert_many(models).on_conflict(on_conflict).exec(db).await?;
+    let saved_count =
+      Entity::insert_many(models).on_conflict(on_conflict).exec_without_returning(db).await?;
+    if saved_count != roots.len() as u64 {
+      return Err(crate::Error::Conflict(ErrorConflict {
+        entity: TYPE.to_string(),
+        values: Vec::default(),
+      }));
+    }
 
     if !tags.is_empty() {
       account_tag::Entity::insert_many(tags).exec(db).await?;
@@ -205,11 +246,60 @@ impl WriteRoot for Root {
 }
 
 impl Root {
-  pub async fn handle(db: &impl ConnectionTrait, command: Command) -> crate::Result<Vec<Root>> {
-    match command {
+  /// Handle the command, and record the changes of the affected entities in the history on behalf
+  /// of the actor, who should be an editor of the journals
+  pub async fn handle(
+    db: &impl ConnectionTrait,
+    actor: &Actor,
+    command: Command,
+  ) -> crate::Result<Vec<Root>> {
+    Ok(Self::handle_with_inverse(db, actor, command).await?.0)
+  }
+
+  /// Handle the command the same as [`Root::handle`], and return the inverse to undo it as well
+  pub async fn handle_with_inverse(
+    db: &impl ConnectionTrait,
+    actor: &Actor,
+    command: Command,
+  ) -> crate::Result<(Vec<Root>, Inverse)> {
+    let ids = command.ids();
+    let befores = if ids.is_empty() {
+      Vec::default()
+    } else {
+      Self::find_all(db, Some(Query { id: ids, ..Default::default() }), None, None).await?
+    };
+    actor
+      .check_journals(
+        db,
+        befores.iter().map(|root| root.journal_id).chain(command.journal_ids()),
+        Role::Editor,
+      )
+      .await?;
+    let deleted_ids = match &command {
+      Command::Delete(command) => command.id.clone(),
+      Command::Batch(command) => command.delete.clone(),
+      _ => HashSet::default(),
+    };
+    let cascaded_entries = if deleted_ids.is_empty() {
+      Vec::default()
+    } else {
+      entry::Root::find_all(
+        db,
+        Some(entry::Query { account_id: deleted_ids, ..Default::default() }),
+        None,
+        None,
+      )
+      .await?
+    };
+
+    let roots = match command.clone() {
       Command::Create(command) => Self::create(db, vec![command]).await,
       Command::Update(command) => Self::update(db, vec![command]).await,
-      Command::Delete(CommandDelete { id }) => {
+      Command::Delete(CommandDelete { id, expected_version }) => {
+        for (entity_id, expected) in expected_version {
+          let current = befores.iter().find(|root| root.id == entity_id).map(|root| root.version);
+          check_version(TYPE, entity_id, Some(expected), current)?;
+        }
         Self::delete(db, id).await?;
         Ok(Vec::default())
       }
@@ -228,7 +318,55 @@ impl Root {
 
         Self::find_all(db, Some(Query { id: ids, ..Default::default() }), None, None).await
       }
-    }
+    }?;
+
+    let entry_ids: HashSet<_> = cascaded_entries.iter().map(|root| root.id).collect();
+    let cascaded_afters = if entry_ids.is_empty() {
+      Vec::default()
+    } else {
+      entry::Root::find_all(
+        db,
+        Some(entry::Query { id: entry_ids, ..Default::default() }),
+        None,
+        None,
+      )
+      .await?
+    };
+
+    // The entries losing the items of the deleted accounts are recorded as changed by the command
+    let command_id = Uuid::new_v4();
+    history::Root::record(
+      db,
+      command_id,
+      entry::TYPE,
+      actor,
+      &command,
+      cascaded_entries.iter().map(|root| (root.id, root)),
+      cascaded_afters.iter().map(|root| (root.id, root)),
+    )
+    .await?;
+    history::Root::record(
+      db,
+      command_id,
+      TYPE,
+      actor,
+      &command,
+      befores.iter().map(|root| (root.id, root)),
+      roots.iter().map(|root| (root.id, root)),
+    )
+    .await?;
+    let mut inverse = Inverse::default();
+    inverse.record(
+      entry::TYPE,
+      cascaded_entries.iter().map(|root| (root.id, root)),
+      cascaded_afters.iter().map(|root| (root.id, root)),
+    )?;
+    inverse.record(
+      TYPE,
+      befores.iter().map(|root| (root.id, root)),
+      roots.iter().map(|root| (root.id, root)),
+    )?;
+    Ok((roots, inverse))
   }
 
   pub async fn create(
@@ -374,6 +512,7 @@ impl Root {
           values: vec![(FIELD_ID.to_string(), command.id.to_string())],
         })
       })?;
+      check_version(TYPE, model.id, command.expected_version, Some(model.version))?;
 
       if command.name.is_empty()
         && command.description.is_none()
@@ -1,5 +1,6 @@
 use crate::entity::account::Type;
-use crate::entity::{account, account_tag};
+use crate::entity::{account, account_tag, JournalQuery};
+use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use sea_orm::sea_query::{Cond, Func, IntoCondition};
 use sea_orm::{Condition, QuerySelect, QueryTrait};
@@ -22,7 +23,18 @@ pub struct Query {
   #[serde(rename = "type")]
   pub typ: Option<Type>,
   #[serde(default)]
+  pub tags: HashSet<String>,
+  #[serde(default)]
   pub full_text: String,
+  /// The ones saved at or after the time
+  #[serde(default)]
+  pub updated_since: Option<DateTime<Utc>>,
+}
+
+impl JournalQuery for Query {
+  fn journal_ids(&mut self) -> &mut HashSet<Uuid> {
+    &mut self.journal_id
+  }
 }
 
 impl IntoCondition for Query {
@@ -56,6 +68,29 @@ impl IntoCondition for Query {
       cond = cond.add(account::Column::JournalId.is_in(self.journal_id));
     }
 
+    let tags = self
+      .tags
+      .iter()
+      .filter_map(|tag| match tag.trim() {
+        "" => None,
+        val => Some(val.to_string()),
+      })
+      .collect::<HashSet<_>>();
+    if !tags.is_empty() {
+      cond = cond.add(
+        account::Column::Id.in_subquery(
+          account_tag::Entity::find()
+            .select_only()
+            .distinct()
+            .column(account_tag::Column::AccountId)
+            .filter(
+              Expr::expr(Expr::col((account_tag::Entity, account_tag::Column::Tag))).is_in(tags),
+            )
+            .into_query(),
+        ),
+      );
+    }
+
     let keyword = self.full_text.trim().to_lowercase();
     if !keyword.is_empty() {
       let keyword = format!("%{}%", keyword);
@@ -85,6 +120,10 @@ impl IntoCondition for Query {
       cond = cond.add(sub_cond);
     }
 
+    if let Some(updated_since) = self.updated_since {
+      cond = cond.add(account::Column::UpdatedAt.gte(updated_since));
+    }
+
     cond
   }
 }
@@ -104,14 +143,17 @@ mod tests {
       unit: "Unit 1".to_string(),
       typ: Some(Type::Asset),
       journal_id: HashSet::from_iter([uuid!("50a1b556-b99d-4ae0-bfba-d117f9a958de")]),
+      tags: HashSet::from_iter(["Tag 1".to_string()]),
       full_text: "Keyword  ".to_string(),
+      updated_since: None,
     };
 
     assert_eq!(
-      [r#"SELECT "accounts"."id", "accounts"."journal_id", "accounts"."name", "accounts"."description", "accounts"."unit", "accounts"."type" FROM "accounts""#,
+      [r#"SELECT "accounts"."id", "accounts"."journal_id", "accounts"."name", "accounts"."description", "accounts"."unit", "accounts"."type", "accounts"."version", "accounts"."created_at", "accounts"."updated_at" FROM "accounts""#,
         r#"WHERE "accounts"."id" IN ('50a1b556-b99d-4ae0-bfba-d117f9a958de')"#,
         r#"AND "accounts"."name" IN ('Name 1') AND "accounts"."unit" = 'Unit 1'"#,
         r#"AND "accounts"."type" = 'A' AND "accounts"."journal_id" IN ('50a1b556-b99d-4ae0-bfba-d117f9a958de')"#,
+        r#"AND "accounts"."id" IN (SELECT DISTINCT "account_tags"."account_id" FROM "account_tags" WHERE "account_tags"."tag" IN ('Tag 1'))"#,
         r#"AND (LOWER("accounts"."name") LIKE '%keyword%' OR LOWER("accounts"."description") LIKE '%keyword%'"#,
         r#"OR "accounts"."id" IN (SELECT DISTINCT "account_tags"."account_id" FROM "account_tags" WHERE LOWER("account_tags"."tag") LIKE '%keyword%'))"#].join(" "),
       account::Entity::find().filter(query).build(DbBackend::Sqlite).to_string()
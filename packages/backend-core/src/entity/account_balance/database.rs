@@ -0,0 +1,36 @@
+use crate::entity::account;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sea_orm::entity::prelude::*;
+
+/// The cumulative balance of the account at the end of the date, only the dates with records are
+/// stored, the balance of other dates is the one of the latest date before them
+#[derive(Clone, Debug, Hash, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "account_balances")]
+pub struct Model {
+  #[sea_orm(primary_key, auto_increment = false)]
+  pub account_id: Uuid,
+  #[sea_orm(primary_key, auto_increment = false)]
+  pub date: NaiveDate,
+  pub amount: Decimal,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+  #[sea_orm(
+    belongs_to = "account::Entity",
+    from = "Column::AccountId",
+    to = "account::Column::Id",
+    on_update = "Cascade",
+    on_delete = "Cascade"
+  )]
+  Account,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Related<account::Entity> for Entity {
+  fn to() -> RelationDef {
+    Relation::Account.def()
+  }
+}
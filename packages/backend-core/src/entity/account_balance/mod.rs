@@ -0,0 +1,222 @@
+mod database;
+
+pub use database::*;
+
+use crate::entity::{account, entry, entry_item};
+use chrono::NaiveDate;
+use itertools::Itertools;
+use rust_decimal::Decimal;
+use sea_orm::sea_query::{Expr, Func, OnConflict, SimpleExpr};
+use sea_orm::{
+  ColumnTrait, Condition, ConnectionTrait, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder,
+  QuerySelect, QueryTrait,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use uuid::Uuid;
+
+const CHUNK_SIZE: usize = 1000;
+
+/// The changes of the balances caused by records, grouped by account and date
+pub(crate) type Deltas = HashMap<(Uuid, NaiveDate), Decimal>;
+
+/// The balances of the accounts at the end of the date, or of all time if `date` is `None`
+pub async fn find_balances(
+  db: &impl ConnectionTrait,
+  account_ids: HashSet<Uuid>,
+  date: Option<NaiveDate>,
+) -> crate::Result<HashMap<Uuid, Decimal>> {
+  if account_ids.is_empty() {
+    return Ok(HashMap::default());
+  }
+
+  let mut select = Entity::find()
+    .select_only()
+    .column(Column::AccountId)
+    .column_as(SimpleExpr::from(Func::max(Expr::col(Column::Date))), "date")
+    .filter(Column::AccountId.is_in(account_ids));
+  if let Some(date) = date {
+    select = select.filter(Column::Date.lte(date));
+  }
+  let latest = select.group_by(Column::AccountId).into_tuple::<(Uuid, NaiveDate)>().all(db).await?;
+  if latest.is_empty() {
+    return Ok(HashMap::default());
+  }
+
+  let cond = latest.into_iter().fold(Condition::any(), |cond, (account_id, date)| {
+    cond.add(Column::AccountId.eq(account_id).and(Column::Date.eq(date)))
+  });
+  Ok(
+    Entity::find()
+      .filter(cond)
+      .all(db)
+      .await?
+      .into_iter()
+      .map(|model| (model.account_id, model.amount))
+      .collect(),
+  )
+}
+
+/// Add the values of the records to the deltas, or subtract them if `negative`
+pub(crate) fn collect_deltas<'a>(
+  deltas: &mut Deltas,
+  entries: impl IntoIterator<Item = &'a entry::Root>,
+  negative: bool,
+) {
+  for entry in entries.into_iter().filter(|entry| entry.typ == entry::Type::Record) {
+    for item in &entry.items {
+      let value = item.amount * item.price;
+      *deltas.entry((item.account, entry.date)).or_default() +=
+        if negative { -value } else { value };
+    }
+  }
+}
+
+/// Apply the deltas to the stored balances, only the balances on or after the earliest date of the
+/// deltas are touched
+pub(crate) async fn apply(db: &impl ConnectionTrait, deltas: Deltas) -> crate::Result<()> {
+  let deltas: Deltas = deltas.into_iter().filter(|(_, delta)| !delta.is_zero()).collect();
+  let Some(start) = deltas.keys().map(|(_, date)| *date).min() else {
+    return Ok(());
+  };
+  let account_ids: HashSet<_> = deltas.keys().map(|(account_id, _)| *account_id).collect();
+
+  let openings = match start.pred_opt() {
+    Some(date) => find_balances(db, account_ids.clone(), Some(date)).await?,
+    None => HashMap::default(),
+  };
+
+  let existings = Entity::find()
+    .filter(Column::AccountId.is_in(account_ids))
+    .filter(Column::Date.gte(start))
+    .all(db)
+    .await?;
+
+  save(db, do_apply(&openings, existings, deltas)).await
+}
+
+/// Recalculate the balances of the journals from the records, for all journals if empty
+pub async fn rebuild(db: &impl ConnectionTrait, journal_ids: HashSet<Uuid>) -> crate::Result<()> {
+  let mut delete = Entity::delete_many();
+  if !journal_ids.is_empty() {
+    delete = delete.filter(
+      Column::AccountId.in_subquery(
+        account::Entity::find()
+          .select_only()
+          .column(account::Column::Id)
+          .filter(account::Column::JournalId.is_in(journal_ids.clone()))
+          .into_query(),
+      ),
+    );
+  }
+  delete.exec(db).await?;
+
+  // Summed up in SQLite like the hierarchy reports, the floating point error of the sums is rounded
+  // away when they are read into `Decimal`
+  let values = entry_item::Entity::find()
+    .select_only()
+    .column(entry_item::Column::AccountId)
+    .column(entry::Column::Date)
+    .column_as(
+      SimpleExpr::from(Func::sum(
+        Expr::col((entry_item::Entity, entry_item::Column::Amount))
+          .mul(Expr::col((entry_item::Entity, entry_item::Column::Price))),
+      )),
+      "value",
+    )
+    .inner_join(entry::Entity)
+    .filter(entry::Query {
+      journal_id: journal_ids,
+      typ: Some(entry::Type::Record),
+      ..Default::default()
+    })
+    .group_by(entry_item::Column::AccountId)
+    .group_by(entry::Column::Date)
+    .order_by_asc(entry_item::Column::AccountId)
+    .order_by_asc(entry::Column::Date)
+    .into_tuple::<(Uuid, NaiveDate, Option<Decimal>)>()
+    .all(db)
+    .await?;
+
+  let mut models = Vec::new();
+  for (account_id, values) in &values.into_iter().chunk_by(|(account_id, _, _)| *account_id) {
+    let mut amount = Decimal::ZERO;
+    for (_, date, value) in values {
+      amount += value.unwrap_or_default();
+      models.push(Model { account_id, date, amount });
+    }
+  }
+
+  save(db, models).await
+}
+
+fn do_apply(
+  openings: &HashMap<Uuid, Decimal>,
+  existings: Vec<Model>,
+  deltas: Deltas,
+) -> Vec<Model> {
+  // For each account and date: the stored balance if any, and the change on that date
+  let mut timelines = HashMap::<Uuid, BTreeMap<NaiveDate, (Option<Decimal>, Decimal)>>::new();
+  for model in existings {
+    timelines.entry(model.account_id).or_default().entry(model.date).or_default().0 =
+      Some(model.amount);
+  }
+  for ((account_id, date), delta) in deltas {
+    timelines.entry(account_id).or_default().entry(date).or_default().1 += delta;
+  }
+
+  let mut models = Vec::new();
+  for (account_id, timeline) in timelines {
+    let mut stored = openings.get(&account_id).copied().unwrap_or_default();
+    let mut changed = Decimal::ZERO;
+    for (date, (amount, delta)) in timeline {
+      if let Some(amount) = amount {
+        stored = amount;
+      }
+      changed += delta;
+      models.push(Model { account_id, date, amount: stored + changed });
+    }
+  }
+  models
+}
+
+async fn save(db: &impl ConnectionTrait, models: Vec<Model>) -> crate::Result<()> {
+  for chunk in models.chunks(CHUNK_SIZE) {
+    let mut on_conflict = OnConflict::columns([Column::AccountId, Column::Date]);
+    on_conflict.update_column(Column::Amount);
+    Entity::insert_many(chunk.iter().cloned().map(|model| model.into_active_model()))
+      .on_conflict(on_conflict)
+      .exec(db)
+      .await?;
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::entity::account_balance::{do_apply, Model};
+  use chrono::NaiveDate;
+  use rust_decimal_macros::dec;
+  use std::collections::HashMap;
+  use uuid::Uuid;
+
+  #[test]
+  fn test_do_apply() -> anyhow::Result<()> {
+    let account_id = Uuid::new_v4();
+    let day = |day| NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+    let existings = vec![
+      Model { account_id, date: day(3), amount: dec!(30) },
+      Model { account_id, date: day(5), amount: dec!(50) },
+    ];
+    let deltas =
+      HashMap::from_iter([((account_id, day(2)), dec!(5)), ((account_id, day(4)), dec!(-2))]);
+
+    let mut models = do_apply(&HashMap::from_iter([(account_id, dec!(10))]), existings, deltas);
+    models.sort_by_key(|model| model.date);
+    assert_eq!(
+      vec![(day(2), dec!(15)), (day(3), dec!(35)), (day(4), dec!(33)), (day(5), dec!(53))],
+      models.into_iter().map(|model| (model.date, model.amount)).collect::<Vec<_>>()
+    );
+
+    Ok(())
+  }
+}
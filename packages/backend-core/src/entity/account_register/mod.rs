@@ -0,0 +1,190 @@
+mod query;
+
+pub use query::*;
+
+use crate::entity::{account, entry, ReadRoot, FIELD_ID, REPORT_SPLITERATOR};
+use crate::error::{ErrorNotFound, ErrorRequiredField};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sea_orm::ConnectionTrait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+pub const TYPE: &str = "AccountRegister";
+pub const FIELD_ACCOUNT_ID: &str = "accountId";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Root {
+  pub account_id: Uuid,
+  pub entry_id: Uuid,
+  pub date: NaiveDate,
+  pub name: String,
+  pub description: String,
+  pub counter_accounts: Vec<Uuid>,
+  pub amount: Decimal,
+  pub price: Decimal,
+  /// The running balance of the account after this line
+  pub balance: Decimal,
+}
+
+impl ReadRoot for Root {
+  type Query = Query;
+  type Sort = ();
+
+  fn id(&self) -> String {
+    [self.entry_id.to_string(), self.account_id.to_string()].join(REPORT_SPLITERATOR)
+  }
+
+  async fn find_all(
+    db: &impl ConnectionTrait,
+    query: Option<Query>,
+    limit: Option<u64>,
+    _sort: Option<()>,
+  ) -> crate::Result<Vec<Root>> {
+    let query = query.unwrap_or_default();
+    let account_id = query.account_id.ok_or_else(|| {
+      crate::Error::RequiredField(ErrorRequiredField {
+        entity: TYPE.to_string(),
+        field: FIELD_ACCOUNT_ID.to_string(),
+      })
+    })?;
+
+    let account = account::Root::find_one(
+      db,
+      Some(account::Query { id: HashSet::from_iter([account_id]), ..Default::default() }),
+    )
+    .await?
+    .ok_or_else(|| {
+      crate::Error::NotFound(ErrorNotFound {
+        entity: account::TYPE.to_string(),
+        values: vec![(FIELD_ID.to_string(), account_id.to_string())],
+      })
+    })?;
+
+    // Entries before `start` are loaded as well, to get the opening balance
+    let entries = entry::Root::find_all(
+      db,
+      Some(entry::Query {
+        journal_id: HashSet::from_iter([account.journal_id]),
+        account_id: HashSet::from_iter([account.id]),
+        end: query.end,
+        typ: Some(entry::Type::Record),
+        ..Default::default()
+      }),
+      None,
+      None,
+    )
+    .await?;
+
+    Ok(Self::do_register(account.id, entries, query.start, query.offset, limit))
+  }
+}
+
+impl Root {
+  fn do_register(
+    account_id: Uuid,
+    mut entries: Vec<entry::Root>,
+    start: Option<NaiveDate>,
+    offset: u64,
+    limit: Option<u64>,
+  ) -> Vec<Root> {
+    entries.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.id.cmp(&b.id)));
+
+    let mut balance = Decimal::ZERO;
+    let mut results = Vec::new();
+    for entry in entries {
+      let Some(item) = entry.items.iter().find(|item| item.account == account_id) else {
+        continue;
+      };
+      balance += item.amount * item.price;
+
+      if start.is_some_and(|start| entry.date < start) {
+        continue;
+      }
+
+      let mut counter_accounts: Vec<_> = entry
+        .items
+        .iter()
+        .filter(|item| item.account != account_id)
+        .map(|item| item.account)
+        .collect();
+      counter_accounts.sort();
+
+      results.push(Root {
+        account_id,
+        entry_id: entry.id,
+        date: entry.date,
+        name: entry.name,
+        description: entry.description,
+        counter_accounts,
+        amount: item.amount,
+        price: item.price,
+        balance,
+      });
+    }
+
+    results
+      .into_iter()
+      .skip(offset as usize)
+      .take(limit.map(|limit| limit as usize).unwrap_or(usize::MAX))
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::entity::account_register::Root;
+  use crate::entity::entry;
+  use chrono::NaiveDate;
+  use rust_decimal_macros::dec;
+  use std::collections::HashSet;
+  use uuid::Uuid;
+
+  #[test]
+  fn test_do_register() -> anyhow::Result<()> {
+    let journal_id = Uuid::new_v4();
+    let account_id = Uuid::new_v4();
+    let counter_id = Uuid::new_v4();
+    let new_entry = |day: u32, amount| entry::Root {
+      id: Uuid::new_v4(),
+      journal_id,
+      name: format!("Entry: {}", day),
+      description: "".to_string(),
+      typ: entry::Type::Record,
+      date: NaiveDate::from_ymd_opt(2024, 1, day).unwrap(),
+      tags: HashSet::default(),
+      items: vec![
+        entry::Item { account: account_id, amount, price: dec!(1) },
+        entry::Item { account: counter_id, amount, price: dec!(1) },
+      ],
+      payee_id: None,
+      version: 0,
+      created_at: Default::default(),
+      updated_at: Default::default(),
+    };
+    let entries = vec![
+      new_entry(4, dec!(40)),
+      new_entry(1, dec!(10)),
+      new_entry(3, dec!(30)),
+      new_entry(2, dec!(20)),
+    ];
+
+    let lines =
+      Root::do_register(account_id, entries.clone(), NaiveDate::from_ymd_opt(2024, 1, 2), 0, None);
+    assert_eq!(
+      vec![dec!(30), dec!(60), dec!(100)],
+      lines.iter().map(|line| line.balance).collect::<Vec<_>>()
+    );
+    assert_eq!(vec![counter_id], lines[0].counter_accounts);
+
+    let lines =
+      Root::do_register(account_id, entries, NaiveDate::from_ymd_opt(2024, 1, 2), 1, Some(1));
+    assert_eq!(1, lines.len());
+    assert_eq!("Entry: 3", lines[0].name);
+    assert_eq!(dec!(60), lines[0].balance);
+
+    Ok(())
+  }
+}
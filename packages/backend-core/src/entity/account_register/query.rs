@@ -0,0 +1,17 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Query {
+  #[serde(default)]
+  pub account_id: Option<Uuid>,
+  #[serde(default)]
+  pub start: Option<NaiveDate>,
+  #[serde(default)]
+  pub end: Option<NaiveDate>,
+  /// How many lines within the date range to skip, for pagination
+  #[serde(default)]
+  pub offset: u64,
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "commandType")]
+pub enum Command {
+  #[serde(rename = "apiTokens:delete")]
+  Delete(CommandDelete),
+}
+
+/// Issue a new token for the user, such as for a script or another device
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandIssue {
+  pub user_id: Uuid,
+  pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommandDelete {
+  #[serde(default)]
+  pub id: HashSet<Uuid>,
+}
@@ -0,0 +1,36 @@
+use crate::entity::user;
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "api_tokens")]
+pub struct Model {
+  #[sea_orm(primary_key, auto_increment = false)]
+  pub id: Uuid,
+  #[sea_orm(indexed)]
+  pub user_id: Uuid,
+  pub name: String,
+  /// The SHA-256 of the token in hex, since the token itself is only shown once when issued
+  #[sea_orm(unique, indexed)]
+  pub hash: String,
+  pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+  #[sea_orm(
+    belongs_to = "user::Entity",
+    from = "Column::UserId",
+    to = "user::Column::Id",
+    on_update = "Cascade",
+    on_delete = "Cascade"
+  )]
+  User,
+}
+
+impl Related<user::Entity> for Entity {
+  fn to() -> RelationDef {
+    Relation::User.def()
+  }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,159 @@
+mod command;
+mod database;
+mod query;
+
+pub use command::*;
+pub use database::*;
+pub use query::*;
+
+use crate::actor::Actor;
+use crate::entity::{user, ReadRoot, FIELD_ID, FIELD_NAME, MAX_NAME_LENGTH, MIN_SHORT_TEXT_LENGTH};
+use crate::error::{ErrorNotFound, ErrorOutOfRange};
+use chrono::{DateTime, Utc};
+use sea_orm::{
+  ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder, QuerySelect,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+pub const TYPE: &str = "ApiToken";
+pub const TOKEN_PREFIX: &str = "wr_";
+
+/// A long-lived secret for a user to authenticate with, kept as a hash only
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Root {
+  pub id: Uuid,
+  pub user_id: Uuid,
+  pub name: String,
+  pub created_at: DateTime<Utc>,
+}
+
+impl From<Model> for Root {
+  fn from(value: Model) -> Self {
+    Root { id: value.id, user_id: value.user_id, name: value.name, created_at: value.created_at }
+  }
+}
+
+/// The token newly issued, which cannot be found again after returned
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Issued {
+  pub root: Root,
+  pub token: String,
+}
+
+impl ReadRoot for Root {
+  type Query = Query;
+  type Sort = ();
+
+  fn id(&self) -> String {
+    self.id.to_string()
+  }
+
+  async fn find_all(
+    db: &impl ConnectionTrait,
+    query: Option<Query>,
+    limit: Option<u64>,
+    _sort: Option<()>,
+  ) -> crate::Result<Vec<Root>> {
+    let select =
+      if let Some(query) = query { Entity::find().filter(query) } else { Entity::find() };
+    let models = select.order_by_asc(Column::CreatedAt).limit(limit).all(db).await?;
+    Ok(models.into_iter().map(Root::from).collect())
+  }
+}
+
+impl Root {
+  /// Issue a token for the user, which the user and the system actors are allowed to only
+  pub async fn issue(
+    db: &impl ConnectionTrait,
+    actor: &Actor,
+    command: CommandIssue,
+  ) -> crate::Result<Issued> {
+    actor.check_user(command.user_id, TYPE)?;
+    if user::Root::find_one(
+      db,
+      Some(user::Query { id: HashSet::from_iter([command.user_id]), ..Default::default() }),
+    )
+    .await?
+    .is_none()
+    {
+      return Err(crate::Error::NotFound(ErrorNotFound {
+        entity: user::TYPE.to_string(),
+        values: vec![(FIELD_ID.to_string(), command.user_id.to_string())],
+      }));
+    }
+
+    // Both of the UUIDs are generated by the secure random generator, for 244 random bits in all
+    let token = format!("{}{}{}", TOKEN_PREFIX, Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    let model = Model {
+      id: Uuid::new_v4(),
+      user_id: command.user_id,
+      name: normalize_name(command.name)?,
+      hash: hash(&token),
+      created_at: Utc::now(),
+    };
+    Entity::insert(model.clone().into_active_model()).exec_without_returning(db).await?;
+    Ok(Issued { root: model.into(), token })
+  }
+
+  /// Handle the command, which the owners of the tokens and the system actors are allowed to only
+  pub async fn handle(
+    db: &impl ConnectionTrait,
+    actor: &Actor,
+    command: Command,
+  ) -> crate::Result<Vec<Root>> {
+    match command {
+      Command::Delete(CommandDelete { id }) => {
+        let roots =
+          Self::find_all(db, Some(Query { id: id.clone(), ..Default::default() }), None, None)
+            .await?;
+        for root in &roots {
+          actor.check_user(root.user_id, TYPE)?;
+        }
+        Entity::delete_many().filter(Column::Id.is_in(id)).exec(db).await?;
+        Ok(Vec::default())
+      }
+    }
+  }
+
+  /// Find the token matching the secret, for the servers to authenticate the users
+  pub async fn authenticate(db: &impl ConnectionTrait, token: &str) -> crate::Result<Option<Root>> {
+    if !token.starts_with(TOKEN_PREFIX) {
+      return Ok(None);
+    }
+
+    let model = Entity::find().filter(Column::Hash.eq(hash(token))).one(db).await?;
+    Ok(model.map(Root::from))
+  }
+}
+
+fn hash(token: &str) -> String {
+  format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+fn normalize_name(value: String) -> crate::Result<String> {
+  let value = value.trim().to_string();
+  if value.len() < MIN_SHORT_TEXT_LENGTH || value.len() > MAX_NAME_LENGTH {
+    Err(crate::Error::OutOfRange(ErrorOutOfRange {
+      entity: TYPE.to_string(),
+      field: FIELD_NAME.to_string(),
+      start: Some(MIN_SHORT_TEXT_LENGTH.to_string()),
+      end: Some(MAX_NAME_LENGTH.to_string()),
+    }))
+  } else {
+    Ok(value)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::entity::api_token::hash;
+
+  #[test]
+  fn test_hash() {
+    assert_eq!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad", hash("abc"));
+  }
+}
@@ -0,0 +1,32 @@
+use crate::entity::api_token;
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Cond, IntoCondition};
+use sea_orm::Condition;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Query {
+  #[serde(default)]
+  pub id: HashSet<Uuid>,
+  #[serde(default)]
+  pub user_id: HashSet<Uuid>,
+}
+
+impl IntoCondition for Query {
+  fn into_condition(self) -> Condition {
+    let mut cond = Cond::all();
+
+    if !self.id.is_empty() {
+      cond = cond.add(api_token::Column::Id.is_in(self.id));
+    }
+
+    if !self.user_id.is_empty() {
+      cond = cond.add(api_token::Column::UserId.is_in(self.user_id));
+    }
+
+    cond
+  }
+}
@@ -0,0 +1,237 @@
+mod query;
+
+pub use query::*;
+
+use crate::entity::{account, entry, journal, ReadRoot, REPORT_SPLITERATOR};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sea_orm::ConnectionTrait;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Root {
+  pub journal_id: Uuid,
+  pub date: Option<NaiveDate>,
+  pub unit: String,
+  pub assets: HashMap<Uuid, Decimal>,
+  pub liabilities: HashMap<Uuid, Decimal>,
+  pub equity: HashMap<Uuid, Decimal>,
+  pub total_assets: Decimal,
+  pub total_liabilities: Decimal,
+  pub total_equity: Decimal,
+  /// Income minus expense up to `date`, which is not closed into any equity account yet
+  pub current_earnings: Decimal,
+  /// If `Assets = Liabilities + Equity + Current Earnings`
+  pub balanced: bool,
+}
+
+impl ReadRoot for Root {
+  type Query = Query;
+  type Sort = ();
+
+  fn id(&self) -> String {
+    [self.journal_id.to_string(), self.date.map(|date| date.to_string()).unwrap_or_default()]
+      .join(REPORT_SPLITERATOR)
+  }
+
+  async fn find_all(
+    db: &impl ConnectionTrait,
+    query: Option<Query>,
+    limit: Option<u64>,
+    _sort: Option<()>,
+  ) -> crate::Result<Vec<Root>> {
+    let query = query.unwrap_or_default();
+
+    let journals = journal::Root::find_all(
+      db,
+      Some(journal::Query { id: query.journal_id.clone(), ..Default::default() }),
+      limit,
+      None,
+    )
+    .await?;
+    let journal_ids: HashSet<_> = journals.iter().map(|journal| journal.id).collect();
+
+    let entries = entry::Root::find_all(
+      db,
+      Some(entry::Query {
+        journal_id: journal_ids.clone(),
+        end: query.date,
+        typ: Some(entry::Type::Record),
+        ..Default::default()
+      }),
+      None,
+      None,
+    )
+    .await?;
+
+    let accounts = account::Root::find_all(
+      db,
+      Some(account::Query { journal_id: journal_ids, ..Default::default() }),
+      None,
+      None,
+    )
+    .await?;
+
+    Ok(
+      journals
+        .iter()
+        .map(|journal| Self::do_aggregate(journal, query.date, &entries, &accounts))
+        .collect(),
+    )
+  }
+}
+
+impl Root {
+  fn do_aggregate(
+    journal: &journal::Root,
+    date: Option<NaiveDate>,
+    entries: &[entry::Root],
+    accounts: &[account::Root],
+  ) -> Root {
+    let accounts = accounts
+      .iter()
+      .filter(|account| account.journal_id == journal.id)
+      .map(|account| (account.id, account))
+      .collect::<HashMap<_, _>>();
+
+    let mut assets = HashMap::<Uuid, Decimal>::new();
+    let mut liabilities = HashMap::<Uuid, Decimal>::new();
+    let mut equity = HashMap::<Uuid, Decimal>::new();
+    let mut current_earnings = Decimal::ZERO;
+
+    for entry in entries.iter().filter(|entry| entry.journal_id == journal.id) {
+      for item in &entry.items {
+        let value = item.amount * item.price;
+        let values = match accounts.get(&item.account).map(|account| account.typ) {
+          Some(account::Type::Asset) => &mut assets,
+          Some(account::Type::Liability) => &mut liabilities,
+          Some(account::Type::Equity) => &mut equity,
+          Some(account::Type::Income) => {
+            current_earnings += value;
+            continue;
+          }
+          Some(account::Type::Expense) => {
+            current_earnings -= value;
+            continue;
+          }
+          None => continue,
+        };
+        *values.entry(item.account).or_default() += value;
+      }
+    }
+
+    let total_assets: Decimal = assets.values().sum();
+    let total_liabilities: Decimal = liabilities.values().sum();
+    let total_equity: Decimal = equity.values().sum();
+
+    Root {
+      journal_id: journal.id,
+      date,
+      unit: journal.unit.clone(),
+      assets,
+      liabilities,
+      equity,
+      total_assets,
+      total_liabilities,
+      total_equity,
+      current_earnings,
+      balanced: total_assets == total_liabilities + total_equity + current_earnings,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::entity::balance_sheet::Root;
+  use crate::entity::{account, entry, journal};
+  use chrono::NaiveDate;
+  use rust_decimal_macros::dec;
+  use sea_orm::Iterable;
+  use std::collections::HashSet;
+  use uuid::Uuid;
+
+  #[test]
+  fn test_do_aggregate() -> anyhow::Result<()> {
+    let journal = journal::Root {
+      id: Uuid::new_v4(),
+      name: "Journal 1".to_string(),
+      description: "".to_string(),
+      unit: "CNY".to_string(),
+      tags: HashSet::default(),
+      version: 0,
+      created_at: Default::default(),
+      updated_at: Default::default(),
+    };
+    let accounts: Vec<_> = account::Type::iter()
+      .map(|typ| account::Root {
+        id: Uuid::new_v4(),
+        journal_id: journal.id,
+        name: format!("Account::{}", typ),
+        description: "".to_string(),
+        unit: "CNY".to_string(),
+        typ,
+        tags: HashSet::default(),
+        version: 0,
+        created_at: Default::default(),
+        updated_at: Default::default(),
+      })
+      .collect();
+    let find = |typ: account::Type| accounts.iter().find(|a| a.typ == typ).unwrap().id;
+
+    let entries = vec![
+      entry::Root {
+        id: Uuid::new_v4(),
+        journal_id: journal.id,
+        name: "Entry: 1".to_string(),
+        description: "".to_string(),
+        typ: entry::Type::Record,
+        date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+        tags: HashSet::default(),
+        items: vec![
+          entry::Item { account: find(account::Type::Asset), amount: dec!(500.0), price: dec!(1) },
+          entry::Item { account: find(account::Type::Equity), amount: dec!(300.0), price: dec!(1) },
+          entry::Item {
+            account: find(account::Type::Liability),
+            amount: dec!(200.0),
+            price: dec!(1),
+          },
+        ],
+        payee_id: None,
+        version: 0,
+        created_at: Default::default(),
+        updated_at: Default::default(),
+      },
+      entry::Root {
+        id: Uuid::new_v4(),
+        journal_id: journal.id,
+        name: "Entry: 2".to_string(),
+        description: "".to_string(),
+        typ: entry::Type::Record,
+        date: NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(),
+        tags: HashSet::default(),
+        items: vec![
+          entry::Item { account: find(account::Type::Asset), amount: dec!(80.0), price: dec!(1) },
+          entry::Item { account: find(account::Type::Expense), amount: dec!(20.0), price: dec!(1) },
+          entry::Item { account: find(account::Type::Income), amount: dec!(100.0), price: dec!(1) },
+        ],
+        payee_id: None,
+        version: 0,
+        created_at: Default::default(),
+        updated_at: Default::default(),
+      },
+    ];
+
+    let root = Root::do_aggregate(&journal, None, &entries, &accounts);
+
+    assert_eq!(dec!(580.0), root.total_assets);
+    assert_eq!(dec!(200.0), root.total_liabilities);
+    assert_eq!(dec!(300.0), root.total_equity);
+    assert_eq!(dec!(80.0), root.current_earnings);
+    assert!(root.balanced);
+
+    Ok(())
+  }
+}
@@ -0,0 +1,290 @@
+mod query;
+
+pub use query::*;
+
+use crate::entity::{account, entry, journal, ReadRoot, REPORT_SPLITERATOR};
+use crate::error::ErrorRequiredField;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sea_orm::ConnectionTrait;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+pub const TYPE: &str = "CashFlowStatement";
+pub const FIELD_CASH_ACCOUNT_ID: &str = "cashAccountId";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Root {
+  pub journal_id: Uuid,
+  pub start: Option<NaiveDate>,
+  pub end: Option<NaiveDate>,
+  pub unit: String,
+  pub cash_accounts: HashSet<Uuid>,
+  pub opening_balance: Decimal,
+  /// Cash flows against Income and Expense accounts
+  pub operating: Decimal,
+  /// Cash flows against non-cash Asset accounts
+  pub investing: Decimal,
+  /// Cash flows against Liability and Equity accounts
+  pub financing: Decimal,
+  pub closing_balance: Decimal,
+}
+
+impl ReadRoot for Root {
+  type Query = Query;
+  type Sort = ();
+
+  fn id(&self) -> String {
+    [
+      self.journal_id.to_string(),
+      self.start.map(|date| date.to_string()).unwrap_or_default(),
+      self.end.map(|date| date.to_string()).unwrap_or_default(),
+    ]
+    .join(REPORT_SPLITERATOR)
+  }
+
+  async fn find_all(
+    db: &impl ConnectionTrait,
+    query: Option<Query>,
+    limit: Option<u64>,
+    _sort: Option<()>,
+  ) -> crate::Result<Vec<Root>> {
+    let query = query.unwrap_or_default();
+    if query.cash_account_id.is_empty() {
+      return Err(crate::Error::RequiredField(ErrorRequiredField {
+        entity: TYPE.to_string(),
+        field: FIELD_CASH_ACCOUNT_ID.to_string(),
+      }));
+    }
+
+    let accounts = account::Root::find_all(
+      db,
+      Some(account::Query { journal_id: query.journal_id.clone(), ..Default::default() }),
+      None,
+      None,
+    )
+    .await?;
+
+    let journal_ids: HashSet<_> = accounts
+      .iter()
+      .filter(|account| query.cash_account_id.contains(&account.id))
+      .map(|account| account.journal_id)
+      .collect();
+    if journal_ids.is_empty() {
+      return Ok(vec![]);
+    }
+
+    let journals = journal::Root::find_all(
+      db,
+      Some(journal::Query { id: journal_ids.clone(), ..Default::default() }),
+      limit,
+      None,
+    )
+    .await?;
+
+    let entries = entry::Root::find_all(
+      db,
+      Some(entry::Query {
+        journal_id: journal_ids,
+        account_id: query.cash_account_id.clone(),
+        end: query.end,
+        typ: Some(entry::Type::Record),
+        ..Default::default()
+      }),
+      None,
+      None,
+    )
+    .await?;
+
+    Ok(
+      journals
+        .iter()
+        .map(|journal| {
+          Self::do_aggregate(
+            journal,
+            query.start,
+            query.end,
+            &query.cash_account_id,
+            &entries,
+            &accounts,
+          )
+        })
+        .collect(),
+    )
+  }
+}
+
+impl Root {
+  fn do_aggregate(
+    journal: &journal::Root,
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+    cash_account_ids: &HashSet<Uuid>,
+    entries: &[entry::Root],
+    accounts: &[account::Root],
+  ) -> Root {
+    let accounts = accounts
+      .iter()
+      .filter(|account| account.journal_id == journal.id)
+      .map(|account| (account.id, account))
+      .collect::<HashMap<_, _>>();
+    let cash_accounts: HashSet<_> =
+      cash_account_ids.iter().filter(|id| accounts.contains_key(id)).copied().collect();
+
+    let mut opening_balance = Decimal::ZERO;
+    let mut operating = Decimal::ZERO;
+    let mut investing = Decimal::ZERO;
+    let mut financing = Decimal::ZERO;
+    let mut closing_balance = Decimal::ZERO;
+
+    for entry in entries.iter().filter(|entry| entry.journal_id == journal.id) {
+      let (cash_items, counter_items): (Vec<&entry::Item>, Vec<_>) =
+        entry.items.iter().partition(|item| cash_accounts.contains(&item.account));
+      let cash_change: Decimal = cash_items.iter().map(|item| item.amount * item.price).sum();
+
+      closing_balance += cash_change;
+      if start.is_some_and(|start| entry.date < start) {
+        opening_balance += cash_change;
+        continue;
+      }
+
+      // Allocate the cash change to each activity based on the share of counter accounts
+      let counter_total: Decimal = counter_items.iter().map(|item| item.amount * item.price).sum();
+      if counter_total.is_zero() {
+        continue;
+      }
+
+      for item in counter_items {
+        let allocated = cash_change * item.amount * item.price / counter_total;
+        match accounts.get(&item.account).map(|account| account.typ) {
+          Some(account::Type::Income | account::Type::Expense) => operating += allocated,
+          Some(account::Type::Asset) => investing += allocated,
+          Some(account::Type::Liability | account::Type::Equity) => financing += allocated,
+          None => {}
+        }
+      }
+    }
+
+    Root {
+      journal_id: journal.id,
+      start,
+      end,
+      unit: journal.unit.clone(),
+      cash_accounts,
+      opening_balance,
+      operating,
+      investing,
+      financing,
+      closing_balance,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::entity::cash_flow_statement::Root;
+  use crate::entity::{account, entry, journal};
+  use chrono::NaiveDate;
+  use rust_decimal_macros::dec;
+  use sea_orm::Iterable;
+  use std::collections::HashSet;
+  use uuid::Uuid;
+
+  #[test]
+  fn test_do_aggregate() -> anyhow::Result<()> {
+    let journal = journal::Root {
+      id: Uuid::new_v4(),
+      name: "Journal 1".to_string(),
+      description: "".to_string(),
+      unit: "CNY".to_string(),
+      tags: HashSet::default(),
+      version: 0,
+      created_at: Default::default(),
+      updated_at: Default::default(),
+    };
+    let mut accounts: Vec<_> = account::Type::iter()
+      .map(|typ| account::Root {
+        id: Uuid::new_v4(),
+        journal_id: journal.id,
+        name: format!("Account::{}", typ),
+        description: "".to_string(),
+        unit: "CNY".to_string(),
+        typ,
+        tags: HashSet::default(),
+        version: 0,
+        created_at: Default::default(),
+        updated_at: Default::default(),
+      })
+      .collect();
+    let cash = Uuid::new_v4();
+    accounts.push(account::Root {
+      id: cash,
+      journal_id: journal.id,
+      name: "Account::Cash".to_string(),
+      description: "".to_string(),
+      unit: "CNY".to_string(),
+      typ: account::Type::Asset,
+      tags: HashSet::default(),
+      version: 0,
+      created_at: Default::default(),
+      updated_at: Default::default(),
+    });
+    let find = |typ: account::Type| accounts.iter().find(|a| a.typ == typ).unwrap().id;
+
+    let new_entry = |date: NaiveDate, items: Vec<entry::Item>| entry::Root {
+      id: Uuid::new_v4(),
+      journal_id: journal.id,
+      name: format!("Entry: {}", date),
+      description: "".to_string(),
+      typ: entry::Type::Record,
+      date,
+      tags: HashSet::default(),
+      items,
+      payee_id: None,
+      version: 0,
+      created_at: Default::default(),
+      updated_at: Default::default(),
+    };
+
+    let entries = vec![
+      new_entry(
+        NaiveDate::from_ymd_opt(2023, 12, 1).unwrap(),
+        vec![
+          entry::Item { account: cash, amount: dec!(50.0), price: dec!(1) },
+          entry::Item { account: find(account::Type::Equity), amount: dec!(50.0), price: dec!(1) },
+        ],
+      ),
+      new_entry(
+        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+        vec![
+          entry::Item { account: cash, amount: dec!(100.0), price: dec!(1) },
+          entry::Item { account: find(account::Type::Income), amount: dec!(60.0), price: dec!(1) },
+          entry::Item {
+            account: find(account::Type::Liability),
+            amount: dec!(40.0),
+            price: dec!(1),
+          },
+        ],
+      ),
+    ];
+
+    let root = Root::do_aggregate(
+      &journal,
+      NaiveDate::from_ymd_opt(2024, 1, 1),
+      None,
+      &HashSet::from_iter([cash]),
+      &entries,
+      &accounts,
+    );
+
+    assert_eq!(dec!(50.0), root.opening_balance);
+    assert_eq!(dec!(60.0), root.operating);
+    assert_eq!(dec!(0), root.investing);
+    assert_eq!(dec!(40.0), root.financing);
+    assert_eq!(dec!(150.0), root.closing_balance);
+
+    Ok(())
+  }
+}
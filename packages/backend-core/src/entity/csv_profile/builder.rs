@@ -0,0 +1,103 @@
+use crate::entity::csv_profile::{
+  Mapping, Root, FIELD_AMOUNT_COLUMN, FIELD_DATE_COLUMN, FIELD_DELIMITER, TYPE,
+};
+use crate::entity::{account, normalize_name, FIELD_ID, FIELD_JOURNAL};
+use crate::error::{ErrorNotFound, ErrorOutOfRange, ErrorRequiredField};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[derive(Debug, Default)]
+pub struct Builder {
+  id: Option<Uuid>,
+  journal_id: Option<Uuid>,
+  name: String,
+  mapping: Option<Mapping>,
+}
+
+impl From<Root> for Builder {
+  fn from(value: Root) -> Self {
+    Builder {
+      id: Some(value.id),
+      journal_id: Some(value.journal_id),
+      name: value.name,
+      mapping: Some(value.mapping),
+    }
+  }
+}
+
+impl Builder {
+  pub fn build(self, accounts: &HashMap<Uuid, account::Root>) -> crate::Result<Root> {
+    let required = |field: &str| {
+      crate::Error::RequiredField(ErrorRequiredField {
+        entity: TYPE.to_string(),
+        field: field.to_string(),
+      })
+    };
+
+    let name = normalize_name(TYPE, self.name)?;
+    let journal_id = self.journal_id.ok_or_else(|| required(FIELD_JOURNAL))?;
+    let mut mapping = self.mapping.ok_or_else(|| required(FIELD_DATE_COLUMN))?;
+
+    if !mapping.delimiter.is_ascii() {
+      return Err(crate::Error::OutOfRange(ErrorOutOfRange {
+        entity: TYPE.to_string(),
+        field: FIELD_DELIMITER.to_string(),
+        start: Some(char::MIN.escape_unicode().to_string()),
+        end: Some(char::from(0x7f).escape_unicode().to_string()),
+      }));
+    }
+
+    mapping.date_column = mapping.date_column.trim().to_string();
+    if mapping.date_column.is_empty() {
+      return Err(required(FIELD_DATE_COLUMN));
+    }
+    mapping.date_format = mapping.date_format.trim().to_string();
+    if mapping.date_format.is_empty() {
+      mapping.date_format = Mapping::DEFAULT_DATE_FORMAT.to_string();
+    }
+
+    let normalize = |column: Option<String>| {
+      column.map(|column| column.trim().to_string()).filter(|column| !column.is_empty())
+    };
+    mapping.amount_column = normalize(mapping.amount_column);
+    mapping.debit_column = normalize(mapping.debit_column);
+    mapping.credit_column = normalize(mapping.credit_column);
+    mapping.description_column = normalize(mapping.description_column);
+    if mapping.amount_column.is_none()
+      && mapping.debit_column.is_none()
+      && mapping.credit_column.is_none()
+    {
+      return Err(required(FIELD_AMOUNT_COLUMN));
+    }
+
+    for account_id in [mapping.account_id, mapping.counter_account_id] {
+      if accounts.get(&account_id).is_none_or(|account| account.journal_id != journal_id) {
+        return Err(crate::Error::NotFound(ErrorNotFound {
+          entity: account::TYPE.to_string(),
+          values: vec![
+            (FIELD_JOURNAL.to_string(), journal_id.to_string()),
+            (FIELD_ID.to_string(), account_id.to_string()),
+          ],
+        }));
+      }
+    }
+
+    Ok(Root { id: self.id.unwrap_or_else(Uuid::new_v4), journal_id, name, mapping })
+  }
+
+  pub fn id(self, id: Uuid) -> Builder {
+    Builder { id: Some(id), ..self }
+  }
+
+  pub fn journal_id(self, journal_id: Uuid) -> Builder {
+    Builder { journal_id: Some(journal_id), ..self }
+  }
+
+  pub fn name(self, name: impl ToString) -> Builder {
+    Builder { name: name.to_string(), ..self }
+  }
+
+  pub fn mapping(self, mapping: Mapping) -> Builder {
+    Builder { mapping: Some(mapping), ..self }
+  }
+}
@@ -0,0 +1,40 @@
+use crate::entity::csv_profile::Mapping;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "commandType")]
+pub enum Command {
+  #[serde(rename = "csvProfiles:create")]
+  Create(CommandCreate),
+  #[serde(rename = "csvProfiles:update")]
+  Update(CommandUpdate),
+  #[serde(rename = "csvProfiles:delete")]
+  Delete(CommandDelete),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandCreate {
+  pub journal_id: Uuid,
+  pub name: String,
+  #[serde(flatten)]
+  pub mapping: Mapping,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommandUpdate {
+  pub id: Uuid,
+  #[serde(default)]
+  pub name: String,
+  /// The whole mapping is replaced if present
+  #[serde(default)]
+  pub mapping: Option<Mapping>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommandDelete {
+  #[serde(default)]
+  pub id: HashSet<Uuid>,
+}
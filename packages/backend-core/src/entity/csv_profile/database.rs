@@ -0,0 +1,59 @@
+use crate::entity::{account, journal};
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "csv_profiles")]
+pub struct Model {
+  #[sea_orm(primary_key, auto_increment = false)]
+  pub id: Uuid,
+  #[sea_orm(indexed)]
+  pub journal_id: Uuid,
+  #[sea_orm(indexed)]
+  pub name: String,
+  pub delimiter: String,
+  pub date_column: String,
+  pub date_format: String,
+  pub amount_column: Option<String>,
+  pub debit_column: Option<String>,
+  pub credit_column: Option<String>,
+  pub description_column: Option<String>,
+  pub decimal_separator: String,
+  pub account_id: Uuid,
+  pub counter_account_id: Uuid,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+  #[sea_orm(
+    belongs_to = "journal::Entity",
+    from = "Column::JournalId",
+    to = "journal::Column::Id",
+    on_update = "Cascade",
+    on_delete = "Cascade"
+  )]
+  Journal,
+  #[sea_orm(
+    belongs_to = "account::Entity",
+    from = "Column::AccountId",
+    to = "account::Column::Id",
+    on_update = "Cascade",
+    on_delete = "Cascade"
+  )]
+  Account,
+  #[sea_orm(
+    belongs_to = "account::Entity",
+    from = "Column::CounterAccountId",
+    to = "account::Column::Id",
+    on_update = "Cascade",
+    on_delete = "Cascade"
+  )]
+  CounterAccount,
+}
+
+impl Related<journal::Entity> for Entity {
+  fn to() -> RelationDef {
+    Relation::Journal.def()
+  }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
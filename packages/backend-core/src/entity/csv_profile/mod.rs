@@ -0,0 +1,366 @@
+mod builder;
+mod command;
+mod database;
+mod query;
+
+pub use builder::*;
+pub use command::*;
+pub use database::*;
+pub use query::*;
+
+use crate::actor::Actor;
+use crate::entity::membership::Role;
+use crate::entity::{account, journal, ReadRoot, WriteRoot, FIELD_ID, FIELD_JOURNAL, FIELD_NAME};
+use crate::error::{ErrorExistingEntity, ErrorNotFound};
+use itertools::Itertools;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+  ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel, Order, QueryFilter, QueryOrder,
+  QuerySelect,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+pub const TYPE: &str = "CsvProfile";
+pub const FIELD_DATE_COLUMN: &str = "dateColumn";
+pub const FIELD_AMOUNT_COLUMN: &str = "amountColumn";
+pub const FIELD_DELIMITER: &str = "delimiter";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sort {
+  #[serde(rename = "name")]
+  Name,
+  #[serde(rename = "-name")]
+  MinusName,
+}
+
+impl From<Sort> for (Column, Order) {
+  fn from(value: Sort) -> Self {
+    match value {
+      Sort::Name => (Column::Name, Order::Asc),
+      Sort::MinusName => (Column::Name, Order::Desc),
+    }
+  }
+}
+
+/// How the columns of a bank CSV export map to the entries
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Mapping {
+  /// Only the ASCII characters, which the CSV reader splits the lines by
+  #[serde(default = "Mapping::default_delimiter")]
+  pub delimiter: char,
+  pub date_column: String,
+  /// In the format of `chrono`, `%Y-%m-%d` by default
+  #[serde(default)]
+  pub date_format: String,
+  /// A single signed amount column, or the debit and credit columns split
+  #[serde(default)]
+  pub amount_column: Option<String>,
+  #[serde(default)]
+  pub debit_column: Option<String>,
+  #[serde(default)]
+  pub credit_column: Option<String>,
+  #[serde(default)]
+  pub description_column: Option<String>,
+  #[serde(default = "Mapping::default_decimal_separator")]
+  pub decimal_separator: char,
+  /// The account the CSV is exported from
+  pub account_id: Uuid,
+  pub counter_account_id: Uuid,
+}
+
+impl Mapping {
+  pub const DEFAULT_DATE_FORMAT: &'static str = "%Y-%m-%d";
+
+  fn default_delimiter() -> char {
+    ','
+  }
+
+  fn default_decimal_separator() -> char {
+    '.'
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Root {
+  pub id: Uuid,
+  pub journal_id: Uuid,
+  pub name: String,
+  #[serde(flatten)]
+  pub mapping: Mapping,
+}
+
+impl ReadRoot for Root {
+  type Query = Query;
+  type Sort = Sort;
+
+  fn id(&self) -> String {
+    self.id.to_string()
+  }
+
+  async fn find_all(
+    db: &impl ConnectionTrait,
+    query: Option<Query>,
+    limit: Option<u64>,
+    sort: Option<Sort>,
+  ) -> crate::Result<Vec<Root>> {
+    let select =
+      if let Some(query) = query { Entity::find().filter(query) } else { Entity::find() };
+    let select = if let Some(sort) = sort {
+      let (field, order) = Into::<(Column, Order)>::into(sort);
+      select.order_by(field, order)
+    } else {
+      select
+    };
+    let models = select.limit(limit).all(db).await?;
+    Self::from_model(db, models).await
+  }
+}
+
+impl WriteRoot for Root {
+  type Model = Model;
+
+  async fn from_model(
+    _db: &impl ConnectionTrait,
+    models: impl IntoIterator<Item = Model>,
+  ) -> crate::Result<Vec<Root>> {
+    let first_char = |value: String, default: char| value.chars().next().unwrap_or(default);
+    Ok(
+      models
+        .into_iter()
+        .map(|model| Root {
+          id: model.id,
+          journal_id: model.journal_id,
+          name: model.name,
+          mapping: Mapping {
+            delimiter: first_char(model.delimiter, Mapping::default_delimiter()),
+            date_column: model.date_column,
+            date_format: model.date_format,
+            amount_column: model.amount_column,
+            debit_column: model.debit_column,
+            credit_column: model.credit_column,
+            description_column: model.description_column,
+            decimal_separator: first_char(
+              model.decimal_separator,
+              Mapping::default_decimal_separator(),
+            ),
+            account_id: model.account_id,
+            counter_account_id: model.counter_account_id,
+          },
+        })
+        .collect(),
+    )
+  }
+
+  async fn save(
+    db: &impl ConnectionTrait,
+    roots: impl IntoIterator<Item = Root>,
+  ) -> crate::Result<Vec<Root>> {
+    let roots: Vec<Root> = roots.into_iter().collect();
+    if roots.is_empty() {
+      return Ok(roots);
+    }
+
+    let model_ids: HashSet<_> = roots.iter().map(|root| root.id).collect();
+    let models: Vec<_> = roots
+      .into_iter()
+      .map(|Root { id, journal_id, name, mapping }| {
+        Model {
+          id,
+          journal_id,
+          name,
+          delimiter: mapping.delimiter.to_string(),
+          date_column: mapping.date_column,
+          date_format: mapping.date_format,
+          amount_column: mapping.amount_column,
+          debit_column: mapping.debit_column,
+          credit_column: mapping.credit_column,
+          description_column: mapping.description_column,
+          decimal_separator: mapping.decimal_separator.to_string(),
+          account_id: mapping.account_id,
+          counter_account_id: mapping.counter_account_id,
+        }
+        .into_active_model()
+      })
+      .collect();
+
+    let mut on_conflict = OnConflict::column(Column::Id);
+    on_conflict.update_columns([
+      Column::Name,
+      Column::Delimiter,
+      Column::DateColumn,
+      Column::DateFormat,
+      Column::AmountColumn,
+      Column::DebitColumn,
+      Column::CreditColumn,
+      Column::DescriptionColumn,
+      Column::DecimalSeparator,
+      Column::AccountId,
+      Column::CounterAccountId,
+    ]);
+    Entity::insert_many(models).on_conflict(on_conflict).exec(db).await?;
+
+    Self::find_all(db, Some(Query { id: model_ids, ..Default::default() }), None, None).await
+  }
+
+  async fn delete(
+    db: &impl ConnectionTrait,
+    ids: impl IntoIterator<Item = Uuid>,
+  ) -> crate::Result<()> {
+    Entity::delete_many().filter(Column::Id.is_in(ids)).exec(db).await?;
+    Ok(())
+  }
+}
+
+impl Root {
+  /// Handle the command, which the editors of the journals are allowed to only
+  pub async fn handle(
+    db: &impl ConnectionTrait,
+    actor: &Actor,
+    command: Command,
+  ) -> crate::Result<Vec<Root>> {
+    let (ids, mut journal_ids): (HashSet<_>, HashSet<_>) = match &command {
+      Command::Create(command) => (HashSet::default(), HashSet::from_iter([command.journal_id])),
+      Command::Update(command) => (HashSet::from_iter([command.id]), HashSet::default()),
+      Command::Delete(command) => (command.id.clone(), HashSet::default()),
+    };
+    if !ids.is_empty() {
+      let befores =
+        Self::find_all(db, Some(Query { id: ids, ..Default::default() }), None, None).await?;
+      journal_ids.extend(befores.into_iter().map(|root| root.journal_id));
+    }
+    actor.check_journals(db, journal_ids, Role::Editor).await?;
+
+    match command {
+      Command::Create(command) => Self::create(db, vec![command]).await,
+      Command::Update(command) => Self::update(db, vec![command]).await,
+      Command::Delete(CommandDelete { id }) => {
+        Self::delete(db, id).await?;
+        Ok(Vec::default())
+      }
+    }
+  }
+
+  pub async fn create(
+    db: &impl ConnectionTrait,
+    commands: Vec<CommandCreate>,
+  ) -> crate::Result<Vec<Root>> {
+    let mut roots = Vec::new();
+    for command in commands {
+      let accounts = Self::find_accounts(db, command.journal_id).await?;
+      Self::check_name(db, command.journal_id, &command.name, None).await?;
+
+      roots.push(
+        Builder::default()
+          .journal_id(command.journal_id)
+          .name(command.name)
+          .mapping(command.mapping)
+          .build(&accounts)?,
+      );
+    }
+
+    Self::save(db, roots).await
+  }
+
+  pub async fn update(
+    db: &impl ConnectionTrait,
+    commands: Vec<CommandUpdate>,
+  ) -> crate::Result<Vec<Root>> {
+    let mut models = Self::find_all(
+      db,
+      Some(Query { id: commands.iter().map(|command| command.id).collect(), ..Default::default() }),
+      None,
+      None,
+    )
+    .await?
+    .into_iter()
+    .map(|model| (model.id, model))
+    .collect::<HashMap<_, _>>();
+
+    let mut roots = Vec::new();
+    for command in commands {
+      let model = models.remove(&command.id).ok_or_else(|| {
+        crate::Error::NotFound(ErrorNotFound {
+          entity: TYPE.to_string(),
+          values: vec![(FIELD_ID.to_string(), command.id.to_string())],
+        })
+      })?;
+      let accounts = Self::find_accounts(db, model.journal_id).await?;
+
+      let mut builder = Builder::from(model.clone());
+      if !command.name.is_empty() {
+        Self::check_name(db, model.journal_id, &command.name, Some(model.id)).await?;
+        builder = builder.name(command.name);
+      }
+      if let Some(mapping) = command.mapping {
+        builder = builder.mapping(mapping);
+      }
+      roots.push(builder.build(&accounts)?);
+    }
+
+    Self::save(db, roots).await
+  }
+
+  async fn find_accounts(
+    db: &impl ConnectionTrait,
+    journal_id: Uuid,
+  ) -> crate::Result<HashMap<Uuid, account::Root>> {
+    if journal::Root::find_one(
+      db,
+      Some(journal::Query { id: HashSet::from_iter([journal_id]), ..Default::default() }),
+    )
+    .await?
+    .is_none()
+    {
+      return Err(crate::Error::NotFound(ErrorNotFound {
+        entity: journal::TYPE.to_string(),
+        values: vec![(FIELD_ID.to_string(), journal_id.to_string())],
+      }));
+    }
+
+    Ok(
+      account::Root::find_all(
+        db,
+        Some(account::Query { journal_id: HashSet::from_iter([journal_id]), ..Default::default() }),
+        None,
+        None,
+      )
+      .await?
+      .into_iter()
+      .map(|account| (account.id, account))
+      .collect(),
+    )
+  }
+
+  async fn check_name(
+    db: &impl ConnectionTrait,
+    journal_id: Uuid,
+    name: &str,
+    id: Option<Uuid>,
+  ) -> crate::Result<()> {
+    let existings = Self::find_all(
+      db,
+      Some(Query {
+        journal_id: HashSet::from_iter([journal_id]),
+        name: HashSet::from_iter([name.to_string()]),
+        ..Default::default()
+      }),
+      None,
+      None,
+    )
+    .await?;
+    if existings.iter().any(|existing| Some(existing.id) != id) {
+      return Err(crate::Error::ExistingEntity(ErrorExistingEntity {
+        entity: TYPE.to_string(),
+        values: vec![
+          (FIELD_JOURNAL.to_string(), journal_id.to_string()),
+          (FIELD_NAME.to_string(), existings.iter().map(|model| &model.name).join(", ")),
+        ],
+      }));
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,50 @@
+use crate::entity::{csv_profile, JournalQuery};
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Cond, IntoCondition};
+use sea_orm::Condition;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Query {
+  #[serde(default)]
+  pub id: HashSet<Uuid>,
+  #[serde(default)]
+  pub journal_id: HashSet<Uuid>,
+  #[serde(default)]
+  pub name: HashSet<String>,
+}
+
+impl JournalQuery for Query {
+  fn journal_ids(&mut self) -> &mut HashSet<Uuid> {
+    &mut self.journal_id
+  }
+}
+
+impl IntoCondition for Query {
+  fn into_condition(self) -> Condition {
+    let mut cond = Cond::all();
+
+    if !self.id.is_empty() {
+      cond = cond.add(csv_profile::Column::Id.is_in(self.id));
+    }
+
+    if !self.journal_id.is_empty() {
+      cond = cond.add(csv_profile::Column::JournalId.is_in(self.journal_id));
+    }
+
+    let name: HashSet<String> = self
+      .name
+      .into_iter()
+      .map(|name| name.trim().to_string())
+      .filter(|name| !name.is_empty())
+      .collect();
+    if !name.is_empty() {
+      cond = cond.add(csv_profile::Column::Name.is_in(name));
+    }
+
+    cond
+  }
+}
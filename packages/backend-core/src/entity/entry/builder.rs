@@ -4,7 +4,7 @@ use crate::entity::{
   FIELD_TYPE,
 };
 use crate::error::{ErrorNotFound, ErrorOutOfRange, ErrorRequiredField};
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
@@ -19,6 +19,10 @@ pub struct Builder {
   date: NaiveDate,
   tags: HashSet<String>,
   items: Vec<Item>,
+  payee_id: Option<Uuid>,
+  version: i32,
+  created_at: DateTime<Utc>,
+  updated_at: DateTime<Utc>,
 }
 
 impl From<Root> for Builder {
@@ -32,6 +36,10 @@ impl From<Root> for Builder {
       date: value.date,
       tags: value.tags,
       items: value.items,
+      payee_id: value.payee_id,
+      version: value.version,
+      created_at: value.created_at,
+      updated_at: value.updated_at,
     }
   }
 }
@@ -103,6 +111,10 @@ impl Builder {
         .into_iter()
         .map(|(account, (amount, price))| Item { account, amount, price })
         .collect(),
+      payee_id: self.payee_id,
+      version: self.version,
+      created_at: self.created_at,
+      updated_at: self.updated_at,
     })
   }
 
@@ -136,4 +148,13 @@ impl Builder {
   pub fn items(self, items: Vec<Item>) -> Builder {
     Builder { items, ..self }
   }
+
+  pub fn payee_id(self, payee_id: Option<Uuid>) -> Builder {
+    Builder { payee_id, ..self }
+  }
+
+  /// Keep the time the root is created at when restoring it, rather than taking the one saved at
+  pub fn created_at(self, created_at: DateTime<Utc>) -> Builder {
+    Builder { created_at, ..self }
+  }
 }
@@ -1,7 +1,7 @@
-use crate::entity::entry;
+use crate::entity::{deserialize_some, entry};
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -17,6 +17,29 @@ pub enum Command {
   Batch(CommandBatch),
 }
 
+impl Command {
+  /// The IDs of the existing entities the command refers to
+  pub fn ids(&self) -> HashSet<Uuid> {
+    match self {
+      Command::Create(_) => HashSet::default(),
+      Command::Update(command) => HashSet::from_iter([command.id]),
+      Command::Delete(command) => command.id.clone(),
+      Command::Batch(command) => {
+        command.update.iter().map(|command| command.id).chain(command.delete.clone()).collect()
+      }
+    }
+  }
+
+  /// The IDs of the journals the new entities are created in
+  pub fn journal_ids(&self) -> HashSet<Uuid> {
+    match self {
+      Command::Create(command) => HashSet::from_iter([command.journal_id]),
+      Command::Batch(command) => command.create.iter().map(|command| command.journal_id).collect(),
+      _ => HashSet::default(),
+    }
+  }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct CommandCreate {
@@ -31,6 +54,8 @@ pub struct CommandCreate {
   pub tags: HashSet<String>,
   #[serde(default)]
   pub items: Vec<entry::Item>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub payee_id: Option<Uuid>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -50,12 +75,23 @@ pub struct CommandUpdate {
   pub tags: Option<HashSet<String>>,
   #[serde(default)]
   pub items: Vec<entry::Item>,
+  /// Cleared by an explicit `null`, kept if missing
+  #[serde(default, deserialize_with = "deserialize_some", skip_serializing_if = "Option::is_none")]
+  pub payee_id: Option<Option<Uuid>>,
+  /// Conflicts if the entity has been saved since read at this version
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub expected_version: Option<i32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct CommandDelete {
   #[serde(default)]
   pub id: HashSet<Uuid>,
+  /// The versions the entities are expected at, keyed by the IDs. The ones missing are deleted
+  /// whatever the versions are
+  #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+  pub expected_version: HashMap<Uuid, i32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -74,7 +110,7 @@ mod tests {
   use chrono::NaiveDate;
   use rust_decimal_macros::dec;
   use serde_json::json;
-  use std::collections::HashSet;
+  use std::collections::{HashMap, HashSet};
   use uuid::uuid;
 
   #[test]
@@ -92,6 +128,7 @@ mod tests {
           amount: dec!(1.2),
           price: dec!(1.0),
         }],
+        payee_id: None,
       }),
       entry::Command::Update(entry::CommandUpdate {
         id: uuid!("7aaec70c-adbc-47d1-8b74-a3e21f387d21"),
@@ -101,9 +138,12 @@ mod tests {
         date: Some(NaiveDate::from_ymd_opt(2023, 2, 1).unwrap()),
         tags: None,
         items: Vec::default(),
+        payee_id: None,
+        expected_version: None,
       }),
       entry::Command::Delete(entry::CommandDelete {
         id: HashSet::from_iter([uuid!("7aaec70c-adbc-47d1-8b74-a3e21f387d21")]),
+        expected_version: HashMap::default(),
       }),
       entry::Command::Batch(entry::CommandBatch {
         create: vec![
@@ -119,6 +159,7 @@ mod tests {
               amount: dec!(1.2),
               price: dec!(1.0),
             }],
+            payee_id: None,
           },
           entry::CommandCreate {
             journal_id: uuid!("7aaec70c-adbc-47d1-8b74-a3e21f387d22"),
@@ -132,6 +173,7 @@ mod tests {
               amount: dec!(1.1),
               price: dec!(2.2),
             }],
+            payee_id: None,
           },
         ],
         update: vec![
@@ -143,6 +185,8 @@ mod tests {
             date: Some(NaiveDate::from_ymd_opt(2023, 2, 1).unwrap()),
             tags: None,
             items: Vec::default(),
+            payee_id: None,
+            expected_version: None,
           },
           entry::CommandUpdate {
             id: uuid!("7aaec70c-adbc-47d1-8b74-a3e21f387d25"),
@@ -152,6 +196,8 @@ mod tests {
             date: Some(NaiveDate::from_ymd_opt(2023, 2, 1).unwrap()),
             tags: None,
             items: Vec::default(),
+            payee_id: None,
+            expected_version: None,
           },
         ],
         delete: HashSet::from_iter([uuid!("7aaec70c-adbc-47d1-8b74-a3e21f387d21")]),
@@ -238,4 +284,24 @@ mod tests {
       ])
     );
   }
+
+  #[test]
+  fn test_serde_payee_id() -> anyhow::Result<()> {
+    let missing: entry::CommandUpdate =
+      serde_json::from_value(json!({ "id": "7aaec70c-adbc-47d1-8b74-a3e21f387d21" }))?;
+    assert_eq!(None, missing.payee_id);
+
+    let cleared: entry::CommandUpdate = serde_json::from_value(
+      json!({ "id": "7aaec70c-adbc-47d1-8b74-a3e21f387d21", "payeeId": null }),
+    )?;
+    assert_eq!(Some(None), cleared.payee_id);
+
+    let set: entry::CommandUpdate = serde_json::from_value(json!({
+      "id": "7aaec70c-adbc-47d1-8b74-a3e21f387d21",
+      "payeeId": "7aaec70c-adbc-47d1-8b74-a3e21f387d22",
+    }))?;
+    assert_eq!(Some(Some(uuid!("7aaec70c-adbc-47d1-8b74-a3e21f387d22"))), set.payee_id);
+
+    Ok(())
+  }
 }
@@ -1,4 +1,4 @@
-use crate::entity::{entry_item, entry_tag, journal};
+use crate::entity::{entry_item, entry_tag, journal, payee};
 use chrono::NaiveDate;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -17,6 +17,11 @@ pub struct Model {
   pub typ: Type,
   #[sea_orm(indexed)]
   pub date: NaiveDate,
+  #[sea_orm(indexed)]
+  pub payee_id: Option<Uuid>,
+  pub version: i32,
+  pub created_at: DateTimeUtc,
+  pub updated_at: DateTimeUtc,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -33,6 +38,14 @@ pub enum Relation {
   Journal,
   #[sea_orm(has_many = "entry_item::Entity")]
   Items,
+  #[sea_orm(
+    belongs_to = "payee::Entity",
+    from = "Column::PayeeId",
+    to = "payee::Column::Id",
+    on_update = "Cascade",
+    on_delete = "SetNull"
+  )]
+  Payee,
 }
 
 impl Related<journal::Entity> for Entity {
@@ -10,11 +10,17 @@ pub use database::*;
 pub use presentation::*;
 pub use query::*;
 
+use crate::actor::Actor;
+use crate::entity::membership::Role;
 use crate::entity::{
-  account, entry_item, entry_tag, journal, ReadRoot, WriteRoot, FIELD_ID, FIELD_JOURNAL, FIELD_NAME,
+  account, account_balance, check_version, check_versions, created_at, entry_item, entry_tag,
+  history, journal, next_version, payee, reconciliation, ReadRoot, WriteRoot, FIELD_ID,
+  FIELD_JOURNAL, FIELD_NAME,
 };
-use crate::error::{ErrorExistingEntity, ErrorNotFound};
+use crate::error::{ErrorConflict, ErrorExistingEntity, ErrorNotFound};
+use crate::undo::Inverse;
 use chrono::NaiveDate;
+use chrono::{DateTime, Utc};
 use itertools::Itertools;
 use rust_decimal::Decimal;
 use sea_orm::sea_query::{BinOper, Expr, OnConflict};
@@ -75,16 +81,28 @@ impl From<Sort> for (Column, Order) {
   }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
 pub struct Root {
   pub id: Uuid,
   pub journal_id: Uuid,
   pub name: String,
   pub description: String,
+  #[serde(rename = "type")]
   pub typ: Type,
   pub date: NaiveDate,
   pub tags: HashSet<String>,
   pub items: Vec<Item>,
+  #[serde(default)]
+  pub payee_id: Option<Uuid>,
+  /// Increased on every save, for the commands to expect the one they have read
+  #[serde(default)]
+  pub version: i32,
+  /// Kept as it is on the saves, unless the root is new
+  #[serde(default)]
+  pub created_at: DateTime<Utc>,
+  #[serde(default)]
+  pub updated_at: DateTime<Utc>,
 }
 
 impl ReadRoot for Root {
@@ -134,6 +152,10 @@ impl WriteRoot for Root {
         date: model.date,
         tags: HashSet::default(),
         items: Vec::default(),
+        payee_id: model.payee_id,
+        version: model.version,
+        created_at: model.created_at,
+        updated_at: model.updated_at,
       });
       ids.insert(model.id);
     }
@@ -186,12 +208,13 @@ impl WriteRoot for Root {
       return Ok(roots);
     }
 
+    let now = Utc::now();
     let mut model_ids = HashSet::new();
     let mut models: Vec<ActiveModel> = vec![];
     let mut tags: Vec<entry_tag::ActiveModel> = vec![];
     let mut items: Vec<entry_item::ActiveModel> = vec![];
 
-    for ref root in roots {
+    for root in &roots {
       model_ids.insert(root.id);
       models.push(
         Model {
@@ -201,6 +224,10 @@ impl WriteRoot for Root {
           description: root.description.to_string(),
           typ: root.typ,
           date: root.date,
+          payee_id: root.payee_id,
+          version: root.version + 1,
+          created_at: created_at(root.created_at, now),
+          updated_at: now,
         }
         .into_active_model(),
       );
@@ -220,6 +247,15 @@ impl WriteRoot for Root {
       }
     }
 
+    let mut deltas = account_balance::Deltas::new();
+    let previous =
+      Self::find_all(db, Some(Query { id: model_ids.clone(), ..Default::default() }), None, None)
+        .await?;
+    let currents = previous.iter().map(|root| (root.id, root.version)).collect();
+    check_versions(TYPE, &roots, |root| (root.id, root.version), &currents)?;
+    account_balance::collect_deltas(&mut deltas, &previous, true);
+    account_balance::collect_deltas(&mut deltas, &roots, false);
+
     entry_tag::Entity::delete_many()
       .filter(entry_tag::Column::EntryId.is_in(model_ids.clone()))
       .exec(db)
@@ -237,7 +273,11 @@ impl WriteRoot for Root {
       Column::Description,
       Column::Typ,
       Column::Date,
+      Column::PayeeId,
+      Column::Version,
+      Column::UpdatedAt,
     ]);
+    on_conflict.action_and_where(next_version(Entity, Column::Version));
 
     // Update unique column name to temp value
     Entity::update_many()
@@ -249,7 +289,14 @@ impl WriteRoot for Root {
       .exec(db)
       .await?;
 
-    Entity::insert_many(models).on_conflict(on_conflict).exec(db).await?;
+    let saved_count =
+      Entity::insert_many(models).on_conflict(on_conflict).exec_without_returning(db).await?;
+    if saved_count != roots.len() as u64 {
+      return Err(crate::Error::Conflict(ErrorConflict {
+        entity: TYPE.to_string(),
+        values: Vec::default(),
+      }));
+    }
 
     if !tags.is_empty() {
       entry_tag::Entity::insert_many(tags).exec(db).await?;
@@ -259,6 +306,8 @@ impl WriteRoot for Root {
       entry_item::Entity::insert_many(items).exec(db).await?;
     }
 
+    account_balance::apply(db, deltas).await?;
+
     Self::find_all(db, Some(Query { id: model_ids, ..Default::default() }), None, None).await
   }
 
@@ -266,17 +315,68 @@ impl WriteRoot for Root {
     db: &impl ConnectionTrait,
     ids: impl IntoIterator<Item = Uuid>,
   ) -> crate::Result<()> {
+    let ids: HashSet<_> = ids.into_iter().collect();
+    let mut deltas = account_balance::Deltas::new();
+    let previous =
+      Self::find_all(db, Some(Query { id: ids.clone(), ..Default::default() }), None, None).await?;
+    account_balance::collect_deltas(&mut deltas, &previous, true);
+
     Entity::delete_many().filter(Column::Id.is_in(ids)).exec(db).await?;
+    account_balance::apply(db, deltas).await?;
     Ok(())
   }
 }
 
 impl Root {
-  pub async fn handle(db: &impl ConnectionTrait, command: Command) -> crate::Result<Vec<Root>> {
-    match command {
+  /// Handle the command, and record the changes of the affected entities in the history on behalf
+  /// of the actor, who should be an editor of the journals
+  pub async fn handle(
+    db: &impl ConnectionTrait,
+    actor: &Actor,
+    command: Command,
+  ) -> crate::Result<Vec<Root>> {
+    Ok(Self::handle_with_inverse(db, actor, command).await?.0)
+  }
+
+  /// Handle the command the same as [`Root::handle`], and return the inverse to undo it as well
+  pub async fn handle_with_inverse(
+    db: &impl ConnectionTrait,
+    actor: &Actor,
+    command: Command,
+  ) -> crate::Result<(Vec<Root>, Inverse)> {
+    let ids = command.ids();
+    let befores = if ids.is_empty() {
+      Vec::default()
+    } else {
+      Self::find_all(db, Some(Query { id: ids, ..Default::default() }), None, None).await?
+    };
+    actor
+      .check_journals(
+        db,
+        befores.iter().map(|root| root.journal_id).chain(command.journal_ids()),
+        Role::Editor,
+      )
+      .await?;
+
+    // The reconciled records are confirmed against the statements, so they are kept as they are
+    // until the reconciliations are deleted
+    let reconciled: Vec<_> =
+      reconciliation::find_reconciled(db, command.ids()).await?.into_keys().sorted().collect();
+    if !reconciled.is_empty() {
+      return Err(crate::Error::ExistingEntity(ErrorExistingEntity {
+        entity: reconciliation::TYPE.to_string(),
+        values: vec![(reconciliation::FIELD_ENTRIES.to_string(), reconciled.iter().join(", "))],
+      }));
+    }
+
+    let roots = match command.clone() {
       Command::Create(command) => Self::create(db, vec![command]).await,
       Command::Update(command) => Self::update(db, vec![command]).await,
-      Command::Delete(CommandDelete { id }) => {
+      Command::Delete(CommandDelete { id, expected_version }) => {
+        for (entity_id, expected) in expected_version {
+          let current = befores.iter().find(|root| root.id == entity_id).map(|root| root.version);
+          check_version(TYPE, entity_id, Some(expected), current)?;
+        }
         Self::delete(db, id).await?;
         Ok(Vec::default())
       }
@@ -295,7 +395,26 @@ impl Root {
 
         Self::find_all(db, Some(Query { id: ids, ..Default::default() }), None, None).await
       }
-    }
+    }?;
+
+    history::Root::record(
+      db,
+      Uuid::new_v4(),
+      TYPE,
+      actor,
+      &command,
+      befores.iter().map(|root| (root.id, root)),
+      roots.iter().map(|root| (root.id, root)),
+    )
+    .await?;
+
+    let mut inverse = Inverse::default();
+    inverse.record(
+      TYPE,
+      befores.iter().map(|root| (root.id, root)),
+      roots.iter().map(|root| (root.id, root)),
+    )?;
+    Ok((roots, inverse))
   }
 
   pub async fn create(
@@ -374,6 +493,14 @@ impl Root {
       }
     }
 
+    for (journal_id, payee_ids) in commands
+      .iter()
+      .filter_map(|command| command.payee_id.map(|payee_id| (command.journal_id, payee_id)))
+      .into_group_map()
+    {
+      Self::check_payees(db, journal_id, payee_ids).await?;
+    }
+
     let roots: Vec<_> = commands
       .into_iter()
       .filter_map(|command| {
@@ -387,6 +514,7 @@ impl Root {
               .date(command.date)
               .tags(command.tags)
               .items(command.items)
+              .payee_id(command.payee_id)
               .build(&accounts),
           )
         } else {
@@ -419,6 +547,13 @@ impl Root {
     .map(|model| (model.id, model))
     .collect();
 
+    Self::check_payees(
+      db,
+      journal.id,
+      commands.iter().filter_map(|command| command.payee_id.flatten()).collect(),
+    )
+    .await?;
+
     let mut name_mappings = HashMap::new();
     let mut model_ids = HashSet::new();
 
@@ -467,6 +602,7 @@ impl Root {
           values: vec![(FIELD_ID.to_string(), command.id.to_string())],
         })
       })?;
+      check_version(TYPE, model.id, command.expected_version, Some(model.version))?;
 
       if command.name.is_empty()
         && command.description.is_none()
@@ -474,6 +610,7 @@ impl Root {
         && command.date.is_none()
         && command.tags.is_none()
         && command.items.is_empty()
+        && command.payee_id.is_none()
       {
         continue;
       }
@@ -503,6 +640,10 @@ impl Root {
         builder = builder.items(command.items.clone());
       }
 
+      if let Some(payee_id) = command.payee_id {
+        builder = builder.payee_id(payee_id);
+      }
+
       let model = builder.build(&accounts)?;
 
       entries.insert(model.id, model.clone());
@@ -564,6 +705,41 @@ impl Root {
 
     Self::save(db, updated.into_values()).await
   }
+
+  async fn check_payees(
+    db: &impl ConnectionTrait,
+    journal_id: Uuid,
+    payee_ids: Vec<Uuid>,
+  ) -> crate::Result<()> {
+    if payee_ids.is_empty() {
+      return Ok(());
+    }
+
+    let existings: HashSet<_> = payee::Root::find_all(
+      db,
+      Some(payee::Query {
+        id: payee_ids.iter().copied().collect(),
+        journal_id: HashSet::from_iter([journal_id]),
+        ..Default::default()
+      }),
+      None,
+      None,
+    )
+    .await?
+    .into_iter()
+    .map(|payee| payee.id)
+    .collect();
+    if let Some(payee_id) = payee_ids.iter().find(|payee_id| !existings.contains(payee_id)) {
+      return Err(crate::Error::NotFound(ErrorNotFound {
+        entity: payee::TYPE.to_string(),
+        values: vec![
+          (FIELD_JOURNAL.to_string(), journal_id.to_string()),
+          (FIELD_ID.to_string(), payee_id.to_string()),
+        ],
+      }));
+    }
+    Ok(())
+  }
 }
 
 #[cfg(test)]
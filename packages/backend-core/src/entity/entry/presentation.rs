@@ -1,21 +1,13 @@
-use crate::entity::account;
-use crate::entity::entry::{Item, Query, Root, Type};
+use crate::entity::entry::{Item, Root, StateItem, Type};
 use crate::entity::{self, ReadRoot};
-use chrono::NaiveDate;
-use itertools::Itertools;
+use crate::entity::{account, account_balance, reconciliation};
+use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use sea_orm::ConnectionTrait;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
-#[derive(Serialize, Deserialize)]
-#[serde(tag = "type", content = "value")]
-pub enum StateItem {
-  Valid(Decimal),
-  Invalid(Decimal, Decimal),
-}
-
 #[derive(Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum Presentation {
@@ -33,7 +25,13 @@ pub struct PresentationRecord {
   pub date: NaiveDate,
   pub tags: HashSet<String>,
   pub items: Vec<Item>,
+  pub payee_id: Option<Uuid>,
+  pub version: i32,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
   pub state: StateItem,
+  /// The reconciliation this record is confirmed by, if any
+  pub reconciliation_id: Option<Uuid>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -46,6 +44,10 @@ pub struct PresentationCheck {
   pub date: NaiveDate,
   pub tags: HashSet<String>,
   pub items: Vec<Item>,
+  pub payee_id: Option<Uuid>,
+  pub version: i32,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
   pub state: HashMap<Uuid, StateItem>,
 }
 
@@ -53,19 +55,27 @@ impl entity::Presentation for Presentation {
   type R = Root;
 
   async fn from_roots(db: &impl ConnectionTrait, roots: Vec<Self::R>) -> crate::Result<Vec<Self>> {
-    let journal_ids: HashSet<_> = roots.iter().map(|root| root.journal_id).collect();
     let account_ids: HashSet<_> =
       roots.iter().flat_map(|root| root.items.iter()).map(|item| item.account).collect();
 
-    let related_entries = Root::find_all(
+    let mut checking_accounts = HashMap::<NaiveDate, HashSet<Uuid>>::new();
+    for root in roots.iter().filter(|root| root.typ == Type::Check) {
+      checking_accounts
+        .entry(root.date)
+        .or_default()
+        .extend(root.items.iter().map(|item| item.account));
+    }
+    let mut balances = HashMap::<NaiveDate, HashMap<Uuid, Decimal>>::new();
+    for (date, account_ids) in checking_accounts {
+      balances.insert(date, account_balance::find_balances(db, account_ids, Some(date)).await?);
+    }
+
+    let reconciled = reconciliation::find_reconciled(
       db,
-      Some(Query { journal_id: journal_ids, typ: Some(Type::Record), ..Default::default() }),
-      None,
-      None,
+      roots.iter().filter(|root| root.typ == Type::Record).map(|root| root.id).collect(),
     )
-    .await?
-    .into_iter()
-    .into_group_map_by(|root| root.journal_id);
+    .await?;
+
     let related_accounts: HashMap<_, _> = account::Root::find_all(
       db,
       Some(account::Query { id: account_ids, ..Default::default() }),
@@ -84,13 +94,10 @@ impl entity::Presentation for Presentation {
         let mut right = Decimal::ZERO;
         for item in &root.items {
           if let Some(account) = related_accounts.get(&item.account) {
-            match &account.typ {
-              account::Type::Asset | account::Type::Expense => {
-                left += item.amount * item.price;
-              }
-              _ => {
-                right += item.amount * item.price;
-              }
+            if account.typ.is_debit() {
+              left += item.amount * item.price;
+            } else {
+              right += item.amount * item.price;
             }
           }
         }
@@ -105,27 +112,22 @@ impl entity::Presentation for Presentation {
           date: root.date,
           tags: root.tags.clone(),
           items: root.items.clone(),
+          payee_id: root.payee_id,
+          version: root.version,
+          created_at: root.created_at,
+          updated_at: root.updated_at,
           state,
+          reconciliation_id: reconciled.get(&root.id).copied(),
         }))
       } else {
-        let mut actuals = HashMap::<Uuid, Decimal>::new();
-        if let Some(related_records) = related_entries.get(&root.journal_id) {
-          for record in related_records {
-            if record.date <= root.date {
-              for item in &record.items {
-                let value = actuals.get(&item.account).copied().unwrap_or_default();
-                actuals.insert(item.account, value + item.amount * item.price);
-              }
-            }
-          }
-        }
-
+        let actuals = balances.get(&root.date);
         let state = root
           .items
           .iter()
           .map(|item| {
             let expected = item.amount * item.price;
-            let actual = actuals.get(&item.account).copied().unwrap_or_default();
+            let actual =
+              actuals.and_then(|actuals| actuals.get(&item.account)).copied().unwrap_or_default();
             (
               item.account,
               if expected == actual {
@@ -144,6 +146,10 @@ impl entity::Presentation for Presentation {
           date: root.date,
           tags: root.tags.clone(),
           items: root.items.clone(),
+          payee_id: root.payee_id,
+          version: root.version,
+          created_at: root.created_at,
+          updated_at: root.updated_at,
           state,
         }))
       }
@@ -1,6 +1,6 @@
 use crate::entity::entry::Type;
-use crate::entity::{entry, entry_item, entry_tag};
-use chrono::NaiveDate;
+use crate::entity::{entry, entry_item, entry_tag, reconciliation_entry, JournalQuery};
+use chrono::{DateTime, NaiveDate, Utc};
 use sea_orm::entity::prelude::*;
 use sea_orm::sea_query::{Cond, Func, IntoCondition};
 use sea_orm::{Condition, QuerySelect, QueryTrait};
@@ -27,7 +27,23 @@ pub struct Query {
   #[serde(default)]
   pub end: Option<NaiveDate>,
   #[serde(default)]
+  pub tags: HashSet<String>,
+  #[serde(default)]
   pub full_text: String,
+  /// If the entries are marked as reconciled by any reconciliation
+  #[serde(default)]
+  pub reconciled: Option<bool>,
+  #[serde(default)]
+  pub payee_id: HashSet<Uuid>,
+  /// The ones saved at or after the time
+  #[serde(default)]
+  pub updated_since: Option<DateTime<Utc>>,
+}
+
+impl JournalQuery for Query {
+  fn journal_ids(&mut self) -> &mut HashSet<Uuid> {
+    &mut self.journal_id
+  }
 }
 
 impl IntoCondition for Query {
@@ -77,6 +93,44 @@ impl IntoCondition for Query {
       cond = cond.add(entry::Column::Date.lte(end));
     }
 
+    let tags = self
+      .tags
+      .iter()
+      .filter_map(|tag| match tag.trim() {
+        "" => None,
+        val => Some(val.to_string()),
+      })
+      .collect::<HashSet<_>>();
+    if !tags.is_empty() {
+      cond = cond.add(
+        entry::Column::Id.in_subquery(
+          entry_tag::Entity::find()
+            .select_only()
+            .distinct()
+            .column(entry_tag::Column::EntryId)
+            .filter(Expr::expr(Expr::col((entry_tag::Entity, entry_tag::Column::Tag))).is_in(tags))
+            .into_query(),
+        ),
+      );
+    }
+
+    if let Some(reconciled) = self.reconciled {
+      let reconciled_ids = reconciliation_entry::Entity::find()
+        .select_only()
+        .distinct()
+        .column(reconciliation_entry::Column::EntryId)
+        .into_query();
+      cond = cond.add(if reconciled {
+        entry::Column::Id.in_subquery(reconciled_ids)
+      } else {
+        entry::Column::Id.not_in_subquery(reconciled_ids)
+      });
+    }
+
+    if !self.payee_id.is_empty() {
+      cond = cond.add(entry::Column::PayeeId.is_in(self.payee_id));
+    }
+
     let keyword = self.full_text.trim().to_lowercase();
     if !keyword.is_empty() {
       let keyword = format!("%{}%", keyword);
@@ -106,6 +160,10 @@ impl IntoCondition for Query {
       cond = cond.add(sub_cond);
     }
 
+    if let Some(updated_since) = self.updated_since {
+      cond = cond.add(entry::Column::UpdatedAt.gte(updated_since));
+    }
+
     cond
   }
 }
@@ -128,15 +186,22 @@ mod tests {
       typ: Some(Type::Check),
       start: Some(NaiveDate::from_ymd_opt(2023, 1, 1).unwrap()),
       end: Some(NaiveDate::from_ymd_opt(2023, 12, 31).unwrap()),
+      tags: HashSet::from_iter(["Tag 1".to_string()]),
       full_text: "Keyword  ".to_string(),
+      reconciled: Some(false),
+      payee_id: HashSet::from_iter([uuid!("50a1b556-b99d-4ae0-bfba-d117f9a958df")]),
+      updated_since: None,
     };
 
     assert_eq!(
-      [r#"SELECT "entries"."id", "entries"."journal_id", "entries"."name", "entries"."description", "entries"."type", "entries"."date" FROM "entries""#,
+      [r#"SELECT "entries"."id", "entries"."journal_id", "entries"."name", "entries"."description", "entries"."type", "entries"."date", "entries"."payee_id", "entries"."version", "entries"."created_at", "entries"."updated_at" FROM "entries""#,
         r#"WHERE "entries"."id" IN ('50a1b556-b99d-4ae0-bfba-d117f9a958de')"#,
         r#"AND "entries"."journal_id" IN ('50a1b556-b99d-4ae0-bfba-d117f9a958de')"#,
         r#"AND "entries"."id" IN (SELECT DISTINCT "entry_items"."entry_id" FROM "entry_items" WHERE "entry_items"."account_id" IN ('50a1b556-b99d-4ae0-bfba-d117f9a958de'))"#,
         r#"AND "entries"."name" IN ('Name 1') AND "entries"."type" = 'C' AND "entries"."date" >= '2023-01-01' AND "entries"."date" <= '2023-12-31'"#,
+        r#"AND "entries"."id" IN (SELECT DISTINCT "entry_tags"."entry_id" FROM "entry_tags" WHERE "entry_tags"."tag" IN ('Tag 1'))"#,
+        r#"AND "entries"."id" NOT IN (SELECT DISTINCT "reconciliation_entries"."entry_id" FROM "reconciliation_entries")"#,
+        r#"AND "entries"."payee_id" IN ('50a1b556-b99d-4ae0-bfba-d117f9a958df')"#,
         r#"AND (LOWER("entries"."name") LIKE '%keyword%' OR LOWER("entries"."description") LIKE '%keyword%'"#,
         r#"OR "entries"."id" IN (SELECT DISTINCT "entry_tags"."entry_id" FROM "entry_tags" WHERE LOWER("entry_tags"."tag") LIKE '%keyword%'))"#].join(" "),
       entry::Entity::find().filter(query).build(DbBackend::Sqlite).to_string()
@@ -2,29 +2,60 @@ mod query;
 
 pub use query::*;
 
-use crate::entity::{account, entry, ReadRoot};
+use crate::entity::{account, account_balance, entry, entry_item, ReadRoot, REPORT_SPLITERATOR};
 use itertools::Itertools;
 use rust_decimal::Decimal;
-use sea_orm::ConnectionTrait;
+use sea_orm::sea_query::{Expr, Func, SimpleExpr};
+use sea_orm::{ConnectionTrait, EntityTrait, QueryFilter, QuerySelect, Select};
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
-use std::ops::{Add, Mul};
 use uuid::Uuid;
 
-const REPORT_SPLITERATOR: &str = ":::";
+pub const TYPE: &str = "HierarchyReport";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sort {
+  #[serde(rename = "name")]
+  Name,
+  #[serde(rename = "-name")]
+  MinusName,
+  #[serde(rename = "value")]
+  Value,
+  #[serde(rename = "-value")]
+  MinusValue,
+}
+
+impl Sort {
+  fn compare(&self, a: &Root, b: &Root) -> Ordering {
+    match self {
+      Sort::Name => a.prefix.cmp(&b.prefix),
+      Sort::MinusName => b.prefix.cmp(&a.prefix),
+      Sort::Value => a.value.cmp(&b.value).then_with(|| a.prefix.cmp(&b.prefix)),
+      Sort::MinusValue => b.value.cmp(&a.value).then_with(|| a.prefix.cmp(&b.prefix)),
+    }
+  }
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct Root {
   pub journal_id: Uuid,
   pub prefix: String,
+  /// The last segment of the prefix
+  pub name: String,
   pub unit: String,
+  pub depth: usize,
+  /// The sum of all the values
+  pub value: Decimal,
+  /// The values of all the accounts under this prefix, including the ones with zero balance
   pub values: HashMap<Uuid, Decimal>,
+  pub children: Vec<Root>,
 }
 
 impl ReadRoot for Root {
   type Query = Query;
-  type Sort = ();
+  type Sort = Sort;
 
   fn id(&self) -> String {
     [self.journal_id.to_string(), self.prefix.clone(), self.unit.clone()].join(REPORT_SPLITERATOR)
@@ -33,12 +64,14 @@ impl ReadRoot for Root {
   async fn find_all(
     db: &impl ConnectionTrait,
     query: Option<Query>,
-    _limit: Option<u64>,
-    _sort: Option<()>,
+    limit: Option<u64>,
+    sort: Option<Sort>,
   ) -> crate::Result<Vec<Root>> {
-    let mut journal_ids: HashSet<_> =
-      query.iter().flat_map(|query| query.journal_id.iter().copied()).collect();
-    if let Some(query) = &query {
+    let query = query.unwrap_or_default();
+    let mut journal_ids = query.journal_id.clone();
+    // The journals of the IDs are used only if no journal is given, so that the IDs cannot reach
+    // out of the journals, such as the ones allowed to the actors
+    if journal_ids.is_empty() {
       for id in &query.id {
         if let Some((journal_id, _)) = id.split_once(REPORT_SPLITERATOR) {
           if let Ok(journal_id) = Uuid::parse_str(journal_id) {
@@ -48,179 +81,263 @@ impl ReadRoot for Root {
       }
     }
 
-    let entries = entry::Root::find_all(
+    let accounts: Vec<_> = account::Root::find_all(
       db,
-      Some(entry::Query {
+      Some(account::Query {
         journal_id: journal_ids.clone(),
-        start: query.as_ref().and_then(|query| query.start),
-        end: query.as_ref().and_then(|query| query.end),
-        typ: Some(entry::Type::Record),
+        tags: query.account_tags.clone(),
         ..Default::default()
       }),
       None,
       None,
     )
-    .await?;
+    .await?
+    .into_iter()
+    .filter(|account| query.account_type.is_empty() || query.account_type.contains(&account.typ))
+    .collect();
 
-    let accounts = account::Root::find_all(
-      db,
-      Some(account::Query { journal_id: journal_ids.clone(), ..Default::default() }),
-      None,
-      None,
-    )
-    .await?;
-
-    let aggregated = Self::do_aggregate_by_account(&entries, &accounts);
-    let aggregated = Self::do_aggregate(aggregated);
-
-    Ok(match query {
-      Some(query) if !query.id.is_empty() => aggregated
-        .into_iter()
-        .map(|root| (root.id(), root))
-        .filter(|(id, _)| query.id.contains(id))
-        .map(|(_, root)| root)
-        .collect::<Vec<_>>(),
-      _ => aggregated,
-    })
-  }
-}
+    let entry_type = query.entry_type.unwrap_or(entry::Type::Record);
+    let values = if entry_type == entry::Type::Record && query.entry_tags.is_empty() {
+      // Plain records can be read from the balance snapshots directly
+      let account_ids: HashSet<_> = accounts.iter().map(|account| account.id).collect();
+      let mut values = account_balance::find_balances(db, account_ids.clone(), query.end).await?;
+      if let Some(date) = query.start.and_then(|start| start.pred_opt()) {
+        for (account_id, opening) in
+          account_balance::find_balances(db, account_ids, Some(date)).await?
+        {
+          *values.entry(account_id).or_default() -= opening;
+        }
+      }
+      values
+    } else {
+      Self::select_values(entry::Query {
+        journal_id: journal_ids,
+        start: query.start,
+        end: query.end,
+        typ: Some(entry_type),
+        tags: query.entry_tags.clone(),
+        ..Default::default()
+      })
+      .into_tuple::<(Uuid, Option<Decimal>)>()
+      .all(db)
+      .await?
+      .into_iter()
+      .map(|(account_id, value)| (account_id, value.unwrap_or_default()))
+      .collect()
+    };
 
-#[derive(Hash, Debug, Clone, PartialEq, Eq)]
-struct Index {
-  pub journal_id: Uuid,
-  pub prefix: String,
-  pub unit: String,
-  pub account_id: Uuid,
+    let roots = Self::do_aggregate(&accounts, &values, query.max_depth, sort.unwrap_or(Sort::Name));
+
+    let roots = if query.id.is_empty() {
+      roots
+    } else {
+      let mut found = Vec::new();
+      Self::do_find_by_id(roots, &query.id, &mut found);
+      found
+    };
+
+    Ok(roots.into_iter().take(limit.map(|limit| limit as usize).unwrap_or(usize::MAX)).collect())
+  }
 }
 
 impl Root {
-  fn do_aggregate(aggregated: HashMap<Index, Decimal>) -> Vec<Root> {
-    let mut results = Vec::default();
-    for ((journal_id, prefix, unit), items) in
-      aggregated.into_iter().into_group_map_by(|(Index { journal_id, prefix, unit, .. }, _)| {
-        (*journal_id, prefix.to_string(), unit.to_string())
-      })
-    {
-      results.push(Root {
-        journal_id,
-        prefix,
-        unit,
-        values: items
-          .into_iter()
-          .map(|(Index { account_id, .. }, value)| (account_id, value))
-          .collect::<HashMap<_, _>>(),
-      })
+  fn do_find_by_id(roots: Vec<Root>, ids: &HashSet<String>, found: &mut Vec<Root>) {
+    for root in roots {
+      if ids.contains(&root.id()) {
+        found.push(root);
+      } else {
+        Self::do_find_by_id(root.children, ids, found);
+      }
     }
-
-    results
   }
 
-  fn do_aggregate_by_account(
-    entries: &[entry::Root],
+  fn do_aggregate(
     accounts: &[account::Root],
-  ) -> HashMap<Index, Decimal> {
-    let accounts = accounts.iter().map(|account| (account.id, account)).collect::<HashMap<_, _>>();
-
-    let mut results = HashMap::<Index, Decimal>::default();
-
-    for entry in entries {
-      for entry_item in &entry.items {
-        if let Some(account) = accounts.get(&entry_item.account) {
-          let mut prefixes = HashSet::<String>::default();
-          prefixes.insert(account.name.to_string());
-          for (idx, _) in account.name.match_indices(account::NAME_SPLITERATOR) {
-            prefixes.insert(account.name[0..idx].to_string());
-          }
-
-          for prefix in prefixes {
-            let map_index = Index {
-              journal_id: entry.journal_id,
-              prefix,
-              unit: account.unit.to_string(),
-              account_id: account.id,
-            };
-            let item = results.get(&map_index).copied().unwrap_or_default();
-            results.insert(map_index, item.add(entry_item.price.mul(entry_item.amount)));
-          }
-        }
+    values: &HashMap<Uuid, Decimal>,
+    max_depth: Option<usize>,
+    sort: Sort,
+  ) -> Vec<Root> {
+    let mut nodes = HashMap::<(Uuid, String, String), HashMap<Uuid, Decimal>>::new();
+    for account in accounts {
+      let segments: Vec<_> = account.name.split(account::NAME_SPLITERATOR).collect();
+      let depth = max_depth.map(|max| segments.len().min(max)).unwrap_or(segments.len());
+      for idx in 1..=depth {
+        nodes
+          .entry((
+            account.journal_id,
+            account.unit.clone(),
+            segments[0..idx].join(account::NAME_SPLITERATOR),
+          ))
+          .or_default()
+          .insert(account.id, values.get(&account.id).copied().unwrap_or_default());
       }
     }
 
-    results
+    let mut nodes_by_parent = nodes
+      .into_iter()
+      .map(|((journal_id, unit, prefix), values)| {
+        let (parent, name) = match prefix.rsplit_once(account::NAME_SPLITERATOR) {
+          Some((parent, name)) => (Some(parent.to_string()), name.to_string()),
+          None => (None, prefix.clone()),
+        };
+        let depth = prefix.matches(account::NAME_SPLITERATOR).count() + 1;
+        (
+          (journal_id, unit.clone(), parent),
+          Root {
+            journal_id,
+            prefix,
+            name,
+            unit,
+            depth,
+            value: values.values().sum(),
+            values,
+            children: vec![],
+          },
+        )
+      })
+      .into_group_map();
+
+    let mut roots: Vec<_> = nodes_by_parent
+      .iter()
+      .filter(|((_, _, parent), _)| parent.is_none())
+      .map(|(key, _)| key.clone())
+      .collect::<Vec<_>>()
+      .into_iter()
+      .flat_map(|key| nodes_by_parent.remove(&key).unwrap_or_default())
+      .collect();
+    for root in &mut roots {
+      Self::do_build_children(root, &mut nodes_by_parent, sort);
+    }
+    roots.sort_by(|a, b| sort.compare(a, b).then_with(|| a.journal_id.cmp(&b.journal_id)));
+    roots
+  }
+
+  fn do_build_children(
+    root: &mut Root,
+    nodes_by_parent: &mut HashMap<(Uuid, String, Option<String>), Vec<Root>>,
+    sort: Sort,
+  ) {
+    let mut children = nodes_by_parent
+      .remove(&(root.journal_id, root.unit.clone(), Some(root.prefix.clone())))
+      .unwrap_or_default();
+    for child in &mut children {
+      Self::do_build_children(child, nodes_by_parent, sort);
+    }
+    children.sort_by(|a, b| sort.compare(a, b));
+    root.children = children;
+  }
+
+  /// Sum up the values of each account in the database, for the queries the snapshots cannot answer.
+  /// SQLite does the arithmetic of the decimal columns in floating points, but sea-orm already
+  /// reads them as `f64` and the conversion into `Decimal` rounds away the error of the sums, so
+  /// they are as precise as the items they are summed from, the same as in `account_balance`
+  fn select_values(query: entry::Query) -> Select<entry_item::Entity> {
+    entry_item::Entity::find()
+      .select_only()
+      .column(entry_item::Column::AccountId)
+      .column_as(
+        SimpleExpr::from(Func::sum(
+          Expr::col((entry_item::Entity, entry_item::Column::Amount))
+            .mul(Expr::col((entry_item::Entity, entry_item::Column::Price))),
+        )),
+        "value",
+      )
+      .inner_join(entry::Entity)
+      .filter(query)
+      .group_by(entry_item::Column::AccountId)
   }
 }
 
+/// All the prefixes of the account name, including the name itself
+pub(crate) fn prefixes(name: &str) -> HashSet<String> {
+  let mut prefixes = HashSet::<String>::default();
+  prefixes.insert(name.to_string());
+  for (idx, _) in name.match_indices(account::NAME_SPLITERATOR) {
+    prefixes.insert(name[0..idx].to_string());
+  }
+  prefixes
+}
+
 #[cfg(test)]
 mod tests {
-  use crate::entity::hierarchy_report::Root;
+  use crate::entity::hierarchy_report::{Root, Sort};
   use crate::entity::{account, entry};
   use chrono::NaiveDate;
   use rust_decimal_macros::dec;
-  use sea_orm::Iterable;
-  use std::collections::HashSet;
-  use uuid::Uuid;
+  use sea_orm::{DbBackend, QueryTrait};
+  use std::collections::{HashMap, HashSet};
+  use uuid::{uuid, Uuid};
+
+  #[test]
+  fn test_select_values() -> anyhow::Result<()> {
+    let query = entry::Query {
+      journal_id: HashSet::from_iter([uuid!("50a1b556-b99d-4ae0-bfba-d117f9a958de")]),
+      typ: Some(entry::Type::Record),
+      end: Some(NaiveDate::from_ymd_opt(2023, 12, 31).unwrap()),
+      ..Default::default()
+    };
+
+    assert_eq!(
+      [r#"SELECT "entry_items"."account_id", SUM("entry_items"."amount" * "entry_items"."price") AS "value" FROM "entry_items""#,
+        r#"INNER JOIN "entries" ON "entry_items"."entry_id" = "entries"."id""#,
+        r#"WHERE "entries"."journal_id" IN ('50a1b556-b99d-4ae0-bfba-d117f9a958de') AND "entries"."type" = 'R' AND "entries"."date" <= '2023-12-31'"#,
+        r#"GROUP BY "entry_items"."account_id""#].join(" "),
+      Root::select_values(query).build(DbBackend::Sqlite).to_string()
+    );
+
+    Ok(())
+  }
 
   #[test]
   fn test_do_aggregate() -> anyhow::Result<()> {
     let journal_id = Uuid::new_v4();
-    let mut accounts = Vec::default();
-    for typ in account::Type::iter() {
-      let id = Uuid::new_v4();
-      accounts.push(account::Root {
-        id,
-        journal_id,
-        name: format!("Account::{}", typ),
-        description: "".to_string(),
-        unit: "CNY".to_string(),
-        typ,
-        tags: HashSet::default(),
-      });
-
-      let id = Uuid::new_v4();
-      accounts.push(account::Root {
-        id,
-        journal_id,
-        name: format!("Account::{}::Child", typ),
-        description: "".to_string(),
-        unit: "USD".to_string(),
-        typ,
-        tags: HashSet::default(),
-      });
-    }
+    let accounts: Vec<_> = [
+      "Assets::Bank::Checking",
+      "Assets::Bank::Saving",
+      "Assets::Cash",
+      "Expenses::Food",
+      "Expenses::Rent",
+    ]
+    .into_iter()
+    .map(|name| account::Root {
+      id: Uuid::new_v4(),
+      journal_id,
+      name: name.to_string(),
+      description: "".to_string(),
+      unit: "CNY".to_string(),
+      typ: if name.starts_with("Assets") { account::Type::Asset } else { account::Type::Expense },
+      tags: HashSet::default(),
+      version: 0,
+      created_at: Default::default(),
+      updated_at: Default::default(),
+    })
+    .collect();
+
+    let values = HashMap::from_iter([
+      (accounts[0].id, dec!(2.0)),
+      (accounts[1].id, dec!(12.0)),
+      (accounts[3].id, dec!(20.0)),
+    ]);
+    let roots = Root::do_aggregate(&accounts, &values, None, Sort::MinusValue);
+
+    assert_eq!(vec!["Expenses", "Assets"], roots.iter().map(|r| &r.name).collect::<Vec<_>>());
+    let assets = &roots[1];
+    assert_eq!(dec!(14.0), assets.value);
+    assert_eq!(3, assets.values.len());
+    assert_eq!(
+      vec!["Assets::Bank", "Assets::Cash"],
+      assets.children.iter().map(|r| &r.prefix).collect::<Vec<_>>()
+    );
+    assert_eq!(dec!(0), assets.children[1].value);
+    assert_eq!(
+      vec![("Assets::Bank::Saving", 3), ("Assets::Bank::Checking", 3)],
+      assets.children[0].children.iter().map(|r| (r.prefix.as_str(), r.depth)).collect::<Vec<_>>()
+    );
 
-    let entries = vec![
-      entry::Root {
-        id: Uuid::new_v4(),
-        journal_id,
-        name: "Entry: 1".to_string(),
-        description: "".to_string(),
-        typ: entry::Type::Record,
-        date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
-        tags: HashSet::default(),
-        items: vec![
-          entry::Item { account: accounts[0].id, amount: dec!(1.0), price: dec!(2.0) },
-          entry::Item { account: accounts[1].id, amount: dec!(3.0), price: dec!(4.0) },
-        ],
-      },
-      entry::Root {
-        id: Uuid::new_v4(),
-        journal_id,
-        name: "Entry: 2".to_string(),
-        description: "".to_string(),
-        typ: entry::Type::Record,
-        date: NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
-        tags: HashSet::default(),
-        items: vec![
-          entry::Item { account: accounts[0].id, amount: dec!(2.0), price: dec!(2.0) },
-          entry::Item { account: accounts[2].id, amount: dec!(1.0), price: dec!(3.0) },
-        ],
-      },
-    ];
-
-    let aggregated = Root::do_aggregate_by_account(&entries, &accounts);
-    println!("Result: {:#?}", aggregated);
-
-    println!("Aggregated: {:#?}", Root::do_aggregate(aggregated));
+    let roots = Root::do_aggregate(&accounts, &values, Some(1), Sort::Name);
+    assert_eq!(vec!["Assets", "Expenses"], roots.iter().map(|r| &r.name).collect::<Vec<_>>());
+    assert!(roots.iter().all(|root| root.children.is_empty()));
+    assert_eq!(dec!(14.0), roots[0].value);
 
     Ok(())
   }
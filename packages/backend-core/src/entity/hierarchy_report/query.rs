@@ -1,3 +1,4 @@
+use crate::entity::{account, entry, JournalQuery};
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -14,4 +15,22 @@ pub struct Query {
   pub start: Option<NaiveDate>,
   #[serde(default)]
   pub end: Option<NaiveDate>,
+  /// Nodes deeper than this are folded into their ancestors
+  #[serde(default)]
+  pub max_depth: Option<usize>,
+  #[serde(default)]
+  pub account_type: HashSet<account::Type>,
+  #[serde(default)]
+  pub account_tags: HashSet<String>,
+  #[serde(default)]
+  pub entry_tags: HashSet<String>,
+  /// `Record` by default
+  #[serde(default)]
+  pub entry_type: Option<entry::Type>,
+}
+
+impl JournalQuery for Query {
+  fn journal_ids(&mut self) -> &mut HashSet<Uuid> {
+    &mut self.journal_id
+  }
 }
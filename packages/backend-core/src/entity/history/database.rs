@@ -0,0 +1,27 @@
+use sea_orm::entity::prelude::*;
+
+/// Append-only, so that the records are kept even after the entities are deleted
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "histories")]
+pub struct Model {
+  #[sea_orm(primary_key, auto_increment = false)]
+  pub id: Uuid,
+  #[sea_orm(unique)]
+  pub sequence: i64,
+  #[sea_orm(indexed)]
+  pub command_id: Uuid,
+  pub entity: String,
+  #[sea_orm(indexed)]
+  pub entity_id: Uuid,
+  pub actor: String,
+  #[sea_orm(indexed)]
+  pub timestamp: DateTimeUtc,
+  pub command: Json,
+  pub before: Option<Json>,
+  pub after: Option<Json>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
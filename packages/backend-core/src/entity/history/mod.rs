@@ -0,0 +1,200 @@
+mod database;
+mod query;
+
+pub use database::*;
+pub use query::*;
+
+use crate::actor::Actor;
+use crate::entity::{journal, ReadRoot};
+use crate::error::ErrorInternal;
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use sea_orm::{
+  ColumnTrait, ConnectionTrait, EntityTrait, Order, QueryFilter, QueryOrder, QuerySelect,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+pub const TYPE: &str = "History";
+pub(crate) const FIELD_JOURNAL_ID: &str = "journalId";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sort {
+  #[serde(rename = "timestamp")]
+  Timestamp,
+  #[serde(rename = "-timestamp")]
+  MinusTimestamp,
+}
+
+impl From<Sort> for (Column, Order) {
+  fn from(value: Sort) -> Self {
+    match value {
+      Sort::Timestamp => (Column::Timestamp, Order::Asc),
+      Sort::MinusTimestamp => (Column::Timestamp, Order::Desc),
+    }
+  }
+}
+
+/// The change of one entity made by a handled command. The records of the same command share the
+/// `command_id`, and the snapshots are `None` before the creation and after the deletion
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Root {
+  pub id: Uuid,
+  /// Increases in the order the records are committed, so that the watchers resume from it
+  pub sequence: i64,
+  pub command_id: Uuid,
+  pub entity: String,
+  pub entity_id: Uuid,
+  pub actor: String,
+  pub timestamp: DateTime<Utc>,
+  pub command: serde_json::Value,
+  pub before: Option<serde_json::Value>,
+  pub after: Option<serde_json::Value>,
+}
+
+impl From<Model> for Root {
+  fn from(value: Model) -> Self {
+    Root {
+      id: value.id,
+      sequence: value.sequence,
+      command_id: value.command_id,
+      entity: value.entity,
+      entity_id: value.entity_id,
+      actor: value.actor,
+      timestamp: value.timestamp,
+      command: value.command,
+      before: value.before,
+      after: value.after,
+    }
+  }
+}
+
+impl ReadRoot for Root {
+  type Query = Query;
+  type Sort = Sort;
+
+  fn id(&self) -> String {
+    self.id.to_string()
+  }
+
+  async fn find_all(
+    db: &impl ConnectionTrait,
+    query: Option<Query>,
+    limit: Option<u64>,
+    sort: Option<Sort>,
+  ) -> crate::Result<Vec<Root>> {
+    let select =
+      if let Some(query) = query { Entity::find().filter(query) } else { Entity::find() };
+    let (field, order) = Into::<(Column, Order)>::into(sort.unwrap_or(Sort::Timestamp));
+    let models = select
+      .order_by(field, order.clone())
+      .order_by(Column::Sequence, order)
+      .limit(limit)
+      .all(db)
+      .await?;
+    Ok(models.into_iter().map(Root::from).collect())
+  }
+}
+
+impl Root {
+  /// The journal the entity belongs to, read from the snapshots unless it is a journal
+  pub fn journal_id(&self) -> Option<Uuid> {
+    if self.entity == journal::TYPE {
+      return Some(self.entity_id);
+    }
+    self
+      .after
+      .iter()
+      .chain(self.before.iter())
+      .filter_map(|value| value[FIELD_JOURNAL_ID].as_str())
+      .find_map(|id| Uuid::parse_str(id).ok())
+  }
+
+  /// The sequence of the last record, or 0 without any records
+  pub async fn find_last_sequence(db: &impl ConnectionTrait) -> crate::Result<i64> {
+    let sequence: Option<Option<i64>> = Entity::find()
+      .select_only()
+      .column_as(Column::Sequence.max(), "sequence")
+      .into_tuple()
+      .one(db)
+      .await?;
+    Ok(sequence.flatten().unwrap_or_default())
+  }
+
+  /// Find the records after the sequence in order, for the watchers to catch up with the changes
+  pub async fn find_after(
+    db: &impl ConnectionTrait,
+    query: Query,
+    sequence: i64,
+    limit: Option<u64>,
+  ) -> crate::Result<Vec<Root>> {
+    let models = Entity::find()
+      .filter(query)
+      .filter(Column::Sequence.gt(sequence))
+      .order_by(Column::Sequence, Order::Asc)
+      .limit(limit)
+      .all(db)
+      .await?;
+    Ok(models.into_iter().map(Root::from).collect())
+  }
+
+  /// Append the changes of the entities affected by the command, keyed by the entity IDs. The
+  /// entities of the other types cascaded by the same command are recorded with the same
+  /// `command_id`
+  pub(crate) async fn record<'a, R: Serialize + 'a>(
+    db: &impl ConnectionTrait,
+    command_id: Uuid,
+    entity: &str,
+    actor: &Actor,
+    command: &impl Serialize,
+    befores: impl IntoIterator<Item = (Uuid, &'a R)>,
+    afters: impl IntoIterator<Item = (Uuid, &'a R)>,
+  ) -> crate::Result<Vec<Root>> {
+    let befores: Vec<_> = befores.into_iter().collect();
+    let afters: Vec<_> = afters.into_iter().collect();
+    let entity_ids: Vec<_> =
+      befores.iter().chain(afters.iter()).map(|(entity_id, _)| *entity_id).unique().collect();
+    if entity_ids.is_empty() {
+      return Ok(Vec::default());
+    }
+
+    let mut befores = to_values(befores)?;
+    let mut afters = to_values(afters)?;
+    let command = serde_json::to_value(command).map_err(ErrorInternal::from)?;
+    let timestamp = Utc::now();
+    // Read in the same transaction as the insertion, which the unique index guards against the
+    // concurrent ones
+    let last = Self::find_last_sequence(db).await?;
+
+    let models: Vec<_> = entity_ids
+      .into_iter()
+      .zip(last + 1..)
+      .map(|(entity_id, sequence)| Model {
+        id: Uuid::new_v4(),
+        sequence,
+        command_id,
+        entity: entity.to_string(),
+        entity_id,
+        actor: actor.to_string(),
+        timestamp,
+        command: command.clone(),
+        before: befores.remove(&entity_id),
+        after: afters.remove(&entity_id),
+      })
+      .collect();
+    Entity::insert_many(models.iter().cloned().map(ActiveModel::from)).exec(db).await?;
+    Ok(models.into_iter().map(Root::from).collect())
+  }
+}
+
+fn to_values<R: Serialize>(
+  roots: Vec<(Uuid, &R)>,
+) -> crate::Result<HashMap<Uuid, serde_json::Value>> {
+  let mut values = HashMap::new();
+  for (entity_id, root) in roots {
+    values.insert(entity_id, serde_json::to_value(root).map_err(ErrorInternal::from)?);
+  }
+  Ok(values)
+}
@@ -0,0 +1,80 @@
+use crate::entity::history;
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Cond, IntoCondition};
+use sea_orm::Condition;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Query {
+  #[serde(default)]
+  pub id: HashSet<Uuid>,
+  #[serde(default)]
+  pub command_id: HashSet<Uuid>,
+  #[serde(default)]
+  pub entity: HashSet<String>,
+  #[serde(default)]
+  pub entity_id: HashSet<Uuid>,
+  #[serde(default)]
+  pub actor: HashSet<String>,
+}
+
+impl IntoCondition for Query {
+  fn into_condition(self) -> Condition {
+    let mut cond = Cond::all();
+
+    if !self.id.is_empty() {
+      cond = cond.add(history::Column::Id.is_in(self.id));
+    }
+
+    if !self.command_id.is_empty() {
+      cond = cond.add(history::Column::CommandId.is_in(self.command_id));
+    }
+
+    if !self.entity.is_empty() {
+      cond = cond.add(history::Column::Entity.is_in(self.entity));
+    }
+
+    if !self.entity_id.is_empty() {
+      cond = cond.add(history::Column::EntityId.is_in(self.entity_id));
+    }
+
+    if !self.actor.is_empty() {
+      cond = cond.add(history::Column::Actor.is_in(self.actor));
+    }
+
+    cond
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::entity::history;
+  use sea_orm::{DbBackend, EntityTrait, QueryFilter, QueryTrait};
+  use std::collections::HashSet;
+  use uuid::uuid;
+
+  #[test]
+  fn test_query() -> anyhow::Result<()> {
+    let query = history::Query {
+      id: HashSet::default(),
+      command_id: HashSet::default(),
+      entity: HashSet::from_iter(["Entry".to_string()]),
+      entity_id: HashSet::from_iter([uuid!("50a1b556-b99d-4ae0-bfba-d117f9a958de")]),
+      actor: HashSet::from_iter(["someone".to_string()]),
+    };
+
+    assert_eq!(
+      [r#"SELECT "histories"."id", "histories"."sequence", "histories"."command_id", "histories"."entity", "histories"."entity_id", "histories"."actor","#,
+        r#""histories"."timestamp", "histories"."command", "histories"."before", "histories"."after" FROM "histories""#,
+        r#"WHERE "histories"."entity" IN ('Entry')"#,
+        r#"AND "histories"."entity_id" IN ('50a1b556-b99d-4ae0-bfba-d117f9a958de')"#,
+        r#"AND "histories"."actor" IN ('someone')"#].join(" "),
+      history::Entity::find().filter(query).build(DbBackend::Sqlite).to_string()
+    );
+
+    Ok(())
+  }
+}
@@ -0,0 +1,50 @@
+use crate::entity::{account, entry};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+/// The transaction IDs given by the banks, such as the FITID of OFX, to import each only once
+#[derive(Clone, Debug, Hash, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "imported_transactions")]
+pub struct Model {
+  #[sea_orm(primary_key)]
+  pub account_id: Uuid,
+  #[sea_orm(primary_key)]
+  pub external_id: String,
+  #[sea_orm(indexed)]
+  pub entry_id: Uuid,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+  #[sea_orm(
+    belongs_to = "account::Entity",
+    from = "Column::AccountId",
+    to = "account::Column::Id",
+    on_update = "Cascade",
+    on_delete = "Cascade"
+  )]
+  Account,
+
+  #[sea_orm(
+    belongs_to = "entry::Entity",
+    from = "Column::EntryId",
+    to = "entry::Column::Id",
+    on_update = "Cascade",
+    on_delete = "Cascade"
+  )]
+  Entry,
+}
+
+impl Related<account::Entity> for Entity {
+  fn to() -> RelationDef {
+    Relation::Account.def()
+  }
+}
+
+impl Related<entry::Entity> for Entity {
+  fn to() -> RelationDef {
+    Relation::Entry.def()
+  }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,217 @@
+mod query;
+
+pub use query::*;
+
+use crate::entity::{account, entry, journal, ReadRoot, REPORT_SPLITERATOR};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sea_orm::ConnectionTrait;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Root {
+  pub journal_id: Uuid,
+  pub start: Option<NaiveDate>,
+  pub end: Option<NaiveDate>,
+  pub unit: String,
+  pub income: HashMap<Uuid, Decimal>,
+  pub expense: HashMap<Uuid, Decimal>,
+  pub total_income: Decimal,
+  pub total_expense: Decimal,
+  pub net_income: Decimal,
+}
+
+impl ReadRoot for Root {
+  type Query = Query;
+  type Sort = ();
+
+  fn id(&self) -> String {
+    [
+      self.journal_id.to_string(),
+      self.start.map(|date| date.to_string()).unwrap_or_default(),
+      self.end.map(|date| date.to_string()).unwrap_or_default(),
+    ]
+    .join(REPORT_SPLITERATOR)
+  }
+
+  async fn find_all(
+    db: &impl ConnectionTrait,
+    query: Option<Query>,
+    limit: Option<u64>,
+    _sort: Option<()>,
+  ) -> crate::Result<Vec<Root>> {
+    let query = query.unwrap_or_default();
+
+    let journals = journal::Root::find_all(
+      db,
+      Some(journal::Query { id: query.journal_id.clone(), ..Default::default() }),
+      limit,
+      None,
+    )
+    .await?;
+    let journal_ids: HashSet<_> = journals.iter().map(|journal| journal.id).collect();
+
+    let entries = entry::Root::find_all(
+      db,
+      Some(entry::Query {
+        journal_id: journal_ids.clone(),
+        start: query.start,
+        end: query.end,
+        typ: Some(entry::Type::Record),
+        ..Default::default()
+      }),
+      None,
+      None,
+    )
+    .await?;
+
+    let accounts = account::Root::find_all(
+      db,
+      Some(account::Query { journal_id: journal_ids, ..Default::default() }),
+      None,
+      None,
+    )
+    .await?;
+
+    Ok(
+      journals
+        .iter()
+        .map(|journal| Self::do_aggregate(journal, query.start, query.end, &entries, &accounts))
+        .collect(),
+    )
+  }
+}
+
+impl Root {
+  fn do_aggregate(
+    journal: &journal::Root,
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+    entries: &[entry::Root],
+    accounts: &[account::Root],
+  ) -> Root {
+    let accounts = accounts
+      .iter()
+      .filter(|account| account.journal_id == journal.id)
+      .map(|account| (account.id, account))
+      .collect::<HashMap<_, _>>();
+
+    let mut income = HashMap::<Uuid, Decimal>::new();
+    let mut expense = HashMap::<Uuid, Decimal>::new();
+
+    for entry in entries.iter().filter(|entry| entry.journal_id == journal.id) {
+      for item in &entry.items {
+        let values = match accounts.get(&item.account).map(|account| account.typ) {
+          Some(account::Type::Income) => &mut income,
+          Some(account::Type::Expense) => &mut expense,
+          _ => continue,
+        };
+        *values.entry(item.account).or_default() += item.amount * item.price;
+      }
+    }
+
+    let total_income: Decimal = income.values().sum();
+    let total_expense: Decimal = expense.values().sum();
+
+    Root {
+      journal_id: journal.id,
+      start,
+      end,
+      unit: journal.unit.clone(),
+      income,
+      expense,
+      total_income,
+      total_expense,
+      net_income: total_income - total_expense,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::entity::income_statement::Root;
+  use crate::entity::{account, entry, journal};
+  use chrono::NaiveDate;
+  use rust_decimal_macros::dec;
+  use std::collections::HashSet;
+  use uuid::Uuid;
+
+  #[test]
+  fn test_do_aggregate() -> anyhow::Result<()> {
+    let journal = journal::Root {
+      id: Uuid::new_v4(),
+      name: "Journal 1".to_string(),
+      description: "".to_string(),
+      unit: "CNY".to_string(),
+      tags: HashSet::default(),
+      version: 0,
+      created_at: Default::default(),
+      updated_at: Default::default(),
+    };
+    let accounts: Vec<_> = [account::Type::Income, account::Type::Expense, account::Type::Asset]
+      .into_iter()
+      .map(|typ| account::Root {
+        id: Uuid::new_v4(),
+        journal_id: journal.id,
+        name: format!("Account::{}", typ),
+        description: "".to_string(),
+        unit: "CNY".to_string(),
+        typ,
+        tags: HashSet::default(),
+        version: 0,
+        created_at: Default::default(),
+        updated_at: Default::default(),
+      })
+      .collect();
+
+    let entries = vec![
+      entry::Root {
+        id: Uuid::new_v4(),
+        journal_id: journal.id,
+        name: "Entry: 1".to_string(),
+        description: "".to_string(),
+        typ: entry::Type::Record,
+        date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+        tags: HashSet::default(),
+        items: vec![
+          entry::Item { account: accounts[0].id, amount: dec!(100.0), price: dec!(1.0) },
+          entry::Item { account: accounts[2].id, amount: dec!(100.0), price: dec!(1.0) },
+        ],
+        payee_id: None,
+        version: 0,
+        created_at: Default::default(),
+        updated_at: Default::default(),
+      },
+      entry::Root {
+        id: Uuid::new_v4(),
+        journal_id: journal.id,
+        name: "Entry: 2".to_string(),
+        description: "".to_string(),
+        typ: entry::Type::Record,
+        date: NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(),
+        tags: HashSet::default(),
+        items: vec![
+          entry::Item { account: accounts[1].id, amount: dec!(15.0), price: dec!(2.0) },
+          entry::Item { account: accounts[0].id, amount: dec!(30.0), price: dec!(1.0) },
+        ],
+        payee_id: None,
+        version: 0,
+        created_at: Default::default(),
+        updated_at: Default::default(),
+      },
+    ];
+
+    let root = Root::do_aggregate(&journal, None, None, &entries, &accounts);
+
+    assert_eq!(dec!(130.0), root.total_income);
+    assert_eq!(dec!(30.0), root.total_expense);
+    assert_eq!(dec!(100.0), root.net_income);
+    assert_eq!(Some(&dec!(130.0)), root.income.get(&accounts[0].id));
+    assert_eq!(Some(&dec!(30.0)), root.expense.get(&accounts[1].id));
+
+    Ok(())
+  }
+}
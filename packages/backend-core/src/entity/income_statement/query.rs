@@ -0,0 +1,22 @@
+use crate::entity::JournalQuery;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Query {
+  #[serde(default)]
+  pub journal_id: HashSet<Uuid>,
+  #[serde(default)]
+  pub start: Option<NaiveDate>,
+  #[serde(default)]
+  pub end: Option<NaiveDate>,
+}
+
+impl JournalQuery for Query {
+  fn journal_ids(&mut self) -> &mut HashSet<Uuid> {
+    &mut self.journal_id
+  }
+}
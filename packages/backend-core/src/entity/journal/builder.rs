@@ -1,5 +1,6 @@
 use crate::entity::journal::Root;
 use crate::entity::{normalize_description, normalize_name, normalize_tags, normalize_unit};
+use chrono::{DateTime, Utc};
 use std::collections::HashSet;
 use uuid::Uuid;
 
@@ -10,6 +11,9 @@ pub struct Builder {
   description: String,
   unit: String,
   tags: HashSet<String>,
+  version: i32,
+  created_at: DateTime<Utc>,
+  updated_at: DateTime<Utc>,
 }
 
 impl From<Root> for Builder {
@@ -20,6 +24,9 @@ impl From<Root> for Builder {
       description: value.description,
       unit: value.unit,
       tags: value.tags,
+      version: value.version,
+      created_at: value.created_at,
+      updated_at: value.updated_at,
     }
   }
 }
@@ -30,7 +37,16 @@ impl Builder {
     let description = normalize_description(crate::entity::journal::TYPE, self.description)?;
     let unit = normalize_unit(crate::entity::journal::TYPE, self.unit)?;
     let tags = normalize_tags(crate::entity::journal::TYPE, self.tags)?;
-    Ok(Root { id: self.id.unwrap_or_else(Uuid::new_v4), name, description, unit, tags })
+    Ok(Root {
+      id: self.id.unwrap_or_else(Uuid::new_v4),
+      name,
+      description,
+      unit,
+      tags,
+      version: self.version,
+      created_at: self.created_at,
+      updated_at: self.updated_at,
+    })
   }
 
   pub fn id(self, id: Uuid) -> Builder {
@@ -52,4 +68,9 @@ impl Builder {
   pub fn tags(self, tags: impl IntoIterator<Item = impl ToString>) -> Builder {
     Builder { tags: tags.into_iter().map(|s| s.to_string()).collect(), ..self }
   }
+
+  /// Keep the time the root is created at when restoring it, rather than taking the one saved at
+  pub fn created_at(self, created_at: DateTime<Utc>) -> Builder {
+    Builder { created_at, ..self }
+  }
 }
@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -15,6 +15,20 @@ pub enum Command {
   Batch(CommandBatch),
 }
 
+impl Command {
+  /// The IDs of the existing entities the command refers to
+  pub fn ids(&self) -> HashSet<Uuid> {
+    match self {
+      Command::Create(_) => HashSet::default(),
+      Command::Update(command) => HashSet::from_iter([command.id]),
+      Command::Delete(command) => command.id.clone(),
+      Command::Batch(command) => {
+        command.update.iter().map(|command| command.id).chain(command.delete.clone()).collect()
+      }
+    }
+  }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CommandCreate {
   pub name: String,
@@ -26,6 +40,7 @@ pub struct CommandCreate {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct CommandUpdate {
   pub id: Uuid,
   #[serde(default)]
@@ -36,12 +51,20 @@ pub struct CommandUpdate {
   pub unit: String,
   #[serde(default)]
   pub tags: Option<HashSet<String>>,
+  /// Conflicts if the entity has been saved since read at this version
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub expected_version: Option<i32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct CommandDelete {
   #[serde(default)]
   pub id: HashSet<Uuid>,
+  /// The versions the entities are expected at, keyed by the IDs. The ones missing are deleted
+  /// whatever the versions are
+  #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+  pub expected_version: HashMap<Uuid, i32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -12,6 +12,9 @@ pub struct Model {
   pub description: String,
   #[sea_orm(indexed)]
   pub unit: String,
+  pub version: i32,
+  pub created_at: DateTimeUtc,
+  pub updated_at: DateTimeUtc,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
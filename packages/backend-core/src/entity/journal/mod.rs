@@ -8,8 +8,15 @@ pub use command::*;
 pub use database::*;
 pub use query::*;
 
-use crate::entity::{journal_tag, ReadRoot, WriteRoot, FIELD_ID, FIELD_NAME};
-use crate::error::{ErrorExistingEntity, ErrorNotFound};
+use crate::actor::Actor;
+use crate::entity::membership::Role;
+use crate::entity::{
+  account, check_version, check_versions, created_at, entry, history, journal_tag, membership,
+  next_version, payee, ReadRoot, WriteRoot, FIELD_ID, FIELD_NAME,
+};
+use crate::error::{ErrorConflict, ErrorExistingEntity, ErrorNotFound};
+use crate::undo::Inverse;
+use chrono::{DateTime, Utc};
 use itertools::Itertools;
 use sea_orm::entity::prelude::*;
 use sea_orm::sea_query::{BinOper, OnConflict};
@@ -44,12 +51,21 @@ impl From<Sort> for (Column, Order) {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
 pub struct Root {
   pub id: Uuid,
   pub name: String,
   pub description: String,
   pub unit: String,
   pub tags: HashSet<String>,
+  /// Increased on every save, for the commands to expect the one they have read
+  #[serde(default)]
+  pub version: i32,
+  /// Kept as it is on the saves, unless the root is new
+  #[serde(default)]
+  pub created_at: DateTime<Utc>,
+  #[serde(default)]
+  pub updated_at: DateTime<Utc>,
 }
 
 impl ReadRoot for Root {
@@ -96,6 +112,9 @@ impl WriteRoot for Root {
         description: model.description,
         unit: model.unit,
         tags: HashSet::default(),
+        version: model.version,
+        created_at: model.created_at,
+        updated_at: model.updated_at,
       });
       ids.insert(model.id);
     }
@@ -127,11 +146,12 @@ impl WriteRoot for Root {
       return Ok(roots);
     }
 
+    let now = Utc::now();
     let mut model_ids = HashSet::new();
     let mut models: Vec<ActiveModel> = vec![];
     let mut tags: Vec<journal_tag::ActiveModel> = vec![];
 
-    for ref root in roots {
+    for root in &roots {
       model_ids.insert(root.id);
       models.push(
         Model {
@@ -139,6 +159,9 @@ impl WriteRoot for Root {
           name: root.name.to_string(),
           description: root.description.to_string(),
           unit: root.unit.to_string(),
+          version: root.version + 1,
+          created_at: created_at(root.created_at, now),
+          updated_at: now,
         }
         .into_active_model(),
       );
@@ -154,6 +177,17 @@ impl WriteRoot for Root {
       .exec(db)
       .await?;
 
+    let currents: HashMap<Uuid, i32> = Entity::find()
+      .select_only()
+      .columns([Column::Id, Column::Version])
+      .filter(Column::Id.is_in(model_ids.clone()))
+      .into_tuple()
+      .all(db)
+      .await?
+      .into_iter()
+      .collect();
+    check_versions(TYPE, &roots, |root| (root.id, root.version), &currents)?;
+
     // Update unique column name to temp value
     Entity::update_many()
       .col_expr(
@@ -165,8 +199,23 @@ impl WriteRoot for Root {
       .await?;
 
     let mut on_conflict = OnConflict::column(Column::Id);
-    on_conflict.update_columns([Column::Name, Column::Description, Column::Unit]);
-    Entity::insert_many(models).on_conflict(on_conflict).exec(db).await?;
+    on_conflict
+      .update_columns([
+        Column::Name,
+        Column::Description,
+        Column::Unit,
+        Column::Version,
+        Column::UpdatedAt,
+      ])
+      .action_and_where(next_version(Entity, Column::Version));
+    let saved_count =
+      Entity::insert_many(models).on_conflict(on_conflict).exec_without_returning(db).await?;
+    if saved_count != roots.len() as u64 {
+      return Err(crate::Error::Conflict(ErrorConflict {
+        entity: TYPE.to_string(),
+        values: Vec::default(),
+      }));
+    }
 
     if !tags.is_empty() {
       journal_tag::Entity::insert_many(tags).exec(db).await?;
@@ -185,11 +234,89 @@ impl WriteRoot for Root {
 }
 
 impl Root {
-  pub async fn handle(db: &impl ConnectionTrait, command: Command) -> crate::Result<Vec<Root>> {
-    match command {
+  /// Handle the command, and record the changes of the affected entities in the history on behalf
+  /// of the actor. Anyone is allowed to create the journals, becoming the owner of them, while only
+  /// the editors are allowed to update and the owners to delete
+  pub async fn handle(
+    db: &impl ConnectionTrait,
+    actor: &Actor,
+    command: Command,
+  ) -> crate::Result<Vec<Root>> {
+    Ok(Self::handle_with_inverse(db, actor, command).await?.0)
+  }
+
+  /// Handle the command the same as [`Root::handle`], and return the inverse to undo it as well
+  pub async fn handle_with_inverse(
+    db: &impl ConnectionTrait,
+    actor: &Actor,
+    command: Command,
+  ) -> crate::Result<(Vec<Root>, Inverse)> {
+    let ids = command.ids();
+    let befores = if ids.is_empty() {
+      Vec::default()
+    } else {
+      Self::find_all(db, Some(Query { id: ids, ..Default::default() }), None, None).await?
+    };
+    let deleted_ids = match &command {
+      Command::Delete(command) => command.id.clone(),
+      Command::Batch(command) => command.delete.clone(),
+      _ => HashSet::default(),
+    };
+    actor
+      .check_journals(
+        db,
+        befores.iter().map(|root| root.id).filter(|id| !deleted_ids.contains(id)),
+        Role::Editor,
+      )
+      .await?;
+    actor.check_journals(db, deleted_ids.iter().copied(), Role::Owner).await?;
+    let cascaded_memberships = if deleted_ids.is_empty() {
+      Vec::default()
+    } else {
+      membership::Root::find_all(
+        db,
+        Some(membership::Query { journal_id: deleted_ids.clone(), ..Default::default() }),
+        None,
+        None,
+      )
+      .await?
+    };
+    let (cascaded_accounts, cascaded_payees, cascaded_entries) = if deleted_ids.is_empty() {
+      (Vec::default(), Vec::default(), Vec::default())
+    } else {
+      (
+        account::Root::find_all(
+          db,
+          Some(account::Query { journal_id: deleted_ids.clone(), ..Default::default() }),
+          None,
+          None,
+        )
+        .await?,
+        payee::Root::find_all(
+          db,
+          Some(payee::Query { journal_id: deleted_ids.clone(), ..Default::default() }),
+          None,
+          None,
+        )
+        .await?,
+        entry::Root::find_all(
+          db,
+          Some(entry::Query { journal_id: deleted_ids, ..Default::default() }),
+          None,
+          None,
+        )
+        .await?,
+      )
+    };
+
+    let roots = match command.clone() {
       Command::Create(command) => Self::create(db, vec![command]).await,
       Command::Update(command) => Self::update(db, vec![command]).await,
-      Command::Delete(CommandDelete { id }) => {
+      Command::Delete(CommandDelete { id, expected_version }) => {
+        for (entity_id, expected) in expected_version {
+          let current = befores.iter().find(|root| root.id == entity_id).map(|root| root.version);
+          check_version(TYPE, entity_id, Some(expected), current)?;
+        }
         Self::delete(db, id).await?;
         Ok(Vec::default())
       }
@@ -208,7 +335,77 @@ impl Root {
 
         Self::find_all(db, Some(Query { id: ids, ..Default::default() }), None, None).await
       }
-    }
+    }?;
+
+    let created_memberships = if let Actor::User(user_id) = actor {
+      let created = roots.iter().filter(|root| befores.iter().all(|before| before.id != root.id));
+      membership::Root::save(
+        db,
+        created.map(|root| membership::Root {
+          journal_id: root.id,
+          user_id: *user_id,
+          role: Role::Owner,
+        }),
+      )
+      .await?
+    } else {
+      Vec::default()
+    };
+
+    // The accounts, the entries and the memberships deleted along with the journals are recorded
+    // before them
+    let command_id = Uuid::new_v4();
+    history::Root::record(
+      db,
+      command_id,
+      entry::TYPE,
+      actor,
+      &command,
+      cascaded_entries.iter().map(|root| (root.id, root)),
+      [],
+    )
+    .await?;
+    history::Root::record(
+      db,
+      command_id,
+      account::TYPE,
+      actor,
+      &command,
+      cascaded_accounts.iter().map(|root| (root.id, root)),
+      [],
+    )
+    .await?;
+    membership::Root::record(
+      db,
+      command_id,
+      actor,
+      &command,
+      &cascaded_memberships,
+      &created_memberships,
+    )
+    .await?;
+    history::Root::record(
+      db,
+      command_id,
+      TYPE,
+      actor,
+      &command,
+      befores.iter().map(|root| (root.id, root)),
+      roots.iter().map(|root| (root.id, root)),
+    )
+    .await?;
+
+    let mut inverse = Inverse::default();
+    // The payees are restored after the accounts they default to, and before the entries
+    inverse.record(entry::TYPE, cascaded_entries.iter().map(|root| (root.id, root)), [])?;
+    inverse.record(payee::TYPE, cascaded_payees.iter().map(|root| (root.id, root)), [])?;
+    inverse.record(account::TYPE, cascaded_accounts.iter().map(|root| (root.id, root)), [])?;
+    inverse.record(
+      TYPE,
+      befores.iter().map(|root| (root.id, root)),
+      roots.iter().map(|root| (root.id, root)),
+    )?;
+    Ok((roots, inverse))
   }
 
   pub async fn create(
@@ -314,6 +511,7 @@ impl Root {
           values: vec![(FIELD_ID.to_string(), command.id.to_string())],
         })
       })?;
+      check_version(TYPE, model.id, command.expected_version, Some(model.version))?;
 
       if command.name.is_empty()
         && command.description.is_none()
@@ -382,7 +580,7 @@ mod tests {
 
     assert_eq!(
       Entity::find().order_by(field, order).build(DatabaseBackend::Sqlite).to_string(),
-      r#"SELECT "journals"."id", "journals"."name", "journals"."description", "journals"."unit" FROM "journals" ORDER BY "journals"."name" ASC"#
+      r#"SELECT "journals"."id", "journals"."name", "journals"."description", "journals"."unit", "journals"."version", "journals"."created_at", "journals"."updated_at" FROM "journals" ORDER BY "journals"."name" ASC"#
     );
 
     Ok(())
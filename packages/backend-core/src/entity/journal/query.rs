@@ -1,4 +1,5 @@
-use crate::entity::{journal, journal_tag};
+use crate::entity::{journal, journal_tag, JournalQuery};
+use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use sea_orm::sea_query::{Cond, Func, IntoCondition};
 use sea_orm::{Condition, QuerySelect, QueryTrait};
@@ -19,6 +20,15 @@ pub struct Query {
   pub tags: HashSet<String>,
   #[serde(default)]
   pub full_text: String,
+  /// The ones saved at or after the time
+  #[serde(default)]
+  pub updated_since: Option<DateTime<Utc>>,
+}
+
+impl JournalQuery for Query {
+  fn journal_ids(&mut self) -> &mut HashSet<Uuid> {
+    &mut self.id
+  }
 }
 
 impl IntoCondition for Query {
@@ -96,6 +106,10 @@ impl IntoCondition for Query {
       cond = cond.add(sub_cond);
     }
 
+    if let Some(updated_since) = self.updated_since {
+      cond = cond.add(journal::Column::UpdatedAt.gte(updated_since));
+    }
+
     cond
   }
 }
@@ -114,15 +128,17 @@ mod tests {
       name: HashSet::from_iter(["Name 1".to_string(), "".to_string(), "  ".to_string()]),
       unit: "Unit 1".to_string(),
       full_text: "Keyword  ".to_string(),
+      updated_since: Some("2023-01-01T00:00:00Z".parse()?),
       ..Default::default()
     };
 
     assert_eq!(
-      [r#"SELECT "journals"."id", "journals"."name", "journals"."description", "journals"."unit" FROM "journals""#,
+      [r#"SELECT "journals"."id", "journals"."name", "journals"."description", "journals"."unit", "journals"."version", "journals"."created_at", "journals"."updated_at" FROM "journals""#,
         r#"WHERE "journals"."id" IN ('50a1b556-b99d-4ae0-bfba-d117f9a958de')"#,
         r#"AND "journals"."name" IN ('Name 1') AND "journals"."unit" = 'Unit 1'"#,
         r#"AND (LOWER("journals"."name") LIKE '%keyword%' OR LOWER("journals"."description") LIKE '%keyword%'"#,
-        r#"OR "journals"."id" IN (SELECT DISTINCT "journal_tags"."journal_id" FROM "journal_tags" WHERE LOWER("journal_tags"."tag") LIKE '%keyword%'))"#].join(" "),
+        r#"OR "journals"."id" IN (SELECT DISTINCT "journal_tags"."journal_id" FROM "journal_tags" WHERE LOWER("journal_tags"."tag") LIKE '%keyword%'))"#,
+        r#"AND "journals"."updated_at" >= '2023-01-01 00:00:00.000000 +00:00'"#].join(" "),
       journal::Entity::find().filter(query).build(DbBackend::Sqlite).to_string()
     );
 
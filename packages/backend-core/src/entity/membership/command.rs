@@ -0,0 +1,28 @@
+use crate::entity::membership::Role;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "commandType")]
+pub enum Command {
+  #[serde(rename = "memberships:grant")]
+  Grant(CommandGrant),
+  #[serde(rename = "memberships:revoke")]
+  Revoke(CommandRevoke),
+}
+
+/// Add the user to the journal, or change the role of the member
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandGrant {
+  pub journal_id: Uuid,
+  pub user_id: Uuid,
+  pub role: Role,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandRevoke {
+  pub journal_id: Uuid,
+  pub user_id: Uuid,
+}
@@ -0,0 +1,76 @@
+use crate::entity::{journal, user};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "memberships")]
+pub struct Model {
+  #[sea_orm(primary_key)]
+  pub journal_id: Uuid,
+  #[sea_orm(primary_key, indexed)]
+  pub user_id: Uuid,
+  pub role: Role,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+  #[sea_orm(
+    belongs_to = "journal::Entity",
+    from = "Column::JournalId",
+    to = "journal::Column::Id",
+    on_update = "Cascade",
+    on_delete = "Cascade"
+  )]
+  Journal,
+  #[sea_orm(
+    belongs_to = "user::Entity",
+    from = "Column::UserId",
+    to = "user::Column::Id",
+    on_update = "Cascade",
+    on_delete = "Cascade"
+  )]
+  User,
+}
+
+impl Related<journal::Entity> for Entity {
+  fn to() -> RelationDef {
+    Relation::Journal.def()
+  }
+}
+
+impl Related<user::Entity> for Entity {
+  fn to() -> RelationDef {
+    Relation::User.def()
+  }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// What the members are allowed in the journals, each role including the ones before it
+#[derive(
+  Debug,
+  Copy,
+  Clone,
+  Hash,
+  Eq,
+  PartialEq,
+  Ord,
+  PartialOrd,
+  strum_macros::Display,
+  Serialize,
+  Deserialize,
+  EnumIter,
+  DeriveActiveEnum,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(1))")]
+pub enum Role {
+  /// Reading the journals and everything in them
+  #[sea_orm(string_value = "V")]
+  Viewer,
+  /// Changing the journals, the accounts and the entries
+  #[sea_orm(string_value = "E")]
+  Editor,
+  /// Deleting the journals, and granting the roles to the others
+  #[sea_orm(string_value = "O")]
+  Owner,
+}
@@ -0,0 +1,207 @@
+mod command;
+mod database;
+mod query;
+
+pub use command::*;
+pub use database::*;
+pub use query::*;
+
+use crate::actor::Actor;
+use crate::entity::{history, journal, user, ReadRoot, FIELD_ID};
+use crate::error::{ErrorNotFound, ErrorRequiredField};
+use itertools::Itertools;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+  ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel, QueryFilter, QuerySelect,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+pub const TYPE: &str = "Membership";
+pub const FIELD_OWNER: &str = "owner";
+
+/// The role of a user in a journal, the only way for the users to access the journals
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Root {
+  pub journal_id: Uuid,
+  pub user_id: Uuid,
+  pub role: Role,
+}
+
+impl From<Model> for Root {
+  fn from(value: Model) -> Self {
+    Root { journal_id: value.journal_id, user_id: value.user_id, role: value.role }
+  }
+}
+
+impl ReadRoot for Root {
+  type Query = Query;
+  type Sort = ();
+
+  fn id(&self) -> String {
+    format!("{}:{}", self.journal_id, self.user_id)
+  }
+
+  async fn find_all(
+    db: &impl ConnectionTrait,
+    query: Option<Query>,
+    limit: Option<u64>,
+    _sort: Option<()>,
+  ) -> crate::Result<Vec<Root>> {
+    let select =
+      if let Some(query) = query { Entity::find().filter(query) } else { Entity::find() };
+    let models = select.limit(limit).all(db).await?;
+    Ok(models.into_iter().map(Root::from).collect())
+  }
+}
+
+impl Root {
+  /// Handle the command, and record the change in the history on behalf of the actor. The owners
+  /// of the journal are allowed to only, and the journals are kept with at least one owner
+  pub async fn handle(
+    db: &impl ConnectionTrait,
+    actor: &Actor,
+    command: Command,
+  ) -> crate::Result<Vec<Root>> {
+    let (journal_id, user_id) = match &command {
+      Command::Grant(command) => (command.journal_id, command.user_id),
+      Command::Revoke(command) => (command.journal_id, command.user_id),
+    };
+    actor.check_journals(db, [journal_id], Role::Owner).await?;
+    Self::check_existing(db, journal_id, user_id).await?;
+
+    let memberships = Self::find_all(
+      db,
+      Some(Query { journal_id: HashSet::from_iter([journal_id]), ..Default::default() }),
+      None,
+      None,
+    )
+    .await?;
+    let owners: HashSet<_> = memberships
+      .iter()
+      .filter(|membership| membership.role == Role::Owner)
+      .map(|membership| membership.user_id)
+      .collect();
+    let removes_owner = match &command {
+      Command::Grant(command) => command.role != Role::Owner,
+      Command::Revoke(_) => true,
+    };
+    if removes_owner && owners.len() == 1 && owners.contains(&user_id) {
+      return Err(crate::Error::RequiredField(ErrorRequiredField {
+        entity: journal::TYPE.to_string(),
+        field: FIELD_OWNER.to_string(),
+      }));
+    }
+
+    let roots = match command.clone() {
+      Command::Grant(CommandGrant { journal_id, user_id, role }) => {
+        Self::save(db, [Root { journal_id, user_id, role }]).await?
+      }
+      Command::Revoke(CommandRevoke { journal_id, user_id }) => {
+        Entity::delete_many()
+          .filter(Column::JournalId.eq(journal_id))
+          .filter(Column::UserId.eq(user_id))
+          .exec(db)
+          .await?;
+        Vec::default()
+      }
+    };
+    let befores: Vec<_> =
+      memberships.into_iter().filter(|membership| membership.user_id == user_id).collect();
+    Self::record(db, Uuid::new_v4(), actor, &command, &befores, &roots).await?;
+    Ok(roots)
+  }
+
+  /// Record the changes of the memberships in the history, keyed by the users within each journal,
+  /// so that the watchers follow the journals the users have been allowed
+  pub(crate) async fn record(
+    db: &impl ConnectionTrait,
+    command_id: Uuid,
+    actor: &Actor,
+    command: &impl Serialize,
+    befores: &[Root],
+    afters: &[Root],
+  ) -> crate::Result<()> {
+    let journal_ids: Vec<_> =
+      befores.iter().chain(afters).map(|root| root.journal_id).unique().collect();
+    for journal_id in journal_ids {
+      let keyed = |roots: &[Root]| -> Vec<(Uuid, Root)> {
+        roots
+          .iter()
+          .filter(|root| root.journal_id == journal_id)
+          .map(|root| (root.user_id, root.clone()))
+          .collect()
+      };
+      let (befores, afters) = (keyed(befores), keyed(afters));
+      history::Root::record(
+        db,
+        command_id,
+        TYPE,
+        actor,
+        command,
+        befores.iter().map(|(user_id, root)| (*user_id, root)),
+        afters.iter().map(|(user_id, root)| (*user_id, root)),
+      )
+      .await?;
+    }
+    Ok(())
+  }
+
+  /// Save the memberships, replacing the roles the users have had in the journals
+  pub(crate) async fn save(
+    db: &impl ConnectionTrait,
+    roots: impl IntoIterator<Item = Root>,
+  ) -> crate::Result<Vec<Root>> {
+    let roots: Vec<Root> = roots.into_iter().collect();
+    if roots.is_empty() {
+      return Ok(roots);
+    }
+
+    let models: Vec<_> = roots
+      .iter()
+      .map(|root| {
+        Model { journal_id: root.journal_id, user_id: root.user_id, role: root.role }
+          .into_active_model()
+      })
+      .collect();
+    let mut on_conflict = OnConflict::columns([Column::JournalId, Column::UserId]);
+    on_conflict.update_columns([Column::Role]);
+    Entity::insert_many(models).on_conflict(on_conflict).exec(db).await?;
+    Ok(roots)
+  }
+
+  async fn check_existing(
+    db: &impl ConnectionTrait,
+    journal_id: Uuid,
+    user_id: Uuid,
+  ) -> crate::Result<()> {
+    if journal::Root::find_one(
+      db,
+      Some(journal::Query { id: HashSet::from_iter([journal_id]), ..Default::default() }),
+    )
+    .await?
+    .is_none()
+    {
+      return Err(crate::Error::NotFound(ErrorNotFound {
+        entity: journal::TYPE.to_string(),
+        values: vec![(FIELD_ID.to_string(), journal_id.to_string())],
+      }));
+    }
+
+    if user::Root::find_one(
+      db,
+      Some(user::Query { id: HashSet::from_iter([user_id]), ..Default::default() }),
+    )
+    .await?
+    .is_none()
+    {
+      return Err(crate::Error::NotFound(ErrorNotFound {
+        entity: user::TYPE.to_string(),
+        values: vec![(FIELD_ID.to_string(), user_id.to_string())],
+      }));
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,65 @@
+use crate::entity::membership::{self, Role};
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Cond, IntoCondition};
+use sea_orm::Condition;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Query {
+  #[serde(default)]
+  pub journal_id: HashSet<Uuid>,
+  #[serde(default)]
+  pub user_id: HashSet<Uuid>,
+  #[serde(default)]
+  pub role: HashSet<Role>,
+}
+
+impl IntoCondition for Query {
+  fn into_condition(self) -> Condition {
+    let mut cond = Cond::all();
+
+    if !self.journal_id.is_empty() {
+      cond = cond.add(membership::Column::JournalId.is_in(self.journal_id));
+    }
+
+    if !self.user_id.is_empty() {
+      cond = cond.add(membership::Column::UserId.is_in(self.user_id));
+    }
+
+    if !self.role.is_empty() {
+      cond = cond.add(membership::Column::Role.is_in(self.role));
+    }
+
+    cond
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::entity::membership;
+  use sea_orm::{DbBackend, EntityTrait, QueryFilter, QueryTrait};
+  use std::collections::HashSet;
+  use uuid::uuid;
+
+  #[test]
+  fn test_query() -> anyhow::Result<()> {
+    let query = membership::Query {
+      journal_id: HashSet::from_iter([uuid!("50a1b556-b99d-4ae0-bfba-d117f9a958de")]),
+      user_id: HashSet::from_iter([uuid!("50a1b556-b99d-4ae0-bfba-d117f9a958df")]),
+      role: HashSet::from_iter([membership::Role::Owner]),
+    };
+
+    assert_eq!(
+      [r#"SELECT "memberships"."journal_id", "memberships"."user_id", "memberships"."role" FROM "memberships""#,
+        r#"WHERE "memberships"."journal_id" IN ('50a1b556-b99d-4ae0-bfba-d117f9a958de')"#,
+        r#"AND "memberships"."user_id" IN ('50a1b556-b99d-4ae0-bfba-d117f9a958df')"#,
+        r#"AND "memberships"."role" IN ('O')"#].join(" "),
+      membership::Entity::find().filter(query).build(DbBackend::Sqlite).to_string()
+    );
+
+    Ok(())
+  }
+}
@@ -1,17 +1,41 @@
-use crate::error::ErrorOutOfRange;
+use crate::actor::Actor;
+use crate::error::{ErrorConflict, ErrorOutOfRange};
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::{Alias, Expr, IntoIden, SimpleExpr};
 use sea_orm::ConnectionTrait;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 pub mod account;
+pub mod account_balance;
+pub mod account_register;
 pub mod account_tag;
+pub mod api_token;
+pub mod balance_sheet;
+pub mod cash_flow_statement;
+pub mod csv_profile;
 pub mod entry;
 pub mod entry_item;
 pub mod entry_tag;
 pub mod hierarchy_report;
+pub mod history;
+pub mod imported_transaction;
+pub mod income_statement;
 pub mod journal;
 pub mod journal_tag;
+pub mod membership;
+pub mod payee;
+pub mod payee_alias;
+pub mod payee_report;
+pub mod payee_tag;
+pub mod reconciliation;
+pub mod reconciliation_entry;
+pub mod rule;
+pub mod rule_tag;
+pub mod time_series_report;
+pub mod trial_balance;
+pub mod user;
 
 pub const FIELD_ID: &str = "id";
 pub const FIELD_NAME: &str = "name";
@@ -20,7 +44,9 @@ pub const FIELD_TAGS: &str = "tags";
 pub const FIELD_TAG_EACH: &str = "tags.each";
 pub const FIELD_UNIT: &str = "unit";
 pub const FIELD_JOURNAL: &str = "journal";
+pub const FIELD_USER: &str = "user";
 pub const FIELD_TYPE: &str = "type";
+pub const FIELD_VERSION: &str = "version";
 
 pub const MIN_NAME_LENGTH: usize = 6;
 pub const MAX_NAME_LENGTH: usize = 63;
@@ -29,6 +55,8 @@ pub const MIN_SHORT_TEXT_LENGTH: usize = 2;
 pub const MAX_SHORT_TEXT_LENGTH: usize = 15;
 pub const MAX_TAGS_LENGTH: usize = 7;
 
+pub(crate) const REPORT_SPLITERATOR: &str = ":::";
+
 pub trait ReadRoot: Sized {
   type Query;
 
@@ -49,6 +77,54 @@ pub trait ReadRoot: Sized {
   ) -> crate::Result<Option<Self>> {
     Ok(Self::find_all(db, query, Some(1), None).await?.into_iter().next())
   }
+
+  /// Find the roots the same as [`ReadRoot::find_all`], within the journals the actor is allowed
+  /// to view only
+  async fn find_all_as(
+    db: &impl ConnectionTrait,
+    actor: &Actor,
+    query: Option<Self::Query>,
+    limit: Option<u64>,
+    sort: Option<Self::Sort>,
+  ) -> crate::Result<Vec<Self>>
+  where
+    Self::Query: JournalQuery,
+  {
+    let Some(allowed) = actor.find_journal_ids(db, membership::Role::Viewer).await? else {
+      return Self::find_all(db, query, limit, sort).await;
+    };
+
+    let mut query = query.unwrap_or_default();
+    let journal_ids = query.journal_ids();
+    let scoped: HashSet<_> = if journal_ids.is_empty() {
+      allowed
+    } else {
+      journal_ids.intersection(&allowed).copied().collect()
+    };
+    // The empty IDs are for all the journals, so there is nothing to find
+    if scoped.is_empty() {
+      return Ok(Vec::default());
+    }
+    *journal_ids = scoped;
+    Self::find_all(db, Some(query), limit, sort).await
+  }
+
+  async fn find_one_as(
+    db: &impl ConnectionTrait,
+    actor: &Actor,
+    query: Option<Self::Query>,
+  ) -> crate::Result<Option<Self>>
+  where
+    Self::Query: JournalQuery,
+  {
+    Ok(Self::find_all_as(db, actor, query, Some(1), None).await?.into_iter().next())
+  }
+}
+
+/// The queries of the roots in the journals, to be narrowed to the journals the actors are allowed
+pub trait JournalQuery: Default {
+  /// The journals to find the roots in, which are all of them if empty
+  fn journal_ids(&mut self) -> &mut HashSet<Uuid>;
 }
 
 pub trait WriteRoot: ReadRoot {
@@ -152,3 +228,67 @@ pub(crate) fn normalize_tags(
     Ok(trimmed)
   }
 }
+
+/// Deserialize the present values as `Some`, so that an explicit `null` clears a nullable field
+/// while a missing one leaves it untouched with `#[serde(default)]`
+pub(crate) fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+  T: Deserialize<'de>,
+  D: serde::Deserializer<'de>,
+{
+  T::deserialize(deserializer).map(Some)
+}
+
+/// Check the version expected by a command against the current one, which is `None` if the entity
+/// does not exist any more
+pub(crate) fn check_version(
+  typ: impl ToString,
+  id: Uuid,
+  expected: Option<i32>,
+  current: Option<i32>,
+) -> crate::Result<()> {
+  match expected {
+    Some(expected) if Some(expected) != current => Err(crate::Error::Conflict(ErrorConflict {
+      entity: typ.to_string(),
+      values: vec![
+        (FIELD_ID.to_string(), id.to_string()),
+        (FIELD_VERSION.to_string(), expected.to_string()),
+      ],
+    })),
+    _ => Ok(()),
+  }
+}
+
+/// Check the versions of the roots to save against the current ones of the existing entities, so
+/// that the roots read before the others saved are rejected
+pub(crate) fn check_versions<R>(
+  typ: impl ToString + Copy,
+  roots: &[R],
+  version: impl Fn(&R) -> (Uuid, i32),
+  currents: &HashMap<Uuid, i32>,
+) -> crate::Result<()> {
+  for (id, expected) in roots.iter().map(version) {
+    if let Some(current) = currents.get(&id) {
+      check_version(typ, id, Some(expected), Some(*current))?;
+    }
+  }
+  Ok(())
+}
+
+/// The creation time of a root to save, which is the saving time if the root is built without one
+pub(crate) fn created_at(value: DateTime<Utc>, now: DateTime<Utc>) -> DateTime<Utc> {
+  if value == DateTime::<Utc>::default() {
+    now
+  } else {
+    value
+  }
+}
+
+/// The condition for the upserts to take the next versions only, so that the changes saved by the
+/// others since read are not overwritten silently
+pub(crate) fn next_version(
+  table: impl IntoIden + 'static,
+  column: impl IntoIden + Copy + 'static,
+) -> SimpleExpr {
+  Expr::col((table, column)).add(1).eq(Expr::col((Alias::new("excluded"), column)))
+}
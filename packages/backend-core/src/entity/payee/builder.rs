@@ -0,0 +1,109 @@
+use crate::entity::payee::{Root, FIELD_ALIAS_EACH, TYPE};
+use crate::entity::{
+  account, normalize_tags, FIELD_ID, FIELD_JOURNAL, FIELD_NAME, MAX_NAME_LENGTH,
+  MIN_SHORT_TEXT_LENGTH,
+};
+use crate::error::{ErrorNotFound, ErrorOutOfRange, ErrorRequiredField};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+#[derive(Debug, Default)]
+pub struct Builder {
+  id: Option<Uuid>,
+  journal_id: Option<Uuid>,
+  name: String,
+  aliases: HashSet<String>,
+  default_account_id: Option<Uuid>,
+  tags: HashSet<String>,
+}
+
+impl From<Root> for Builder {
+  fn from(value: Root) -> Self {
+    Builder {
+      id: Some(value.id),
+      journal_id: Some(value.journal_id),
+      name: value.name,
+      aliases: value.aliases,
+      default_account_id: value.default_account_id,
+      tags: value.tags,
+    }
+  }
+}
+
+impl Builder {
+  pub fn build(self, accounts: &HashMap<Uuid, account::Root>) -> crate::Result<Root> {
+    let journal_id = self.journal_id.ok_or_else(|| {
+      crate::Error::RequiredField(ErrorRequiredField {
+        entity: TYPE.to_string(),
+        field: FIELD_JOURNAL.to_string(),
+      })
+    })?;
+    // The merchants are often shorter than the names of the other entities, like `Uber`
+    let normalize = |field: &str, value: String| {
+      let value = value.trim().to_string();
+      if value.len() < MIN_SHORT_TEXT_LENGTH || value.len() > MAX_NAME_LENGTH {
+        Err(crate::Error::OutOfRange(ErrorOutOfRange {
+          entity: TYPE.to_string(),
+          field: field.to_string(),
+          start: Some(MIN_SHORT_TEXT_LENGTH.to_string()),
+          end: Some(MAX_NAME_LENGTH.to_string()),
+        }))
+      } else {
+        Ok(value)
+      }
+    };
+
+    let name = normalize(FIELD_NAME, self.name)?;
+    let mut aliases = HashSet::new();
+    for alias in self.aliases {
+      aliases.insert(normalize(FIELD_ALIAS_EACH, alias)?);
+    }
+    aliases.remove(&name);
+    let tags = normalize_tags(TYPE, self.tags)?;
+
+    if let Some(account_id) = self.default_account_id {
+      if accounts.get(&account_id).is_none_or(|account| account.journal_id != journal_id) {
+        return Err(crate::Error::NotFound(ErrorNotFound {
+          entity: account::TYPE.to_string(),
+          values: vec![
+            (FIELD_JOURNAL.to_string(), journal_id.to_string()),
+            (FIELD_ID.to_string(), account_id.to_string()),
+          ],
+        }));
+      }
+    }
+
+    Ok(Root {
+      id: self.id.unwrap_or_else(Uuid::new_v4),
+      journal_id,
+      name,
+      aliases,
+      default_account_id: self.default_account_id,
+      tags,
+    })
+  }
+
+  pub fn id(self, id: Uuid) -> Builder {
+    Builder { id: Some(id), ..self }
+  }
+
+  pub fn journal_id(self, journal_id: Uuid) -> Builder {
+    Builder { journal_id: Some(journal_id), ..self }
+  }
+
+  pub fn name(self, name: impl ToString) -> Builder {
+    Builder { name: name.to_string(), ..self }
+  }
+
+  pub fn aliases(self, aliases: impl IntoIterator<Item = impl ToString>) -> Builder {
+    Builder { aliases: aliases.into_iter().map(|alias| alias.to_string()).collect(), ..self }
+  }
+
+  pub fn default_account_id(self, default_account_id: Option<Uuid>) -> Builder {
+    Builder { default_account_id, ..self }
+  }
+
+  pub fn tags(self, tags: impl IntoIterator<Item = impl ToString>) -> Builder {
+    Builder { tags: tags.into_iter().map(|tag| tag.to_string()).collect(), ..self }
+  }
+}
@@ -0,0 +1,49 @@
+use crate::entity::deserialize_some;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "commandType")]
+pub enum Command {
+  #[serde(rename = "payees:create")]
+  Create(CommandCreate),
+  #[serde(rename = "payees:update")]
+  Update(CommandUpdate),
+  #[serde(rename = "payees:delete")]
+  Delete(CommandDelete),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandCreate {
+  pub journal_id: Uuid,
+  pub name: String,
+  #[serde(default)]
+  pub aliases: HashSet<String>,
+  #[serde(default)]
+  pub default_account_id: Option<Uuid>,
+  #[serde(default)]
+  pub tags: HashSet<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandUpdate {
+  pub id: Uuid,
+  #[serde(default)]
+  pub name: String,
+  #[serde(default)]
+  pub aliases: Option<HashSet<String>>,
+  /// Cleared by an explicit `null`, kept if missing
+  #[serde(default, deserialize_with = "deserialize_some")]
+  pub default_account_id: Option<Option<Uuid>>,
+  #[serde(default)]
+  pub tags: Option<HashSet<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommandDelete {
+  #[serde(default)]
+  pub id: HashSet<Uuid>,
+}
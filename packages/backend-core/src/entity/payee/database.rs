@@ -0,0 +1,58 @@
+use crate::entity::{account, journal, payee_alias, payee_tag};
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "payees")]
+pub struct Model {
+  #[sea_orm(primary_key, auto_increment = false)]
+  pub id: Uuid,
+  #[sea_orm(indexed)]
+  pub journal_id: Uuid,
+  #[sea_orm(indexed)]
+  pub name: String,
+  pub default_account_id: Option<Uuid>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+  #[sea_orm(has_many = "payee_alias::Entity")]
+  Aliases,
+  #[sea_orm(has_many = "payee_tag::Entity")]
+  Tags,
+  #[sea_orm(
+    belongs_to = "journal::Entity",
+    from = "Column::JournalId",
+    to = "journal::Column::Id",
+    on_update = "Cascade",
+    on_delete = "Cascade"
+  )]
+  Journal,
+  #[sea_orm(
+    belongs_to = "account::Entity",
+    from = "Column::DefaultAccountId",
+    to = "account::Column::Id",
+    on_update = "Cascade",
+    on_delete = "SetNull"
+  )]
+  DefaultAccount,
+}
+
+impl Related<journal::Entity> for Entity {
+  fn to() -> RelationDef {
+    Relation::Journal.def()
+  }
+}
+
+impl Related<payee_alias::Entity> for Entity {
+  fn to() -> RelationDef {
+    Relation::Aliases.def()
+  }
+}
+
+impl Related<payee_tag::Entity> for Entity {
+  fn to() -> RelationDef {
+    Relation::Tags.def()
+  }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,361 @@
+mod builder;
+mod command;
+mod database;
+mod query;
+
+pub use builder::*;
+pub use command::*;
+pub use database::*;
+pub use query::*;
+
+use crate::actor::Actor;
+use crate::entity::membership::Role;
+use crate::entity::{
+  account, journal, payee_alias, payee_tag, ReadRoot, WriteRoot, FIELD_ID, FIELD_JOURNAL,
+  FIELD_NAME,
+};
+use crate::error::{ErrorExistingEntity, ErrorNotFound};
+use itertools::Itertools;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+  ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel, Order, QueryFilter, QueryOrder,
+  QuerySelect,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+pub const TYPE: &str = "Payee";
+pub const FIELD_ALIAS_EACH: &str = "aliases.each";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sort {
+  #[serde(rename = "name")]
+  Name,
+  #[serde(rename = "-name")]
+  MinusName,
+}
+
+impl From<Sort> for (Column, Order) {
+  fn from(value: Sort) -> Self {
+    match value {
+      Sort::Name => (Column::Name, Order::Asc),
+      Sort::MinusName => (Column::Name, Order::Desc),
+    }
+  }
+}
+
+/// The counterparty of the entries, such as a merchant
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Root {
+  pub id: Uuid,
+  pub journal_id: Uuid,
+  pub name: String,
+  /// Unique in the journal together with the names
+  pub aliases: HashSet<String>,
+  pub default_account_id: Option<Uuid>,
+  pub tags: HashSet<String>,
+}
+
+impl ReadRoot for Root {
+  type Query = Query;
+  type Sort = Sort;
+
+  fn id(&self) -> String {
+    self.id.to_string()
+  }
+
+  async fn find_all(
+    db: &impl ConnectionTrait,
+    query: Option<Query>,
+    limit: Option<u64>,
+    sort: Option<Sort>,
+  ) -> crate::Result<Vec<Root>> {
+    let select =
+      if let Some(query) = query { Entity::find().filter(query) } else { Entity::find() };
+    let select = if let Some(sort) = sort {
+      let (field, order) = Into::<(Column, Order)>::into(sort);
+      select.order_by(field, order)
+    } else {
+      select
+    };
+    let models = select.limit(limit).all(db).await?;
+    Self::from_model(db, models).await
+  }
+}
+
+impl WriteRoot for Root {
+  type Model = Model;
+
+  async fn from_model(
+    db: &impl ConnectionTrait,
+    models: impl IntoIterator<Item = Model>,
+  ) -> crate::Result<Vec<Root>> {
+    let models: Vec<_> = models.into_iter().collect();
+    let ids: HashSet<_> = models.iter().map(|model| model.id).collect();
+
+    let mut aliases = payee_alias::Entity::find()
+      .filter(payee_alias::Column::PayeeId.is_in(ids.clone()))
+      .all(db)
+      .await?
+      .into_iter()
+      .into_group_map_by(|alias| alias.payee_id);
+    let mut tags = payee_tag::Entity::find()
+      .filter(payee_tag::Column::PayeeId.is_in(ids))
+      .all(db)
+      .await?
+      .into_iter()
+      .into_group_map_by(|tag| tag.payee_id);
+
+    Ok(
+      models
+        .into_iter()
+        .map(|model| Root {
+          id: model.id,
+          journal_id: model.journal_id,
+          name: model.name,
+          aliases: aliases.remove(&model.id).into_iter().flatten().map(|m| m.alias).collect(),
+          default_account_id: model.default_account_id,
+          tags: tags.remove(&model.id).into_iter().flatten().map(|m| m.tag).collect(),
+        })
+        .collect(),
+    )
+  }
+
+  async fn save(
+    db: &impl ConnectionTrait,
+    roots: impl IntoIterator<Item = Root>,
+  ) -> crate::Result<Vec<Root>> {
+    let roots: Vec<Root> = roots.into_iter().collect();
+    if roots.is_empty() {
+      return Ok(roots);
+    }
+
+    let model_ids: HashSet<_> = roots.iter().map(|root| root.id).collect();
+    let mut models = Vec::new();
+    let mut aliases = Vec::new();
+    let mut tags = Vec::new();
+    for root in roots {
+      models.push(
+        Model {
+          id: root.id,
+          journal_id: root.journal_id,
+          name: root.name,
+          default_account_id: root.default_account_id,
+        }
+        .into_active_model(),
+      );
+      for alias in root.aliases {
+        aliases.push(payee_alias::Model { payee_id: root.id, alias }.into_active_model());
+      }
+      for tag in root.tags {
+        tags.push(payee_tag::Model { payee_id: root.id, tag }.into_active_model());
+      }
+    }
+
+    payee_alias::Entity::delete_many()
+      .filter(payee_alias::Column::PayeeId.is_in(model_ids.clone()))
+      .exec(db)
+      .await?;
+    payee_tag::Entity::delete_many()
+      .filter(payee_tag::Column::PayeeId.is_in(model_ids.clone()))
+      .exec(db)
+      .await?;
+
+    let mut on_conflict = OnConflict::column(Column::Id);
+    on_conflict.update_columns([Column::Name, Column::DefaultAccountId]);
+    Entity::insert_many(models).on_conflict(on_conflict).exec(db).await?;
+
+    if !aliases.is_empty() {
+      payee_alias::Entity::insert_many(aliases).exec(db).await?;
+    }
+    if !tags.is_empty() {
+      payee_tag::Entity::insert_many(tags).exec(db).await?;
+    }
+
+    Self::find_all(db, Some(Query { id: model_ids, ..Default::default() }), None, None).await
+  }
+
+  async fn delete(
+    db: &impl ConnectionTrait,
+    ids: impl IntoIterator<Item = Uuid>,
+  ) -> crate::Result<()> {
+    Entity::delete_many().filter(Column::Id.is_in(ids)).exec(db).await?;
+    Ok(())
+  }
+}
+
+impl Root {
+  /// Handle the command, which the editors of the journals are allowed to only
+  pub async fn handle(
+    db: &impl ConnectionTrait,
+    actor: &Actor,
+    command: Command,
+  ) -> crate::Result<Vec<Root>> {
+    let (ids, mut journal_ids): (HashSet<_>, HashSet<_>) = match &command {
+      Command::Create(command) => (HashSet::default(), HashSet::from_iter([command.journal_id])),
+      Command::Update(command) => (HashSet::from_iter([command.id]), HashSet::default()),
+      Command::Delete(command) => (command.id.clone(), HashSet::default()),
+    };
+    if !ids.is_empty() {
+      let befores =
+        Self::find_all(db, Some(Query { id: ids, ..Default::default() }), None, None).await?;
+      journal_ids.extend(befores.into_iter().map(|root| root.journal_id));
+    }
+    actor.check_journals(db, journal_ids, Role::Editor).await?;
+
+    match command {
+      Command::Create(command) => Self::create(db, vec![command]).await,
+      Command::Update(command) => Self::update(db, vec![command]).await,
+      Command::Delete(CommandDelete { id }) => {
+        Self::delete(db, id).await?;
+        Ok(Vec::default())
+      }
+    }
+  }
+
+  pub async fn create(
+    db: &impl ConnectionTrait,
+    commands: Vec<CommandCreate>,
+  ) -> crate::Result<Vec<Root>> {
+    let mut roots = Vec::new();
+    for command in commands {
+      let accounts = Self::find_accounts(db, command.journal_id).await?;
+      let root = Builder::default()
+        .journal_id(command.journal_id)
+        .name(command.name)
+        .aliases(command.aliases)
+        .default_account_id(command.default_account_id)
+        .tags(command.tags)
+        .build(&accounts)?;
+      Self::check_names(db, &root, &roots).await?;
+      roots.push(root);
+    }
+
+    Self::save(db, roots).await
+  }
+
+  pub async fn update(
+    db: &impl ConnectionTrait,
+    commands: Vec<CommandUpdate>,
+  ) -> crate::Result<Vec<Root>> {
+    let mut models = Self::find_all(
+      db,
+      Some(Query { id: commands.iter().map(|command| command.id).collect(), ..Default::default() }),
+      None,
+      None,
+    )
+    .await?
+    .into_iter()
+    .map(|model| (model.id, model))
+    .collect::<HashMap<_, _>>();
+
+    let mut roots = Vec::new();
+    for command in commands {
+      let model = models.remove(&command.id).ok_or_else(|| {
+        crate::Error::NotFound(ErrorNotFound {
+          entity: TYPE.to_string(),
+          values: vec![(FIELD_ID.to_string(), command.id.to_string())],
+        })
+      })?;
+      let accounts = Self::find_accounts(db, model.journal_id).await?;
+
+      let mut builder = Builder::from(model);
+      if !command.name.is_empty() {
+        builder = builder.name(command.name);
+      }
+      if let Some(aliases) = command.aliases {
+        builder = builder.aliases(aliases);
+      }
+      if let Some(default_account_id) = command.default_account_id {
+        builder = builder.default_account_id(default_account_id);
+      }
+      if let Some(tags) = command.tags {
+        builder = builder.tags(tags);
+      }
+      let root = builder.build(&accounts)?;
+      Self::check_names(db, &root, &roots).await?;
+      roots.push(root);
+    }
+
+    Self::save(db, roots).await
+  }
+
+  /// The names and the aliases of the payee, the ones a bank statement may refer to it by
+  pub fn names(&self) -> impl Iterator<Item = &String> {
+    [&self.name].into_iter().chain(&self.aliases)
+  }
+
+  async fn find_accounts(
+    db: &impl ConnectionTrait,
+    journal_id: Uuid,
+  ) -> crate::Result<HashMap<Uuid, account::Root>> {
+    if journal::Root::find_one(
+      db,
+      Some(journal::Query { id: HashSet::from_iter([journal_id]), ..Default::default() }),
+    )
+    .await?
+    .is_none()
+    {
+      return Err(crate::Error::NotFound(ErrorNotFound {
+        entity: journal::TYPE.to_string(),
+        values: vec![(FIELD_ID.to_string(), journal_id.to_string())],
+      }));
+    }
+
+    Ok(
+      account::Root::find_all(
+        db,
+        Some(account::Query { journal_id: HashSet::from_iter([journal_id]), ..Default::default() }),
+        None,
+        None,
+      )
+      .await?
+      .into_iter()
+      .map(|account| (account.id, account))
+      .collect(),
+    )
+  }
+
+  /// The names and the aliases can refer to one payee only in the journal, including the ones
+  /// pending to be saved in the same batch
+  async fn check_names(
+    db: &impl ConnectionTrait,
+    root: &Root,
+    pendings: &[Root],
+  ) -> crate::Result<()> {
+    let names: HashSet<_> = root.names().cloned().collect();
+    let existings = Self::find_all(
+      db,
+      Some(Query {
+        journal_id: HashSet::from_iter([root.journal_id]),
+        name: names.clone(),
+        ..Default::default()
+      }),
+      None,
+      None,
+    )
+    .await?;
+
+    let duplicated = existings
+      .iter()
+      .chain(pendings.iter().filter(|pending| pending.journal_id == root.journal_id))
+      .filter(|other| other.id != root.id)
+      .flat_map(|other| other.names())
+      .filter(|name| names.contains(*name))
+      .unique()
+      .sorted()
+      .join(", ");
+    if !duplicated.is_empty() {
+      return Err(crate::Error::ExistingEntity(ErrorExistingEntity {
+        entity: TYPE.to_string(),
+        values: vec![
+          (FIELD_JOURNAL.to_string(), root.journal_id.to_string()),
+          (FIELD_NAME.to_string(), duplicated),
+        ],
+      }));
+    }
+    Ok(())
+  }
+}
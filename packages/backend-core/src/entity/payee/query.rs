@@ -0,0 +1,89 @@
+use crate::entity::{payee, payee_alias, JournalQuery};
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Cond, IntoCondition};
+use sea_orm::{Condition, QuerySelect, QueryTrait};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Query {
+  #[serde(default)]
+  pub id: HashSet<Uuid>,
+  #[serde(default)]
+  pub journal_id: HashSet<Uuid>,
+  /// Matching either the names or the aliases
+  #[serde(default)]
+  pub name: HashSet<String>,
+}
+
+impl JournalQuery for Query {
+  fn journal_ids(&mut self) -> &mut HashSet<Uuid> {
+    &mut self.journal_id
+  }
+}
+
+impl IntoCondition for Query {
+  fn into_condition(self) -> Condition {
+    let mut cond = Cond::all();
+
+    if !self.id.is_empty() {
+      cond = cond.add(payee::Column::Id.is_in(self.id));
+    }
+
+    if !self.journal_id.is_empty() {
+      cond = cond.add(payee::Column::JournalId.is_in(self.journal_id));
+    }
+
+    let name: HashSet<String> = self
+      .name
+      .into_iter()
+      .map(|name| name.trim().to_string())
+      .filter(|name| !name.is_empty())
+      .collect();
+    if !name.is_empty() {
+      cond = cond.add(
+        Cond::any().add(payee::Column::Name.is_in(name.clone())).add(
+          payee::Column::Id.in_subquery(
+            payee_alias::Entity::find()
+              .select_only()
+              .distinct()
+              .column(payee_alias::Column::PayeeId)
+              .filter(payee_alias::Column::Alias.is_in(name))
+              .into_query(),
+          ),
+        ),
+      );
+    }
+
+    cond
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::entity::payee;
+  use sea_orm::{DbBackend, EntityTrait, QueryFilter, QueryTrait};
+  use std::collections::HashSet;
+  use uuid::uuid;
+
+  #[test]
+  fn test_query() -> anyhow::Result<()> {
+    let query = payee::Query {
+      id: HashSet::from_iter([uuid!("50a1b556-b99d-4ae0-bfba-d117f9a958de")]),
+      journal_id: HashSet::default(),
+      name: HashSet::from_iter(["Starbucks".to_string(), " ".to_string()]),
+    };
+
+    assert_eq!(
+      [r#"SELECT "payees"."id", "payees"."journal_id", "payees"."name", "payees"."default_account_id" FROM "payees""#,
+        r#"WHERE "payees"."id" IN ('50a1b556-b99d-4ae0-bfba-d117f9a958de')"#,
+        r#"AND ("payees"."name" IN ('Starbucks')"#,
+        r#"OR "payees"."id" IN (SELECT DISTINCT "payee_aliases"."payee_id" FROM "payee_aliases" WHERE "payee_aliases"."alias" IN ('Starbucks')))"#].join(" "),
+      payee::Entity::find().filter(query).build(DbBackend::Sqlite).to_string()
+    );
+
+    Ok(())
+  }
+}
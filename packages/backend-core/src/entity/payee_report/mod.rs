@@ -0,0 +1,238 @@
+mod query;
+
+pub use query::*;
+
+use crate::entity::{account, entry, journal, payee, ReadRoot, REPORT_SPLITERATOR};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sea_orm::ConnectionTrait;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// The totals of the records by payee in the period
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Root {
+  pub journal_id: Uuid,
+  pub start: Option<NaiveDate>,
+  pub end: Option<NaiveDate>,
+  pub unit: String,
+  pub items: Vec<Item>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Item {
+  pub payee_id: Uuid,
+  pub name: String,
+  pub count: usize,
+  /// The sum of the debit side of the records, in the unit of the journal
+  pub total: Decimal,
+}
+
+impl ReadRoot for Root {
+  type Query = Query;
+  type Sort = ();
+
+  fn id(&self) -> String {
+    [
+      self.journal_id.to_string(),
+      self.start.map(|date| date.to_string()).unwrap_or_default(),
+      self.end.map(|date| date.to_string()).unwrap_or_default(),
+    ]
+    .join(REPORT_SPLITERATOR)
+  }
+
+  async fn find_all(
+    db: &impl ConnectionTrait,
+    query: Option<Query>,
+    limit: Option<u64>,
+    _sort: Option<()>,
+  ) -> crate::Result<Vec<Root>> {
+    let query = query.unwrap_or_default();
+
+    let journals = journal::Root::find_all(
+      db,
+      Some(journal::Query { id: query.journal_id.clone(), ..Default::default() }),
+      limit,
+      None,
+    )
+    .await?;
+    let journal_ids: HashSet<_> = journals.iter().map(|journal| journal.id).collect();
+
+    let payees = payee::Root::find_all(
+      db,
+      Some(payee::Query {
+        id: query.payee_id.clone(),
+        journal_id: journal_ids.clone(),
+        ..Default::default()
+      }),
+      None,
+      None,
+    )
+    .await?;
+    if payees.is_empty() {
+      return Ok(
+        journals.iter().map(|journal| Self::do_aggregate(journal, &query, &[], &[], &[])).collect(),
+      );
+    }
+
+    let entries = entry::Root::find_all(
+      db,
+      Some(entry::Query {
+        journal_id: journal_ids.clone(),
+        payee_id: payees.iter().map(|payee| payee.id).collect(),
+        typ: Some(entry::Type::Record),
+        start: query.start,
+        end: query.end,
+        ..Default::default()
+      }),
+      None,
+      None,
+    )
+    .await?;
+
+    let accounts = account::Root::find_all(
+      db,
+      Some(account::Query { journal_id: journal_ids, ..Default::default() }),
+      None,
+      None,
+    )
+    .await?;
+
+    Ok(
+      journals
+        .iter()
+        .map(|journal| Self::do_aggregate(journal, &query, &payees, &entries, &accounts))
+        .collect(),
+    )
+  }
+}
+
+impl Root {
+  fn do_aggregate(
+    journal: &journal::Root,
+    query: &Query,
+    payees: &[payee::Root],
+    entries: &[entry::Root],
+    accounts: &[account::Root],
+  ) -> Root {
+    let debits: HashSet<_> =
+      accounts.iter().filter(|account| account.typ.is_debit()).map(|account| account.id).collect();
+
+    let mut items: HashMap<_, _> = payees
+      .iter()
+      .filter(|payee| payee.journal_id == journal.id)
+      .map(|payee| {
+        (
+          payee.id,
+          Item { payee_id: payee.id, name: payee.name.clone(), count: 0, total: Decimal::ZERO },
+        )
+      })
+      .collect();
+
+    for entry in entries.iter().filter(|entry| entry.journal_id == journal.id) {
+      if let Some(item) = entry.payee_id.and_then(|payee_id| items.get_mut(&payee_id)) {
+        item.count += 1;
+        item.total += entry
+          .items
+          .iter()
+          .filter(|entry_item| debits.contains(&entry_item.account))
+          .map(|entry_item| entry_item.amount * entry_item.price)
+          .sum::<Decimal>();
+      }
+    }
+
+    let mut items: Vec<_> = items.into_values().collect();
+    items.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.name.cmp(&b.name)));
+
+    Root {
+      journal_id: journal.id,
+      start: query.start,
+      end: query.end,
+      unit: journal.unit.clone(),
+      items,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::entity::payee_report::{Query, Root};
+  use crate::entity::{account, entry, journal, payee};
+  use chrono::NaiveDate;
+  use rust_decimal_macros::dec;
+  use std::collections::HashSet;
+  use uuid::Uuid;
+
+  #[test]
+  fn test_do_aggregate() -> anyhow::Result<()> {
+    let journal = journal::Root {
+      id: Uuid::new_v4(),
+      name: "Test Journal".to_string(),
+      description: String::default(),
+      unit: "USD".to_string(),
+      tags: HashSet::default(),
+      version: 0,
+      created_at: Default::default(),
+      updated_at: Default::default(),
+    };
+    let account = |name: &str, typ| account::Root {
+      id: Uuid::new_v4(),
+      journal_id: journal.id,
+      name: name.to_string(),
+      description: String::default(),
+      unit: "USD".to_string(),
+      typ,
+      tags: HashSet::default(),
+      version: 0,
+      created_at: Default::default(),
+      updated_at: Default::default(),
+    };
+    let accounts = vec![
+      account("Expenses::Coffee", account::Type::Expense),
+      account("Liabilities::Card", account::Type::Liability),
+    ];
+    let payee = |name: &str| payee::Root {
+      id: Uuid::new_v4(),
+      journal_id: journal.id,
+      name: name.to_string(),
+      aliases: HashSet::default(),
+      default_account_id: None,
+      tags: HashSet::default(),
+    };
+    let payees = vec![payee("Starbucks"), payee("Uber")];
+    let new_entry = |day: u32, payee_id, amount| entry::Root {
+      id: Uuid::new_v4(),
+      journal_id: journal.id,
+      name: format!("Coffee {}", day),
+      description: String::default(),
+      typ: entry::Type::Record,
+      date: NaiveDate::from_ymd_opt(2024, 1, day).unwrap(),
+      tags: HashSet::default(),
+      items: vec![
+        entry::Item { account: accounts[0].id, amount, price: dec!(1) },
+        entry::Item { account: accounts[1].id, amount, price: dec!(1) },
+      ],
+      payee_id,
+      version: 0,
+      created_at: Default::default(),
+      updated_at: Default::default(),
+    };
+    let entries = vec![
+      new_entry(1, Some(payees[0].id), dec!(4.5)),
+      new_entry(2, Some(payees[0].id), dec!(5)),
+      new_entry(3, None, dec!(100)),
+    ];
+
+    let report = Root::do_aggregate(&journal, &Query::default(), &payees, &entries, &accounts);
+    assert_eq!(2, report.items.len());
+    assert_eq!(payees[0].id, report.items[0].payee_id);
+    assert_eq!(2, report.items[0].count);
+    assert_eq!(dec!(9.5), report.items[0].total);
+    assert_eq!(0, report.items[1].count);
+
+    Ok(())
+  }
+}
@@ -0,0 +1,32 @@
+use crate::entity::payee;
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "payee_tags")]
+pub struct Model {
+  #[sea_orm(primary_key)]
+  pub payee_id: Uuid,
+  #[sea_orm(primary_key)]
+  pub tag: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+  #[sea_orm(
+    belongs_to = "payee::Entity",
+    from = "Column::PayeeId",
+    to = "payee::Column::Id",
+    on_update = "Cascade",
+    on_delete = "Cascade"
+  )]
+  Payee,
+}
+
+impl Related<payee::Entity> for Entity {
+  fn to() -> RelationDef {
+    Relation::Payee.def()
+  }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
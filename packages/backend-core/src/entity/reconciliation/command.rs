@@ -0,0 +1,31 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "commandType")]
+pub enum Command {
+  #[serde(rename = "reconciliations:create")]
+  Create(CommandCreate),
+  #[serde(rename = "reconciliations:delete")]
+  Delete(CommandDelete),
+}
+
+/// Confirm the matched entries against the statement
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandCreate {
+  pub account_id: Uuid,
+  pub date: NaiveDate,
+  pub balance: Decimal,
+  #[serde(default)]
+  pub entries: HashSet<Uuid>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommandDelete {
+  #[serde(default)]
+  pub id: HashSet<Uuid>,
+}
@@ -0,0 +1,54 @@
+use crate::entity::{account, entry, reconciliation_entry};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "reconciliations")]
+pub struct Model {
+  #[sea_orm(primary_key, auto_increment = false)]
+  pub id: Uuid,
+  #[sea_orm(indexed)]
+  pub account_id: Uuid,
+  /// The Check entry asserting the closing balance
+  pub check_id: Uuid,
+  #[sea_orm(indexed)]
+  pub date: NaiveDate,
+  pub balance: Decimal,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+  #[sea_orm(
+    belongs_to = "account::Entity",
+    from = "Column::AccountId",
+    to = "account::Column::Id",
+    on_update = "Cascade",
+    on_delete = "Cascade"
+  )]
+  Account,
+  #[sea_orm(
+    belongs_to = "entry::Entity",
+    from = "Column::CheckId",
+    to = "entry::Column::Id",
+    on_update = "Cascade",
+    on_delete = "Cascade"
+  )]
+  Check,
+  #[sea_orm(has_many = "reconciliation_entry::Entity")]
+  Entries,
+}
+
+impl Related<account::Entity> for Entity {
+  fn to() -> RelationDef {
+    Relation::Account.def()
+  }
+}
+
+impl Related<reconciliation_entry::Entity> for Entity {
+  fn to() -> RelationDef {
+    Relation::Entries.def()
+  }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
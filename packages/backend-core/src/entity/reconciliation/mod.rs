@@ -0,0 +1,383 @@
+mod command;
+mod database;
+mod query;
+mod statement;
+
+pub use command::*;
+pub use database::*;
+pub use query::*;
+pub use statement::*;
+
+use crate::actor::Actor;
+use crate::entity::membership::Role;
+use crate::entity::{
+  account, account_balance, entry, reconciliation_entry, ReadRoot, WriteRoot, FIELD_ID,
+};
+use crate::error::{ErrorExistingEntity, ErrorNotFound};
+use chrono::NaiveDate;
+use itertools::Itertools;
+use rust_decimal::Decimal;
+use sea_orm::{
+  ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel, Order, QueryFilter, QueryOrder,
+  QuerySelect,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+pub const TYPE: &str = "Reconciliation";
+pub const FIELD_ACCOUNT: &str = "account";
+pub const FIELD_ENTRIES: &str = "entries";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sort {
+  #[serde(rename = "date")]
+  Date,
+  #[serde(rename = "-date")]
+  MinusDate,
+}
+
+impl From<Sort> for (Column, Order) {
+  fn from(value: Sort) -> Self {
+    match value {
+      Sort::Date => (Column::Date, Order::Asc),
+      Sort::MinusDate => (Column::Date, Order::Desc),
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Root {
+  pub id: Uuid,
+  pub account_id: Uuid,
+  /// The Check entry asserting the closing balance
+  pub check_id: Uuid,
+  pub date: NaiveDate,
+  pub balance: Decimal,
+  /// The reconciled records
+  pub entries: HashSet<Uuid>,
+}
+
+impl ReadRoot for Root {
+  type Query = Query;
+  type Sort = Sort;
+
+  fn id(&self) -> String {
+    self.id.to_string()
+  }
+
+  async fn find_all(
+    db: &impl ConnectionTrait,
+    query: Option<Query>,
+    limit: Option<u64>,
+    sort: Option<Sort>,
+  ) -> crate::Result<Vec<Root>> {
+    let select =
+      if let Some(query) = query { Entity::find().filter(query) } else { Entity::find() };
+    let select = if let Some(sort) = sort {
+      let (field, order) = Into::<(Column, Order)>::into(sort);
+      select.order_by(field, order)
+    } else {
+      select
+    };
+    let models = select.limit(limit).all(db).await?;
+    Self::from_model(db, models).await
+  }
+}
+
+impl WriteRoot for Root {
+  type Model = Model;
+
+  async fn from_model(
+    db: &impl ConnectionTrait,
+    models: impl IntoIterator<Item = Model>,
+  ) -> crate::Result<Vec<Root>> {
+    let models: Vec<_> = models.into_iter().collect();
+    let ids: HashSet<_> = models.iter().map(|model| model.id).collect();
+
+    let entries = reconciliation_entry::Entity::find()
+      .filter(reconciliation_entry::Column::ReconciliationId.is_in(ids))
+      .all(db)
+      .await?
+      .into_iter()
+      .into_group_map_by(|model| model.reconciliation_id);
+
+    Ok(
+      models
+        .into_iter()
+        .map(|model| Root {
+          id: model.id,
+          account_id: model.account_id,
+          check_id: model.check_id,
+          date: model.date,
+          balance: model.balance,
+          entries: entries.get(&model.id).into_iter().flatten().map(|m| m.entry_id).collect(),
+        })
+        .collect(),
+    )
+  }
+
+  async fn save(
+    db: &impl ConnectionTrait,
+    roots: impl IntoIterator<Item = Root>,
+  ) -> crate::Result<Vec<Root>> {
+    let roots: Vec<Root> = roots.into_iter().collect();
+    if roots.is_empty() {
+      return Ok(roots);
+    }
+
+    let mut model_ids = HashSet::new();
+    let mut models: Vec<ActiveModel> = vec![];
+    let mut entries: Vec<reconciliation_entry::ActiveModel> = vec![];
+
+    for root in &roots {
+      model_ids.insert(root.id);
+      models.push(
+        Model {
+          id: root.id,
+          account_id: root.account_id,
+          check_id: root.check_id,
+          date: root.date,
+          balance: root.balance,
+        }
+        .into_active_model(),
+      );
+      for entry_id in &root.entries {
+        entries.push(
+          reconciliation_entry::Model { reconciliation_id: root.id, entry_id: *entry_id }
+            .into_active_model(),
+        );
+      }
+    }
+
+    reconciliation_entry::Entity::delete_many()
+      .filter(reconciliation_entry::Column::ReconciliationId.is_in(model_ids.clone()))
+      .exec(db)
+      .await?;
+    Entity::delete_many().filter(Column::Id.is_in(model_ids.clone())).exec(db).await?;
+
+    Entity::insert_many(models).exec(db).await?;
+    if !entries.is_empty() {
+      reconciliation_entry::Entity::insert_many(entries).exec(db).await?;
+    }
+
+    Self::find_all(db, Some(Query { id: model_ids, ..Default::default() }), None, None).await
+  }
+
+  async fn delete(
+    db: &impl ConnectionTrait,
+    ids: impl IntoIterator<Item = Uuid>,
+  ) -> crate::Result<()> {
+    let check_ids: Vec<_> = Entity::find()
+      .select_only()
+      .column(Column::CheckId)
+      .filter(Column::Id.is_in(ids))
+      .into_tuple::<Uuid>()
+      .all(db)
+      .await?;
+
+    // The reconciliations and their marks are deleted along with the Check entries
+    entry::Root::delete(db, check_ids).await
+  }
+}
+
+impl Root {
+  /// Handle the command, which the editors of the journals of the accounts are allowed to only
+  pub async fn handle(
+    db: &impl ConnectionTrait,
+    actor: &Actor,
+    command: Command,
+  ) -> crate::Result<Vec<Root>> {
+    let account_ids: HashSet<_> = match &command {
+      Command::Create(command) => HashSet::from_iter([command.account_id]),
+      Command::Delete(command) => {
+        Self::find_all(db, Some(Query { id: command.id.clone(), ..Default::default() }), None, None)
+          .await?
+          .into_iter()
+          .map(|root| root.account_id)
+          .collect()
+      }
+    };
+    if !account_ids.is_empty() {
+      let accounts = account::Root::find_all(
+        db,
+        Some(account::Query { id: account_ids, ..Default::default() }),
+        None,
+        None,
+      )
+      .await?;
+      actor
+        .check_journals(db, accounts.into_iter().map(|account| account.journal_id), Role::Editor)
+        .await?;
+    }
+
+    match command {
+      Command::Create(command) => Self::create(db, vec![command]).await,
+      Command::Delete(CommandDelete { id }) => {
+        Self::delete(db, id).await?;
+        Ok(Vec::default())
+      }
+    }
+  }
+
+  /// Propose the matches between the statement lines and the unreconciled records of the account
+  pub async fn propose(db: &impl ConnectionTrait, statement: Statement) -> crate::Result<Proposal> {
+    let account = Self::find_account(db, statement.account_id).await?;
+    let entries = Self::find_unreconciled(db, &account, statement.date).await?;
+
+    let (matches, unmatched_lines, unmatched_entries) = do_match(
+      account.id,
+      &statement.lines,
+      &entries,
+      statement.window_days.unwrap_or(DEFAULT_WINDOW_DAYS),
+    );
+
+    let actual_balance =
+      account_balance::find_balances(db, HashSet::from_iter([account.id]), Some(statement.date))
+        .await?
+        .get(&account.id)
+        .copied()
+        .unwrap_or_default();
+
+    Ok(Proposal {
+      account_id: account.id,
+      date: statement.date,
+      balance: statement.balance,
+      actual_balance,
+      matches,
+      unmatched_lines,
+      unmatched_entries,
+    })
+  }
+
+  /// Mark the confirmed records as reconciled, and assert the closing balance with a Check entry
+  pub async fn create(
+    db: &impl ConnectionTrait,
+    commands: Vec<CommandCreate>,
+  ) -> crate::Result<Vec<Root>> {
+    let mut roots = Vec::new();
+    // The entries confirmed by the earlier commands of the batch, which are not saved yet
+    let mut confirmed = HashSet::<Uuid>::new();
+    for command in commands {
+      let account = Self::find_account(db, command.account_id).await?;
+      let unreconciled: HashSet<_> = Self::find_unreconciled(db, &account, command.date)
+        .await?
+        .into_iter()
+        .map(|entry| entry.id)
+        .collect();
+
+      let reconciled: Vec<_> = reconciliation_entry::Entity::find()
+        .filter(reconciliation_entry::Column::EntryId.is_in(command.entries.clone()))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|model| model.entry_id)
+        .chain(command.entries.intersection(&confirmed).copied())
+        .sorted()
+        .dedup()
+        .collect();
+      if !reconciled.is_empty() {
+        return Err(crate::Error::ExistingEntity(ErrorExistingEntity {
+          entity: TYPE.to_string(),
+          values: vec![(FIELD_ENTRIES.to_string(), reconciled.iter().join(", "))],
+        }));
+      }
+
+      if let Some(missing) = command.entries.iter().sorted().find(|id| !unreconciled.contains(id)) {
+        return Err(crate::Error::NotFound(ErrorNotFound {
+          entity: entry::TYPE.to_string(),
+          values: vec![
+            (FIELD_ID.to_string(), missing.to_string()),
+            (FIELD_ACCOUNT.to_string(), account.id.to_string()),
+          ],
+        }));
+      }
+
+      let id = Uuid::new_v4();
+      let checks = entry::Root::create(
+        db,
+        vec![entry::CommandCreate {
+          journal_id: account.journal_id,
+          name: format!("Reconciliation: {}", id),
+          description: account.name.clone(),
+          typ: entry::Type::Check,
+          date: command.date,
+          tags: HashSet::default(),
+          items: vec![entry::Item {
+            account: account.id,
+            amount: command.balance,
+            price: Decimal::ONE,
+          }],
+          payee_id: None,
+        }],
+      )
+      .await?;
+
+      confirmed.extend(command.entries.iter().copied());
+      for check in checks {
+        roots.push(Root {
+          id,
+          account_id: account.id,
+          check_id: check.id,
+          date: command.date,
+          balance: command.balance,
+          entries: command.entries.clone(),
+        });
+      }
+    }
+
+    Self::save(db, roots).await
+  }
+
+  async fn find_account(db: &impl ConnectionTrait, id: Uuid) -> crate::Result<account::Root> {
+    account::Root::find_one(
+      db,
+      Some(account::Query { id: HashSet::from_iter([id]), ..Default::default() }),
+    )
+    .await?
+    .ok_or_else(|| {
+      crate::Error::NotFound(ErrorNotFound {
+        entity: account::TYPE.to_string(),
+        values: vec![(FIELD_ID.to_string(), id.to_string())],
+      })
+    })
+  }
+
+  /// The records of the account on or before the date, not reconciled yet
+  async fn find_unreconciled(
+    db: &impl ConnectionTrait,
+    account: &account::Root,
+    date: NaiveDate,
+  ) -> crate::Result<Vec<entry::Root>> {
+    entry::Root::find_all(
+      db,
+      Some(entry::Query {
+        account_id: HashSet::from_iter([account.id]),
+        typ: Some(entry::Type::Record),
+        end: Some(date),
+        reconciled: Some(false),
+        ..Default::default()
+      }),
+      None,
+      None,
+    )
+    .await
+  }
+}
+
+/// The reconciliations of the entries, used to mark them as reconciled
+pub(crate) async fn find_reconciled(
+  db: &impl ConnectionTrait,
+  entry_ids: HashSet<Uuid>,
+) -> crate::Result<HashMap<Uuid, Uuid>> {
+  Ok(
+    reconciliation_entry::Entity::find()
+      .filter(reconciliation_entry::Column::EntryId.is_in(entry_ids))
+      .all(db)
+      .await?
+      .into_iter()
+      .map(|model| (model.entry_id, model.reconciliation_id))
+      .collect(),
+  )
+}
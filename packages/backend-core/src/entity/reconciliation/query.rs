@@ -0,0 +1,91 @@
+use crate::entity::{reconciliation, reconciliation_entry};
+use chrono::NaiveDate;
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Cond, IntoCondition};
+use sea_orm::{Condition, QuerySelect, QueryTrait};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Query {
+  #[serde(default)]
+  pub id: HashSet<Uuid>,
+  #[serde(default)]
+  pub account_id: HashSet<Uuid>,
+  #[serde(default)]
+  pub entry_id: HashSet<Uuid>,
+  #[serde(default)]
+  pub start: Option<NaiveDate>,
+  #[serde(default)]
+  pub end: Option<NaiveDate>,
+}
+
+impl IntoCondition for Query {
+  fn into_condition(self) -> Condition {
+    let mut cond = Cond::all();
+
+    if !self.id.is_empty() {
+      cond = cond.add(reconciliation::Column::Id.is_in(self.id));
+    }
+
+    if !self.account_id.is_empty() {
+      cond = cond.add(reconciliation::Column::AccountId.is_in(self.account_id));
+    }
+
+    if !self.entry_id.is_empty() {
+      cond = cond.add(
+        reconciliation::Column::Id.in_subquery(
+          reconciliation_entry::Entity::find()
+            .select_only()
+            .distinct()
+            .column(reconciliation_entry::Column::ReconciliationId)
+            .filter(reconciliation_entry::Column::EntryId.is_in(self.entry_id))
+            .into_query(),
+        ),
+      );
+    }
+
+    if let Some(start) = self.start {
+      cond = cond.add(reconciliation::Column::Date.gte(start));
+    }
+
+    if let Some(end) = self.end {
+      cond = cond.add(reconciliation::Column::Date.lte(end));
+    }
+
+    cond
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::entity::reconciliation;
+  use chrono::NaiveDate;
+  use sea_orm::{DbBackend, EntityTrait, QueryFilter, QueryTrait};
+  use std::collections::HashSet;
+  use uuid::uuid;
+
+  #[test]
+  fn test_query() -> anyhow::Result<()> {
+    let query = reconciliation::Query {
+      id: HashSet::from_iter([uuid!("50a1b556-b99d-4ae0-bfba-d117f9a958de")]),
+      account_id: HashSet::from_iter([uuid!("50a1b556-b99d-4ae0-bfba-d117f9a958de")]),
+      entry_id: HashSet::from_iter([uuid!("50a1b556-b99d-4ae0-bfba-d117f9a958de")]),
+      start: None,
+      end: Some(NaiveDate::from_ymd_opt(2023, 12, 31).unwrap()),
+    };
+
+    assert_eq!(
+      [r#"SELECT "reconciliations"."id", "reconciliations"."account_id", "reconciliations"."check_id", "reconciliations"."date", "reconciliations"."balance" FROM "reconciliations""#,
+        r#"WHERE "reconciliations"."id" IN ('50a1b556-b99d-4ae0-bfba-d117f9a958de')"#,
+        r#"AND "reconciliations"."account_id" IN ('50a1b556-b99d-4ae0-bfba-d117f9a958de')"#,
+        r#"AND "reconciliations"."id" IN (SELECT DISTINCT "reconciliation_entries"."reconciliation_id" FROM "reconciliation_entries" WHERE "reconciliation_entries"."entry_id" IN ('50a1b556-b99d-4ae0-bfba-d117f9a958de'))"#,
+        r#"AND "reconciliations"."date" <= '2023-12-31'"#].join(" "),
+      reconciliation::Entity::find().filter(query).build(DbBackend::Sqlite).to_string()
+    );
+
+    Ok(())
+  }
+}
@@ -0,0 +1,151 @@
+use crate::entity::entry;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const DEFAULT_WINDOW_DAYS: u64 = 3;
+
+/// The bank statement of an account, to be reconciled with the existing records
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Statement {
+  pub account_id: Uuid,
+  /// The closing date
+  pub date: NaiveDate,
+  /// The closing balance
+  pub balance: Decimal,
+  #[serde(default)]
+  pub lines: Vec<StatementLine>,
+  /// How many days a record can be away from the statement line to match, `3` by default
+  #[serde(default)]
+  pub window_days: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StatementLine {
+  pub date: NaiveDate,
+  pub amount: Decimal,
+  #[serde(default)]
+  pub description: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Proposal {
+  pub account_id: Uuid,
+  pub date: NaiveDate,
+  pub balance: Decimal,
+  /// The balance of the account at the closing date, according to the records
+  pub actual_balance: Decimal,
+  pub matches: Vec<Match>,
+  /// The indices of the statement lines without any matched record
+  pub unmatched_lines: Vec<usize>,
+  /// The unreconciled records not matched by any statement line
+  pub unmatched_entries: Vec<Uuid>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Match {
+  /// The index of the statement line
+  pub line: usize,
+  pub entry_id: Uuid,
+}
+
+/// Match each line with the closest record of the same amount in the window, each record is matched
+/// at most once
+pub(crate) fn do_match(
+  account_id: Uuid,
+  lines: &[StatementLine],
+  entries: &[entry::Root],
+  window_days: u64,
+) -> (Vec<Match>, Vec<usize>, Vec<Uuid>) {
+  let mut candidates: Vec<_> = entries
+    .iter()
+    .map(|entry| {
+      let value: Decimal = entry
+        .items
+        .iter()
+        .filter(|item| item.account == account_id)
+        .map(|item| item.amount * item.price)
+        .sum();
+      (entry.id, entry.date, value)
+    })
+    .collect();
+  candidates.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+
+  let mut matches = Vec::new();
+  let mut unmatched_lines = Vec::new();
+  for (idx, line) in lines.iter().enumerate() {
+    let found = candidates
+      .iter()
+      .enumerate()
+      .filter(|(_, (_, date, value))| {
+        *value == line.amount && (*date - line.date).num_days().unsigned_abs() <= window_days
+      })
+      .min_by_key(|(_, (_, date, _))| (*date - line.date).num_days().abs())
+      .map(|(pos, _)| pos);
+
+    match found {
+      Some(pos) => {
+        let (entry_id, _, _) = candidates.remove(pos);
+        matches.push(Match { line: idx, entry_id });
+      }
+      None => unmatched_lines.push(idx),
+    }
+  }
+
+  (matches, unmatched_lines, candidates.into_iter().map(|(id, _, _)| id).collect())
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::entity::entry;
+  use crate::entity::reconciliation::{do_match, Match, StatementLine};
+  use chrono::NaiveDate;
+  use rust_decimal::Decimal;
+  use rust_decimal_macros::dec;
+  use std::collections::HashSet;
+  use uuid::Uuid;
+
+  #[test]
+  fn test_do_match() -> anyhow::Result<()> {
+    let account_id = Uuid::new_v4();
+    let day = |day| NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+    let new_entry = |date, amount: Decimal| entry::Root {
+      id: Uuid::new_v4(),
+      journal_id: Uuid::new_v4(),
+      name: format!("Entry: {}", date),
+      description: "".to_string(),
+      typ: entry::Type::Record,
+      date,
+      tags: HashSet::default(),
+      items: vec![entry::Item { account: account_id, amount, price: dec!(1) }],
+      payee_id: None,
+      version: 0,
+      created_at: Default::default(),
+      updated_at: Default::default(),
+    };
+    let entries =
+      vec![new_entry(day(1), dec!(10)), new_entry(day(6), dec!(10)), new_entry(day(9), dec!(30))];
+    let new_line = |date, amount| StatementLine { date, amount, description: "".to_string() };
+    let lines = vec![
+      new_line(day(5), dec!(10)),
+      new_line(day(2), dec!(10)),
+      new_line(day(20), dec!(30)),
+      new_line(day(2), dec!(10)),
+    ];
+
+    let (matches, unmatched_lines, unmatched_entries) = do_match(account_id, &lines, &entries, 3);
+    assert_eq!(
+      vec![Match { line: 0, entry_id: entries[1].id }, Match { line: 1, entry_id: entries[0].id }],
+      matches
+    );
+    assert_eq!(vec![2, 3], unmatched_lines);
+    assert_eq!(vec![entries[2].id], unmatched_entries);
+
+    Ok(())
+  }
+}
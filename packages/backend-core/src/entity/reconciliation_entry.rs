@@ -0,0 +1,47 @@
+use crate::entity::{entry, reconciliation};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "reconciliation_entries")]
+pub struct Model {
+  #[sea_orm(primary_key)]
+  pub reconciliation_id: Uuid,
+  #[sea_orm(primary_key)]
+  pub entry_id: Uuid,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+  #[sea_orm(
+    belongs_to = "reconciliation::Entity",
+    from = "Column::ReconciliationId",
+    to = "reconciliation::Column::Id",
+    on_update = "Cascade",
+    on_delete = "Cascade"
+  )]
+  Reconciliation,
+
+  #[sea_orm(
+    belongs_to = "entry::Entity",
+    from = "Column::EntryId",
+    to = "entry::Column::Id",
+    on_update = "Cascade",
+    on_delete = "Cascade"
+  )]
+  Entry,
+}
+
+impl Related<reconciliation::Entity> for Entity {
+  fn to() -> RelationDef {
+    Relation::Reconciliation.def()
+  }
+}
+
+impl Related<entry::Entity> for Entity {
+  fn to() -> RelationDef {
+    Relation::Entry.def()
+  }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
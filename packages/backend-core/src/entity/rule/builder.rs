@@ -0,0 +1,121 @@
+use crate::entity::rule::{
+  Actions, Conditions, Root, FIELD_ACCOUNT, FIELD_ACTIONS, FIELD_CONDITIONS, FIELD_MAX_AMOUNT,
+  FIELD_MIN_AMOUNT, TYPE,
+};
+use crate::entity::{account, normalize_name, normalize_tags, FIELD_ID, FIELD_JOURNAL};
+use crate::error::{ErrorNotFound, ErrorOutOfRange, ErrorRequiredField};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[derive(Debug, Default)]
+pub struct Builder {
+  id: Option<Uuid>,
+  journal_id: Option<Uuid>,
+  name: String,
+  conditions: Conditions,
+  actions: Actions,
+}
+
+impl From<Root> for Builder {
+  fn from(value: Root) -> Self {
+    Builder {
+      id: Some(value.id),
+      journal_id: Some(value.journal_id),
+      name: value.name,
+      conditions: value.conditions,
+      actions: value.actions,
+    }
+  }
+}
+
+impl Builder {
+  pub fn build(self, accounts: &HashMap<Uuid, account::Root>) -> crate::Result<Root> {
+    let required = |field: &str| {
+      crate::Error::RequiredField(ErrorRequiredField {
+        entity: TYPE.to_string(),
+        field: field.to_string(),
+      })
+    };
+
+    let name = normalize_name(TYPE, self.name)?;
+    let journal_id = self.journal_id.ok_or_else(|| required(FIELD_JOURNAL))?;
+
+    let normalize = |pattern: Option<String>| {
+      pattern.map(|pattern| pattern.trim().to_string()).filter(|pattern| !pattern.is_empty())
+    };
+    let mut conditions = self.conditions;
+    conditions.name = normalize(conditions.name);
+    conditions.description = normalize(conditions.description);
+    if conditions == Conditions::default() {
+      return Err(required(FIELD_CONDITIONS));
+    }
+    for (field, amount) in
+      [(FIELD_MIN_AMOUNT, conditions.min_amount), (FIELD_MAX_AMOUNT, conditions.max_amount)]
+    {
+      if amount.is_some_and(|amount| amount.is_sign_negative()) {
+        return Err(crate::Error::OutOfRange(ErrorOutOfRange {
+          entity: TYPE.to_string(),
+          field: field.to_string(),
+          start: Some(Decimal::ZERO.to_string()),
+          end: None,
+        }));
+      }
+    }
+    if let (Some(min_amount), Some(max_amount)) = (conditions.min_amount, conditions.max_amount) {
+      if min_amount > max_amount {
+        return Err(crate::Error::OutOfRange(ErrorOutOfRange {
+          entity: TYPE.to_string(),
+          field: FIELD_MAX_AMOUNT.to_string(),
+          start: Some(min_amount.to_string()),
+          end: None,
+        }));
+      }
+    }
+
+    let mut actions = self.actions;
+    actions.rename = actions.rename.map(|rename| normalize_name(TYPE, rename)).transpose()?;
+    actions.tags = normalize_tags(TYPE, actions.tags)?;
+    if actions == Actions::default() {
+      return Err(required(FIELD_ACTIONS));
+    }
+    // The counter-accounts are the ones other than the source account
+    if actions.counter_account_id.is_some() && conditions.account_id.is_none() {
+      return Err(required(FIELD_ACCOUNT));
+    }
+
+    for account_id in conditions.account_id.into_iter().chain(actions.counter_account_id) {
+      if accounts.get(&account_id).is_none_or(|account| account.journal_id != journal_id) {
+        return Err(crate::Error::NotFound(ErrorNotFound {
+          entity: account::TYPE.to_string(),
+          values: vec![
+            (FIELD_JOURNAL.to_string(), journal_id.to_string()),
+            (FIELD_ID.to_string(), account_id.to_string()),
+          ],
+        }));
+      }
+    }
+
+    Ok(Root { id: self.id.unwrap_or_else(Uuid::new_v4), journal_id, name, conditions, actions })
+  }
+
+  pub fn id(self, id: Uuid) -> Builder {
+    Builder { id: Some(id), ..self }
+  }
+
+  pub fn journal_id(self, journal_id: Uuid) -> Builder {
+    Builder { journal_id: Some(journal_id), ..self }
+  }
+
+  pub fn name(self, name: impl ToString) -> Builder {
+    Builder { name: name.to_string(), ..self }
+  }
+
+  pub fn conditions(self, conditions: Conditions) -> Builder {
+    Builder { conditions, ..self }
+  }
+
+  pub fn actions(self, actions: Actions) -> Builder {
+    Builder { actions, ..self }
+  }
+}
@@ -0,0 +1,60 @@
+use crate::entity::entry;
+use crate::entity::rule::{Actions, Conditions};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "commandType")]
+pub enum Command {
+  #[serde(rename = "rules:create")]
+  Create(CommandCreate),
+  #[serde(rename = "rules:update")]
+  Update(CommandUpdate),
+  #[serde(rename = "rules:delete")]
+  Delete(CommandDelete),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandCreate {
+  pub journal_id: Uuid,
+  pub name: String,
+  pub conditions: Conditions,
+  pub actions: Actions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommandUpdate {
+  pub id: Uuid,
+  #[serde(default)]
+  pub name: String,
+  /// The whole conditions are replaced if present
+  #[serde(default)]
+  pub conditions: Option<Conditions>,
+  /// The whole actions are replaced if present
+  #[serde(default)]
+  pub actions: Option<Actions>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommandDelete {
+  #[serde(default)]
+  pub id: HashSet<Uuid>,
+}
+
+/// Apply the rules to the entries of the journal, such as the ones just created by an import
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandApply {
+  pub journal_id: Uuid,
+  /// All the rules of the journal if empty
+  #[serde(default)]
+  pub rule_id: HashSet<Uuid>,
+  /// Limited to the journal, all the entries of it by default
+  #[serde(default)]
+  pub query: entry::Query,
+  /// Only return the proposed changes, without saving them
+  #[serde(default)]
+  pub preview: bool,
+}
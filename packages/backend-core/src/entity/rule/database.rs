@@ -0,0 +1,56 @@
+use crate::entity::{account, journal};
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "rules")]
+pub struct Model {
+  #[sea_orm(primary_key, auto_increment = false)]
+  pub id: Uuid,
+  #[sea_orm(indexed)]
+  pub journal_id: Uuid,
+  #[sea_orm(indexed)]
+  pub name: String,
+  pub name_pattern: Option<String>,
+  pub description_pattern: Option<String>,
+  pub min_amount: Option<Decimal>,
+  pub max_amount: Option<Decimal>,
+  pub account_id: Option<Uuid>,
+  pub counter_account_id: Option<Uuid>,
+  pub rename: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+  #[sea_orm(
+    belongs_to = "journal::Entity",
+    from = "Column::JournalId",
+    to = "journal::Column::Id",
+    on_update = "Cascade",
+    on_delete = "Cascade"
+  )]
+  Journal,
+  #[sea_orm(
+    belongs_to = "account::Entity",
+    from = "Column::AccountId",
+    to = "account::Column::Id",
+    on_update = "Cascade",
+    on_delete = "Cascade"
+  )]
+  Account,
+  #[sea_orm(
+    belongs_to = "account::Entity",
+    from = "Column::CounterAccountId",
+    to = "account::Column::Id",
+    on_update = "Cascade",
+    on_delete = "Cascade"
+  )]
+  CounterAccount,
+}
+
+impl Related<journal::Entity> for Entity {
+  fn to() -> RelationDef {
+    Relation::Journal.def()
+  }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,647 @@
+mod builder;
+mod command;
+mod database;
+mod query;
+
+pub use builder::*;
+pub use command::*;
+pub use database::*;
+pub use query::*;
+
+use crate::actor::Actor;
+use crate::entity::membership::Role;
+use crate::entity::{
+  account, entry, history, journal, reconciliation, rule_tag, ReadRoot, WriteRoot, FIELD_ID,
+  FIELD_JOURNAL, FIELD_NAME,
+};
+use crate::error::{ErrorExistingEntity, ErrorNotFound};
+use crate::import;
+use itertools::Itertools;
+use rust_decimal::Decimal;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+  ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel, Order, QueryFilter, QueryOrder,
+  QuerySelect,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+pub const TYPE: &str = "Rule";
+pub const FIELD_CONDITIONS: &str = "conditions";
+pub const FIELD_ACTIONS: &str = "actions";
+pub const FIELD_MIN_AMOUNT: &str = "conditions.minAmount";
+pub const FIELD_MAX_AMOUNT: &str = "conditions.maxAmount";
+pub const FIELD_ACCOUNT: &str = "conditions.accountId";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sort {
+  #[serde(rename = "name")]
+  Name,
+  #[serde(rename = "-name")]
+  MinusName,
+}
+
+impl From<Sort> for (Column, Order) {
+  fn from(value: Sort) -> Self {
+    match value {
+      Sort::Name => (Column::Name, Order::Asc),
+      Sort::MinusName => (Column::Name, Order::Desc),
+    }
+  }
+}
+
+/// All the conditions present must hold for an entry to match
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Conditions {
+  /// Contained in the name of the entry, case-insensitively
+  #[serde(default)]
+  pub name: Option<String>,
+  /// Contained in the description of the entry, case-insensitively
+  #[serde(default)]
+  pub description: Option<String>,
+  /// The amount of the item of the source account, or the largest item without one
+  #[serde(default)]
+  pub min_amount: Option<Decimal>,
+  #[serde(default)]
+  pub max_amount: Option<Decimal>,
+  /// The source account, such as the one the entries are imported into
+  #[serde(default)]
+  pub account_id: Option<Uuid>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Actions {
+  /// Merge the items of the accounts other than the source one into an item of this account
+  #[serde(default)]
+  pub counter_account_id: Option<Uuid>,
+  #[serde(default)]
+  pub tags: HashSet<String>,
+  #[serde(default)]
+  pub rename: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Root {
+  pub id: Uuid,
+  pub journal_id: Uuid,
+  pub name: String,
+  pub conditions: Conditions,
+  pub actions: Actions,
+}
+
+/// The change of an entry proposed by the matching rules, in the order of their names
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Change {
+  pub rule_ids: Vec<Uuid>,
+  pub before: entry::Root,
+  pub after: entry::Root,
+  /// The reconciliation the entry is confirmed in, which keeps the change from being saved
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub reconciliation_id: Option<Uuid>,
+}
+
+impl ReadRoot for Root {
+  type Query = Query;
+  type Sort = Sort;
+
+  fn id(&self) -> String {
+    self.id.to_string()
+  }
+
+  async fn find_all(
+    db: &impl ConnectionTrait,
+    query: Option<Query>,
+    limit: Option<u64>,
+    sort: Option<Sort>,
+  ) -> crate::Result<Vec<Root>> {
+    let select =
+      if let Some(query) = query { Entity::find().filter(query) } else { Entity::find() };
+    let select = if let Some(sort) = sort {
+      let (field, order) = Into::<(Column, Order)>::into(sort);
+      select.order_by(field, order)
+    } else {
+      select
+    };
+    let models = select.limit(limit).all(db).await?;
+    Self::from_model(db, models).await
+  }
+}
+
+impl WriteRoot for Root {
+  type Model = Model;
+
+  async fn from_model(
+    db: &impl ConnectionTrait,
+    models: impl IntoIterator<Item = Model>,
+  ) -> crate::Result<Vec<Root>> {
+    let models: Vec<_> = models.into_iter().collect();
+    let mut tags = rule_tag::Entity::find()
+      .filter(rule_tag::Column::RuleId.is_in(models.iter().map(|model| model.id)))
+      .all(db)
+      .await?
+      .into_iter()
+      .into_group_map_by(|tag| tag.rule_id);
+
+    Ok(
+      models
+        .into_iter()
+        .map(|model| Root {
+          id: model.id,
+          journal_id: model.journal_id,
+          name: model.name,
+          conditions: Conditions {
+            name: model.name_pattern,
+            description: model.description_pattern,
+            min_amount: model.min_amount,
+            max_amount: model.max_amount,
+            account_id: model.account_id,
+          },
+          actions: Actions {
+            counter_account_id: model.counter_account_id,
+            tags: tags.remove(&model.id).into_iter().flatten().map(|tag| tag.tag).collect(),
+            rename: model.rename,
+          },
+        })
+        .collect(),
+    )
+  }
+
+  async fn save(
+    db: &impl ConnectionTrait,
+    roots: impl IntoIterator<Item = Root>,
+  ) -> crate::Result<Vec<Root>> {
+    let roots: Vec<Root> = roots.into_iter().collect();
+    if roots.is_empty() {
+      return Ok(roots);
+    }
+
+    let model_ids: HashSet<_> = roots.iter().map(|root| root.id).collect();
+    let mut models = Vec::new();
+    let mut tags = Vec::new();
+    for Root { id, journal_id, name, conditions, actions } in roots {
+      models.push(
+        Model {
+          id,
+          journal_id,
+          name,
+          name_pattern: conditions.name,
+          description_pattern: conditions.description,
+          min_amount: conditions.min_amount,
+          max_amount: conditions.max_amount,
+          account_id: conditions.account_id,
+          counter_account_id: actions.counter_account_id,
+          rename: actions.rename,
+        }
+        .into_active_model(),
+      );
+      for tag in actions.tags {
+        tags.push(rule_tag::Model { rule_id: id, tag }.into_active_model());
+      }
+    }
+
+    rule_tag::Entity::delete_many()
+      .filter(rule_tag::Column::RuleId.is_in(model_ids.clone()))
+      .exec(db)
+      .await?;
+
+    let mut on_conflict = OnConflict::column(Column::Id);
+    on_conflict.update_columns([
+      Column::Name,
+      Column::NamePattern,
+      Column::DescriptionPattern,
+      Column::MinAmount,
+      Column::MaxAmount,
+      Column::AccountId,
+      Column::CounterAccountId,
+      Column::Rename,
+    ]);
+    Entity::insert_many(models).on_conflict(on_conflict).exec(db).await?;
+
+    if !tags.is_empty() {
+      rule_tag::Entity::insert_many(tags).exec(db).await?;
+    }
+
+    Self::find_all(db, Some(Query { id: model_ids, ..Default::default() }), None, None).await
+  }
+
+  async fn delete(
+    db: &impl ConnectionTrait,
+    ids: impl IntoIterator<Item = Uuid>,
+  ) -> crate::Result<()> {
+    Entity::delete_many().filter(Column::Id.is_in(ids)).exec(db).await?;
+    Ok(())
+  }
+}
+
+impl Root {
+  /// Handle the command, which the editors of the journals are allowed to only
+  pub async fn handle(
+    db: &impl ConnectionTrait,
+    actor: &Actor,
+    command: Command,
+  ) -> crate::Result<Vec<Root>> {
+    let (ids, mut journal_ids): (HashSet<_>, HashSet<_>) = match &command {
+      Command::Create(command) => (HashSet::default(), HashSet::from_iter([command.journal_id])),
+      Command::Update(command) => (HashSet::from_iter([command.id]), HashSet::default()),
+      Command::Delete(command) => (command.id.clone(), HashSet::default()),
+    };
+    if !ids.is_empty() {
+      let befores =
+        Self::find_all(db, Some(Query { id: ids, ..Default::default() }), None, None).await?;
+      journal_ids.extend(befores.into_iter().map(|root| root.journal_id));
+    }
+    actor.check_journals(db, journal_ids, Role::Editor).await?;
+
+    match command {
+      Command::Create(command) => Self::create(db, vec![command]).await,
+      Command::Update(command) => Self::update(db, vec![command]).await,
+      Command::Delete(CommandDelete { id }) => {
+        Self::delete(db, id).await?;
+        Ok(Vec::default())
+      }
+    }
+  }
+
+  pub async fn create(
+    db: &impl ConnectionTrait,
+    commands: Vec<CommandCreate>,
+  ) -> crate::Result<Vec<Root>> {
+    let mut roots = Vec::new();
+    for command in commands {
+      let accounts = Self::find_accounts(db, command.journal_id).await?;
+      Self::check_name(db, command.journal_id, &command.name, None).await?;
+
+      roots.push(
+        Builder::default()
+          .journal_id(command.journal_id)
+          .name(command.name)
+          .conditions(command.conditions)
+          .actions(command.actions)
+          .build(&accounts)?,
+      );
+    }
+
+    Self::save(db, roots).await
+  }
+
+  pub async fn update(
+    db: &impl ConnectionTrait,
+    commands: Vec<CommandUpdate>,
+  ) -> crate::Result<Vec<Root>> {
+    let mut models = Self::find_all(
+      db,
+      Some(Query { id: commands.iter().map(|command| command.id).collect(), ..Default::default() }),
+      None,
+      None,
+    )
+    .await?
+    .into_iter()
+    .map(|model| (model.id, model))
+    .collect::<HashMap<_, _>>();
+
+    let mut roots = Vec::new();
+    for command in commands {
+      let model = models.remove(&command.id).ok_or_else(|| {
+        crate::Error::NotFound(ErrorNotFound {
+          entity: TYPE.to_string(),
+          values: vec![(FIELD_ID.to_string(), command.id.to_string())],
+        })
+      })?;
+      let accounts = Self::find_accounts(db, model.journal_id).await?;
+
+      let mut builder = Builder::from(model.clone());
+      if !command.name.is_empty() {
+        Self::check_name(db, model.journal_id, &command.name, Some(model.id)).await?;
+        builder = builder.name(command.name);
+      }
+      if let Some(conditions) = command.conditions {
+        builder = builder.conditions(conditions);
+      }
+      if let Some(actions) = command.actions {
+        builder = builder.actions(actions);
+      }
+      roots.push(builder.build(&accounts)?);
+    }
+
+    Self::save(db, roots).await
+  }
+
+  /// Apply the rules to the entries in the order of the rule names, with each rule seeing the
+  /// changes made by the previous ones, and record the changes saved in the history on behalf of
+  /// the actor, who should be an editor of the journal. The reconciled entries are kept as they
+  /// are, with the changes returned still
+  pub async fn apply(
+    db: &impl ConnectionTrait,
+    actor: &Actor,
+    command: CommandApply,
+  ) -> crate::Result<Vec<Change>> {
+    actor.check_journals(db, [command.journal_id], Role::Editor).await?;
+    let accounts = Self::find_accounts(db, command.journal_id).await?;
+    let rules = Self::find_all(
+      db,
+      Some(Query {
+        id: command.rule_id,
+        journal_id: HashSet::from_iter([command.journal_id]),
+        ..Default::default()
+      }),
+      None,
+      Some(Sort::Name),
+    )
+    .await?;
+    if rules.is_empty() {
+      return Ok(Vec::default());
+    }
+
+    let entries = entry::Root::find_all(
+      db,
+      Some(entry::Query { journal_id: HashSet::from_iter([command.journal_id]), ..command.query }),
+      None,
+      Some(entry::Sort::Date),
+    )
+    .await?;
+
+    let reconciled =
+      reconciliation::find_reconciled(db, entries.iter().map(|entry| entry.id).collect()).await?;
+    let mut taken = import::find_names(db, command.journal_id).await?;
+    let mut changes = Vec::new();
+    for before in entries {
+      let mut after = before.clone();
+      let mut rule_ids = Vec::new();
+      for rule in &rules {
+        if rule.matches(&after) {
+          after = rule.apply_to(after);
+          rule_ids.push(rule.id);
+        }
+      }
+      if rule_ids.is_empty() {
+        continue;
+      }
+
+      // The renamed entries are given ` #2`, ` #3`... for the names unique in the journal, with
+      // their own names released, so applying the rules again keeps the names. The reconciled ones
+      // are not saved, so they keep their names taken
+      let reconciliation_id = reconciled.get(&before.id).copied();
+      if after.name != before.name {
+        if reconciliation_id.is_some() {
+          after.name = import::unique_name(&after.name, &mut taken.clone());
+        } else {
+          taken.remove(&before.name);
+          after.name = import::unique_name(&after.name, &mut taken);
+        }
+      }
+
+      let after = entry::Builder::from(after).build(&accounts)?;
+      let sorted = |entry: &entry::Root| {
+        entry
+          .items
+          .iter()
+          .map(|item| (item.account, item.amount, item.price))
+          .sorted()
+          .collect_vec()
+      };
+      if (&after.name, &after.tags, sorted(&after)) != (&before.name, &before.tags, sorted(&before))
+      {
+        changes.push(Change { rule_ids, before, after, reconciliation_id });
+      }
+    }
+
+    if !command.preview {
+      let saved: Vec<_> =
+        changes.iter().filter(|change| change.reconciliation_id.is_none()).collect();
+      let afters = entry::Root::save(db, saved.iter().map(|change| change.after.clone())).await?;
+      // Recorded as the batch updating the entries, the same as the ones handled
+      let updates = saved
+        .iter()
+        .map(|change| entry::CommandUpdate {
+          id: change.after.id,
+          name: change.after.name.clone(),
+          description: None,
+          typ: None,
+          date: None,
+          tags: Some(change.after.tags.clone()),
+          items: change.after.items.clone(),
+          payee_id: None,
+          expected_version: Some(change.before.version),
+        })
+        .collect();
+      history::Root::record(
+        db,
+        Uuid::new_v4(),
+        entry::TYPE,
+        actor,
+        &entry::Command::Batch(entry::CommandBatch {
+          create: Vec::default(),
+          update: updates,
+          delete: HashSet::default(),
+        }),
+        saved.iter().map(|change| (change.before.id, &change.before)),
+        afters.iter().map(|root| (root.id, root)),
+      )
+      .await?;
+    }
+    Ok(changes)
+  }
+
+  pub fn matches(&self, entry: &entry::Root) -> bool {
+    let contains = |pattern: &Option<String>, value: &str| {
+      pattern.as_ref().is_none_or(|pattern| value.to_lowercase().contains(&pattern.to_lowercase()))
+    };
+    if !contains(&self.conditions.name, &entry.name)
+      || !contains(&self.conditions.description, &entry.description)
+    {
+      return false;
+    }
+
+    let amount = match self.conditions.account_id {
+      Some(account_id) => match entry.items.iter().find(|item| item.account == account_id) {
+        Some(item) => item.amount,
+        None => return false,
+      },
+      None => entry.items.iter().map(|item| item.amount).max().unwrap_or_default(),
+    };
+    self.conditions.min_amount.is_none_or(|min_amount| amount >= min_amount)
+      && self.conditions.max_amount.is_none_or(|max_amount| amount <= max_amount)
+  }
+
+  pub fn apply_to(&self, mut entry: entry::Root) -> entry::Root {
+    if let Some(rename) = &self.actions.rename {
+      entry.name = rename.clone();
+    }
+    entry.tags.extend(self.actions.tags.iter().cloned());
+
+    if let (Some(account_id), Some(counter_account_id)) =
+      (self.conditions.account_id, self.actions.counter_account_id)
+    {
+      let (items, others): (Vec<_>, Vec<_>) =
+        entry.items.into_iter().partition(|item| item.account == account_id);
+      entry.items = items;
+      // Merged at the same price, or by the values at the price of 1 if the prices differ, so
+      // that the value of the entry is kept
+      if let Some(first) = others.first() {
+        entry.items.push(if others.iter().all(|item| item.price == first.price) {
+          entry::Item {
+            account: counter_account_id,
+            amount: others.iter().map(|item| item.amount).sum(),
+            price: first.price,
+          }
+        } else {
+          entry::Item {
+            account: counter_account_id,
+            amount: others.iter().map(|item| item.amount * item.price).sum(),
+            price: Decimal::ONE,
+          }
+        });
+      }
+    }
+    entry
+  }
+
+  async fn find_accounts(
+    db: &impl ConnectionTrait,
+    journal_id: Uuid,
+  ) -> crate::Result<HashMap<Uuid, account::Root>> {
+    if journal::Root::find_one(
+      db,
+      Some(journal::Query { id: HashSet::from_iter([journal_id]), ..Default::default() }),
+    )
+    .await?
+    .is_none()
+    {
+      return Err(crate::Error::NotFound(ErrorNotFound {
+        entity: journal::TYPE.to_string(),
+        values: vec![(FIELD_ID.to_string(), journal_id.to_string())],
+      }));
+    }
+
+    Ok(
+      account::Root::find_all(
+        db,
+        Some(account::Query { journal_id: HashSet::from_iter([journal_id]), ..Default::default() }),
+        None,
+        None,
+      )
+      .await?
+      .into_iter()
+      .map(|account| (account.id, account))
+      .collect(),
+    )
+  }
+
+  async fn check_name(
+    db: &impl ConnectionTrait,
+    journal_id: Uuid,
+    name: &str,
+    id: Option<Uuid>,
+  ) -> crate::Result<()> {
+    let existings = Self::find_all(
+      db,
+      Some(Query {
+        journal_id: HashSet::from_iter([journal_id]),
+        name: HashSet::from_iter([name.to_string()]),
+        ..Default::default()
+      }),
+      None,
+      None,
+    )
+    .await?;
+    if existings.iter().any(|existing| Some(existing.id) != id) {
+      return Err(crate::Error::ExistingEntity(ErrorExistingEntity {
+        entity: TYPE.to_string(),
+        values: vec![
+          (FIELD_JOURNAL.to_string(), journal_id.to_string()),
+          (FIELD_NAME.to_string(), existings.iter().map(|model| &model.name).join(", ")),
+        ],
+      }));
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::entity::entry;
+  use crate::entity::rule::{Actions, Conditions, Root};
+  use chrono::NaiveDate;
+  use rust_decimal_macros::dec;
+  use std::collections::HashSet;
+  use uuid::Uuid;
+
+  #[test]
+  fn test_apply_to() -> anyhow::Result<()> {
+    let (checking, unknown, food, fee) =
+      (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let rule = Root {
+      id: Uuid::new_v4(),
+      journal_id: Uuid::new_v4(),
+      name: "Groceries".to_string(),
+      conditions: Conditions {
+        name: Some("grocery".to_string()),
+        max_amount: Some(dec!(100)),
+        account_id: Some(checking),
+        ..Default::default()
+      },
+      actions: Actions {
+        counter_account_id: Some(food),
+        tags: HashSet::from_iter(["food".to_string()]),
+        rename: Some("Weekly Groceries".to_string()),
+      },
+    };
+    let entry = entry::Root {
+      id: Uuid::new_v4(),
+      journal_id: rule.journal_id,
+      name: "2024-01-05 GROCERY STORE".to_string(),
+      description: String::default(),
+      typ: entry::Type::Record,
+      date: NaiveDate::from_ymd_opt(2024, 1, 5).unwrap(),
+      tags: HashSet::default(),
+      items: vec![
+        entry::Item { account: checking, amount: dec!(50), price: dec!(1) },
+        entry::Item { account: unknown, amount: dec!(48), price: dec!(1) },
+        entry::Item { account: fee, amount: dec!(2), price: dec!(1) },
+      ],
+      payee_id: None,
+      version: 0,
+      created_at: Default::default(),
+      updated_at: Default::default(),
+    };
+
+    assert!(rule.matches(&entry));
+    let after = rule.apply_to(entry.clone());
+    assert_eq!("Weekly Groceries", after.name);
+    assert_eq!(HashSet::from_iter(["food".to_string()]), after.tags);
+    assert_eq!(
+      vec![
+        entry::Item { account: checking, amount: dec!(50), price: dec!(1) },
+        entry::Item { account: food, amount: dec!(50), price: dec!(1) },
+      ],
+      after.items
+    );
+
+    let mut priced = entry.clone();
+    priced.items = vec![
+      entry::Item { account: checking, amount: dec!(50), price: dec!(1) },
+      entry::Item { account: unknown, amount: dec!(2), price: dec!(10) },
+      entry::Item { account: fee, amount: dec!(30), price: dec!(1) },
+    ];
+    assert_eq!(
+      vec![
+        entry::Item { account: checking, amount: dec!(50), price: dec!(1) },
+        entry::Item { account: food, amount: dec!(50), price: dec!(1) },
+      ],
+      rule.apply_to(priced).items
+    );
+
+    let mut other = entry.clone();
+    other.items[0].amount = dec!(150);
+    assert!(!rule.matches(&other));
+    other.items[0].account = unknown;
+    assert!(!rule.matches(&other));
+
+    Ok(())
+  }
+}
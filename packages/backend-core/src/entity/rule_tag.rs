@@ -0,0 +1,33 @@
+use crate::entity::rule;
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+/// The tags added to the matching entries by the rules
+#[derive(Clone, Debug, Hash, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "rule_tags")]
+pub struct Model {
+  #[sea_orm(primary_key)]
+  pub rule_id: Uuid,
+  #[sea_orm(primary_key)]
+  pub tag: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+  #[sea_orm(
+    belongs_to = "rule::Entity",
+    from = "Column::RuleId",
+    to = "rule::Column::Id",
+    on_update = "Cascade",
+    on_delete = "Cascade"
+  )]
+  Rule,
+}
+
+impl Related<rule::Entity> for Entity {
+  fn to() -> RelationDef {
+    Relation::Rule.def()
+  }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
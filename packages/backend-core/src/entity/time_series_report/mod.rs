@@ -0,0 +1,317 @@
+mod query;
+
+pub use query::*;
+
+use crate::entity::hierarchy_report::prefixes;
+use crate::entity::{account, entry, ReadRoot, REPORT_SPLITERATOR};
+use chrono::{Datelike, Days, Months, NaiveDate};
+use rust_decimal::Decimal;
+use sea_orm::ConnectionTrait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Root {
+  pub journal_id: Uuid,
+  pub prefix: String,
+  pub unit: String,
+  pub period: Period,
+  pub mode: Mode,
+  pub points: Vec<Point>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Point {
+  pub start: NaiveDate,
+  pub end: NaiveDate,
+  pub values: HashMap<Uuid, Decimal>,
+}
+
+impl Period {
+  /// The first day of the period containing the date
+  pub fn floor(&self, date: NaiveDate) -> NaiveDate {
+    match self {
+      Period::Day => date,
+      Period::Week => date - Days::new(date.weekday().num_days_from_monday().into()),
+      Period::Month => date.with_day(1).unwrap_or(date),
+      Period::Quarter => {
+        NaiveDate::from_ymd_opt(date.year(), (date.month0() / 3) * 3 + 1, 1).unwrap_or(date)
+      }
+      Period::Year => NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap_or(date),
+    }
+  }
+
+  /// The first day of the next period
+  pub fn next(&self, date: NaiveDate) -> NaiveDate {
+    let start = self.floor(date);
+    match self {
+      Period::Day => start + Days::new(1),
+      Period::Week => start + Days::new(7),
+      Period::Month => start + Months::new(1),
+      Period::Quarter => start + Months::new(3),
+      Period::Year => start + Months::new(12),
+    }
+  }
+}
+
+impl ReadRoot for Root {
+  type Query = Query;
+  type Sort = ();
+
+  fn id(&self) -> String {
+    [self.journal_id.to_string(), self.prefix.clone(), self.unit.clone()].join(REPORT_SPLITERATOR)
+  }
+
+  async fn find_all(
+    db: &impl ConnectionTrait,
+    query: Option<Query>,
+    _limit: Option<u64>,
+    _sort: Option<()>,
+  ) -> crate::Result<Vec<Root>> {
+    let query = query.unwrap_or_default();
+    let mut journal_ids = query.journal_id.clone();
+    // The same as the hierarchy reports, the journals of the IDs are used only if no journal is
+    // given
+    if journal_ids.is_empty() {
+      for id in &query.id {
+        if let Some((journal_id, _)) = id.split_once(REPORT_SPLITERATOR) {
+          if let Ok(journal_id) = Uuid::parse_str(journal_id) {
+            journal_ids.insert(journal_id);
+          }
+        }
+      }
+    }
+
+    let entries = entry::Root::find_all(
+      db,
+      Some(entry::Query {
+        journal_id: journal_ids.clone(),
+        start: if query.mode == Mode::Change { query.start } else { None },
+        end: query.end,
+        typ: Some(entry::Type::Record),
+        ..Default::default()
+      }),
+      None,
+      None,
+    )
+    .await?;
+
+    let accounts = account::Root::find_all(
+      db,
+      Some(account::Query { journal_id: journal_ids, ..Default::default() }),
+      None,
+      None,
+    )
+    .await?;
+
+    let aggregated =
+      Self::do_aggregate(&entries, &accounts, query.start, query.end, query.period, query.mode);
+
+    Ok(if query.id.is_empty() {
+      aggregated
+    } else {
+      aggregated.into_iter().filter(|root| query.id.contains(&root.id())).collect()
+    })
+  }
+}
+
+#[derive(Hash, Debug, Clone, PartialEq, Eq)]
+struct Index {
+  pub journal_id: Uuid,
+  pub prefix: String,
+  pub unit: String,
+}
+
+impl Root {
+  fn do_aggregate(
+    entries: &[entry::Root],
+    accounts: &[account::Root],
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+    period: Period,
+    mode: Mode,
+  ) -> Vec<Root> {
+    let (Some(first), Some(last)) = (
+      start.or_else(|| entries.iter().map(|entry| entry.date).min()),
+      end.or_else(|| entries.iter().map(|entry| entry.date).max()),
+    ) else {
+      return vec![];
+    };
+
+    let mut bucket_starts = Vec::new();
+    let mut current = period.floor(first);
+    while current <= last {
+      bucket_starts.push(current);
+      current = period.next(current);
+    }
+    let bucket_indices: HashMap<_, _> =
+      bucket_starts.iter().enumerate().map(|(idx, start)| (*start, idx)).collect();
+
+    let accounts = accounts.iter().map(|account| (account.id, account)).collect::<HashMap<_, _>>();
+
+    // The opening balance before the first period, and the changes within each period
+    let mut results = HashMap::<Index, HashMap<Uuid, (Decimal, Vec<Decimal>)>>::new();
+    for entry in entries {
+      let bucket = if entry.date < first {
+        None
+      } else {
+        match bucket_indices.get(&period.floor(entry.date)) {
+          Some(idx) => Some(*idx),
+          None => continue,
+        }
+      };
+
+      for item in &entry.items {
+        if let Some(account) = accounts.get(&item.account) {
+          for prefix in prefixes(&account.name) {
+            let index =
+              Index { journal_id: entry.journal_id, prefix, unit: account.unit.to_string() };
+            let (opening, changes) = results
+              .entry(index)
+              .or_default()
+              .entry(account.id)
+              .or_insert_with(|| (Decimal::ZERO, vec![Decimal::ZERO; bucket_starts.len()]));
+            let value = item.amount * item.price;
+            match bucket {
+              Some(idx) => changes[idx] += value,
+              None => *opening += value,
+            }
+          }
+        }
+      }
+    }
+
+    results
+      .into_iter()
+      .map(|(Index { journal_id, prefix, unit }, values)| {
+        let mut running: HashMap<Uuid, Decimal> =
+          values.iter().map(|(account_id, (opening, _))| (*account_id, *opening)).collect();
+        let points = bucket_starts
+          .iter()
+          .enumerate()
+          .map(|(idx, start)| Point {
+            start: *start,
+            end: period.next(*start) - Days::new(1),
+            values: values
+              .iter()
+              .map(|(account_id, (_, changes))| {
+                let value = match mode {
+                  Mode::Change => changes[idx],
+                  Mode::Balance => {
+                    let balance = running.entry(*account_id).or_default();
+                    *balance += changes[idx];
+                    *balance
+                  }
+                };
+                (*account_id, value)
+              })
+              .collect(),
+          })
+          .collect();
+
+        Root { journal_id, prefix, unit, period, mode, points }
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::entity::time_series_report::{Mode, Period, Root};
+  use crate::entity::{account, entry};
+  use chrono::NaiveDate;
+  use rust_decimal_macros::dec;
+  use std::collections::HashSet;
+  use uuid::Uuid;
+
+  #[test]
+  fn test_period() -> anyhow::Result<()> {
+    let date = NaiveDate::from_ymd_opt(2024, 5, 16).unwrap();
+
+    assert_eq!(date, Period::Day.floor(date));
+    assert_eq!(NaiveDate::from_ymd_opt(2024, 5, 13).unwrap(), Period::Week.floor(date));
+    assert_eq!(NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(), Period::Month.floor(date));
+    assert_eq!(NaiveDate::from_ymd_opt(2024, 4, 1).unwrap(), Period::Quarter.floor(date));
+    assert_eq!(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(), Period::Year.floor(date));
+
+    assert_eq!(NaiveDate::from_ymd_opt(2024, 5, 20).unwrap(), Period::Week.next(date));
+    assert_eq!(NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(), Period::Month.next(date));
+    assert_eq!(NaiveDate::from_ymd_opt(2024, 7, 1).unwrap(), Period::Quarter.next(date));
+    assert_eq!(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(), Period::Year.next(date));
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_do_aggregate() -> anyhow::Result<()> {
+    let journal_id = Uuid::new_v4();
+    let account = account::Root {
+      id: Uuid::new_v4(),
+      journal_id,
+      name: "Assets::Bank".to_string(),
+      description: "".to_string(),
+      unit: "CNY".to_string(),
+      typ: account::Type::Asset,
+      tags: HashSet::default(),
+      version: 0,
+      created_at: Default::default(),
+      updated_at: Default::default(),
+    };
+    let new_entry = |date: NaiveDate, amount| entry::Root {
+      id: Uuid::new_v4(),
+      journal_id,
+      name: format!("Entry: {}", date),
+      description: "".to_string(),
+      typ: entry::Type::Record,
+      date,
+      tags: HashSet::default(),
+      items: vec![entry::Item { account: account.id, amount, price: dec!(1) }],
+      payee_id: None,
+      version: 0,
+      created_at: Default::default(),
+      updated_at: Default::default(),
+    };
+    let entries = vec![
+      new_entry(NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(), dec!(10)),
+      new_entry(NaiveDate::from_ymd_opt(2024, 1, 5).unwrap(), dec!(20)),
+      new_entry(NaiveDate::from_ymd_opt(2024, 1, 25).unwrap(), dec!(30)),
+      new_entry(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(), dec!(40)),
+    ];
+    let start = NaiveDate::from_ymd_opt(2024, 1, 1);
+    let end = NaiveDate::from_ymd_opt(2024, 3, 31);
+
+    let changes = Root::do_aggregate(
+      &entries[1..],
+      std::slice::from_ref(&account),
+      start,
+      end,
+      Period::Month,
+      Mode::Change,
+    );
+    let root = changes.iter().find(|root| root.prefix == "Assets").unwrap();
+    assert_eq!(
+      vec![dec!(50), dec!(0), dec!(40)],
+      root.points.iter().map(|point| point.values[&account.id]).collect::<Vec<_>>()
+    );
+    assert_eq!(NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(), root.points[1].end);
+
+    let balances = Root::do_aggregate(
+      &entries,
+      std::slice::from_ref(&account),
+      start,
+      end,
+      Period::Month,
+      Mode::Balance,
+    );
+    let root = balances.iter().find(|root| root.prefix == "Assets::Bank").unwrap();
+    assert_eq!(
+      vec![dec!(60), dec!(60), dec!(100)],
+      root.points.iter().map(|point| point.values[&account.id]).collect::<Vec<_>>()
+    );
+
+    Ok(())
+  }
+}
@@ -0,0 +1,250 @@
+mod query;
+
+pub use query::*;
+
+use crate::entity::{account, entry, journal, ReadRoot, REPORT_SPLITERATOR};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sea_orm::ConnectionTrait;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Root {
+  pub journal_id: Uuid,
+  pub date: Option<NaiveDate>,
+  pub unit: String,
+  pub items: Vec<Item>,
+  pub total_debit: Decimal,
+  pub total_credit: Decimal,
+  /// If the total debits tie out with the total credits
+  pub balanced: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Item {
+  pub account_id: Uuid,
+  pub name: String,
+  #[serde(rename = "type")]
+  pub typ: account::Type,
+  pub debit: Decimal,
+  pub credit: Decimal,
+}
+
+impl ReadRoot for Root {
+  type Query = Query;
+  type Sort = ();
+
+  fn id(&self) -> String {
+    [self.journal_id.to_string(), self.date.map(|date| date.to_string()).unwrap_or_default()]
+      .join(REPORT_SPLITERATOR)
+  }
+
+  async fn find_all(
+    db: &impl ConnectionTrait,
+    query: Option<Query>,
+    limit: Option<u64>,
+    _sort: Option<()>,
+  ) -> crate::Result<Vec<Root>> {
+    let query = query.unwrap_or_default();
+
+    let journals = journal::Root::find_all(
+      db,
+      Some(journal::Query { id: query.journal_id.clone(), ..Default::default() }),
+      limit,
+      None,
+    )
+    .await?;
+    let journal_ids: HashSet<_> = journals.iter().map(|journal| journal.id).collect();
+
+    let entries = entry::Root::find_all(
+      db,
+      Some(entry::Query {
+        journal_id: journal_ids.clone(),
+        end: query.date,
+        typ: Some(entry::Type::Record),
+        ..Default::default()
+      }),
+      None,
+      None,
+    )
+    .await?;
+
+    let accounts = account::Root::find_all(
+      db,
+      Some(account::Query { journal_id: journal_ids, ..Default::default() }),
+      None,
+      None,
+    )
+    .await?;
+
+    Ok(
+      journals
+        .iter()
+        .map(|journal| Self::do_aggregate(journal, query.date, &entries, &accounts))
+        .collect(),
+    )
+  }
+}
+
+impl Root {
+  fn do_aggregate(
+    journal: &journal::Root,
+    date: Option<NaiveDate>,
+    entries: &[entry::Root],
+    accounts: &[account::Root],
+  ) -> Root {
+    let mut items = accounts
+      .iter()
+      .filter(|account| account.journal_id == journal.id)
+      .map(|account| {
+        (
+          account.id,
+          Item {
+            account_id: account.id,
+            name: account.name.clone(),
+            typ: account.typ,
+            debit: Decimal::ZERO,
+            credit: Decimal::ZERO,
+          },
+        )
+      })
+      .collect::<HashMap<_, _>>();
+
+    for entry in entries.iter().filter(|entry| entry.journal_id == journal.id) {
+      for entry_item in &entry.items {
+        if let Some(item) = items.get_mut(&entry_item.account) {
+          // Positive values go to the normal side of the account type, negative ones the other
+          let value = entry_item.amount * entry_item.price;
+          if item.typ.is_debit() == value.is_sign_positive() {
+            item.debit += value.abs();
+          } else {
+            item.credit += value.abs();
+          }
+        }
+      }
+    }
+
+    let mut items: Vec<_> = items.into_values().collect();
+    items.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let total_debit: Decimal = items.iter().map(|item| item.debit).sum();
+    let total_credit: Decimal = items.iter().map(|item| item.credit).sum();
+
+    Root {
+      journal_id: journal.id,
+      date,
+      unit: journal.unit.clone(),
+      items,
+      total_debit,
+      total_credit,
+      balanced: total_debit == total_credit,
+    }
+  }
+
+  pub fn to_csv(&self) -> crate::Result<String> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    let mut write = |record: [&str; 4]| {
+      writer.write_record(record).map_err(|err| crate::Error::Internal(err.into()))
+    };
+
+    write(["Account", "Type", "Debit", "Credit"])?;
+    for item in &self.items {
+      write([
+        &item.name,
+        &item.typ.to_string(),
+        &item.debit.to_string(),
+        &item.credit.to_string(),
+      ])?;
+    }
+    write(["Total", "", &self.total_debit.to_string(), &self.total_credit.to_string()])?;
+
+    let bytes = writer.into_inner().map_err(|err| crate::Error::Internal(err.into()))?;
+    String::from_utf8(bytes).map_err(|err| crate::Error::Internal(err.into()))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::entity::trial_balance::Root;
+  use crate::entity::{account, entry, journal};
+  use chrono::NaiveDate;
+  use rust_decimal_macros::dec;
+  use std::collections::HashSet;
+  use uuid::Uuid;
+
+  #[test]
+  fn test_do_aggregate() -> anyhow::Result<()> {
+    let journal = journal::Root {
+      id: Uuid::new_v4(),
+      name: "Journal 1".to_string(),
+      description: "".to_string(),
+      unit: "CNY".to_string(),
+      tags: HashSet::default(),
+      version: 0,
+      created_at: Default::default(),
+      updated_at: Default::default(),
+    };
+    let accounts: Vec<_> = [
+      ("Assets::Bank", account::Type::Asset),
+      ("Expenses::Food", account::Type::Expense),
+      ("Income::Salary", account::Type::Income),
+    ]
+    .into_iter()
+    .map(|(name, typ)| account::Root {
+      id: Uuid::new_v4(),
+      journal_id: journal.id,
+      name: name.to_string(),
+      description: "".to_string(),
+      unit: "CNY".to_string(),
+      typ,
+      tags: HashSet::default(),
+      version: 0,
+      created_at: Default::default(),
+      updated_at: Default::default(),
+    })
+    .collect();
+
+    let entries = vec![entry::Root {
+      id: Uuid::new_v4(),
+      journal_id: journal.id,
+      name: "Entry: 1".to_string(),
+      description: "".to_string(),
+      typ: entry::Type::Record,
+      date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+      tags: HashSet::default(),
+      items: vec![
+        entry::Item { account: accounts[0].id, amount: dec!(80), price: dec!(1) },
+        entry::Item { account: accounts[1].id, amount: dec!(20), price: dec!(1) },
+        entry::Item { account: accounts[2].id, amount: dec!(100), price: dec!(1) },
+      ],
+      payee_id: None,
+      version: 0,
+      created_at: Default::default(),
+      updated_at: Default::default(),
+    }];
+
+    let root = Root::do_aggregate(&journal, None, &entries, &accounts);
+    assert_eq!(dec!(100), root.total_debit);
+    assert_eq!(dec!(100), root.total_credit);
+    assert!(root.balanced);
+
+    assert_eq!(
+      [
+        "Account,Type,Debit,Credit",
+        "Assets::Bank,Asset,80,0",
+        "Expenses::Food,Expense,20,0",
+        "Income::Salary,Income,0,100",
+        "Total,,100,100",
+        "",
+      ]
+      .join("\n"),
+      root.to_csv()?
+    );
+
+    Ok(())
+  }
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "commandType")]
+pub enum Command {
+  #[serde(rename = "users:create")]
+  Create(CommandCreate),
+  #[serde(rename = "users:update")]
+  Update(CommandUpdate),
+  #[serde(rename = "users:delete")]
+  Delete(CommandDelete),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommandCreate {
+  pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommandUpdate {
+  pub id: Uuid,
+  pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommandDelete {
+  #[serde(default)]
+  pub id: HashSet<Uuid>,
+}
@@ -0,0 +1,25 @@
+use crate::entity::membership;
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "users")]
+pub struct Model {
+  #[sea_orm(primary_key, auto_increment = false)]
+  pub id: Uuid,
+  #[sea_orm(unique, indexed)]
+  pub name: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+  #[sea_orm(has_many = "membership::Entity")]
+  Memberships,
+}
+
+impl Related<membership::Entity> for Entity {
+  fn to() -> RelationDef {
+    Relation::Memberships.def()
+  }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
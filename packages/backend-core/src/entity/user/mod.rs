@@ -0,0 +1,195 @@
+mod command;
+mod database;
+mod query;
+
+pub use command::*;
+pub use database::*;
+pub use query::*;
+
+use crate::actor::Actor;
+use crate::entity::{
+  ReadRoot, WriteRoot, FIELD_ID, FIELD_NAME, MAX_NAME_LENGTH, MIN_SHORT_TEXT_LENGTH,
+};
+use crate::error::{ErrorExistingEntity, ErrorNotFound, ErrorOutOfRange};
+use itertools::Itertools;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+  ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel, Order, QueryFilter, QueryOrder,
+  QuerySelect,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+pub const TYPE: &str = "User";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sort {
+  #[serde(rename = "name")]
+  Name,
+  #[serde(rename = "-name")]
+  MinusName,
+}
+
+impl From<Sort> for (Column, Order) {
+  fn from(value: Sort) -> Self {
+    match value {
+      Sort::Name => (Column::Name, Order::Asc),
+      Sort::MinusName => (Column::Name, Order::Desc),
+    }
+  }
+}
+
+/// The person handling the commands through the servers, who is allowed the journals by the
+/// memberships
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Root {
+  pub id: Uuid,
+  pub name: String,
+}
+
+impl From<Model> for Root {
+  fn from(value: Model) -> Self {
+    Root { id: value.id, name: value.name }
+  }
+}
+
+impl ReadRoot for Root {
+  type Query = Query;
+  type Sort = Sort;
+
+  fn id(&self) -> String {
+    self.id.to_string()
+  }
+
+  async fn find_all(
+    db: &impl ConnectionTrait,
+    query: Option<Query>,
+    limit: Option<u64>,
+    sort: Option<Sort>,
+  ) -> crate::Result<Vec<Root>> {
+    let select =
+      if let Some(query) = query { Entity::find().filter(query) } else { Entity::find() };
+    let select = if let Some(sort) = sort {
+      let (field, order) = Into::<(Column, Order)>::into(sort);
+      select.order_by(field, order)
+    } else {
+      select
+    };
+    let models = select.limit(limit).all(db).await?;
+    Self::from_model(db, models).await
+  }
+}
+
+impl WriteRoot for Root {
+  type Model = Model;
+
+  async fn from_model(
+    _db: &impl ConnectionTrait,
+    models: impl IntoIterator<Item = Model>,
+  ) -> crate::Result<Vec<Root>> {
+    Ok(models.into_iter().map(Root::from).collect())
+  }
+
+  async fn save(
+    db: &impl ConnectionTrait,
+    roots: impl IntoIterator<Item = Root>,
+  ) -> crate::Result<Vec<Root>> {
+    let roots: Vec<Root> = roots.into_iter().collect();
+    if roots.is_empty() {
+      return Ok(roots);
+    }
+
+    let model_ids: HashSet<_> = roots.iter().map(|root| root.id).collect();
+    let models: Vec<_> = roots
+      .into_iter()
+      .map(|root| Model { id: root.id, name: root.name }.into_active_model())
+      .collect();
+
+    let mut on_conflict = OnConflict::column(Column::Id);
+    on_conflict.update_columns([Column::Name]);
+    Entity::insert_many(models).on_conflict(on_conflict).exec(db).await?;
+
+    Self::find_all(db, Some(Query { id: model_ids, ..Default::default() }), None, None).await
+  }
+
+  async fn delete(
+    db: &impl ConnectionTrait,
+    ids: impl IntoIterator<Item = Uuid>,
+  ) -> crate::Result<()> {
+    Entity::delete_many().filter(Column::Id.is_in(ids)).exec(db).await?;
+    Ok(())
+  }
+}
+
+impl Root {
+  /// Handle the command, which only the system actors are allowed to, as the administrators
+  pub async fn handle(
+    db: &impl ConnectionTrait,
+    actor: &Actor,
+    command: Command,
+  ) -> crate::Result<Vec<Root>> {
+    actor.check_system(TYPE)?;
+
+    match command {
+      Command::Create(CommandCreate { name }) => {
+        let root = Root { id: Uuid::new_v4(), name: normalize_name(name)? };
+        Self::check_names(db, &root).await?;
+        Self::save(db, [root]).await
+      }
+      Command::Update(CommandUpdate { id, name }) => {
+        if Self::find_one(db, Some(Query { id: HashSet::from_iter([id]), ..Default::default() }))
+          .await?
+          .is_none()
+        {
+          return Err(crate::Error::NotFound(ErrorNotFound {
+            entity: TYPE.to_string(),
+            values: vec![(FIELD_ID.to_string(), id.to_string())],
+          }));
+        }
+
+        let root = Root { id, name: normalize_name(name)? };
+        Self::check_names(db, &root).await?;
+        Self::save(db, [root]).await
+      }
+      Command::Delete(CommandDelete { id }) => {
+        Self::delete(db, id).await?;
+        Ok(Vec::default())
+      }
+    }
+  }
+
+  async fn check_names(db: &impl ConnectionTrait, root: &Root) -> crate::Result<()> {
+    let existings = Self::find_all(
+      db,
+      Some(Query { name: HashSet::from_iter([root.name.clone()]), ..Default::default() }),
+      None,
+      None,
+    )
+    .await?;
+    let duplicated =
+      existings.iter().filter(|other| other.id != root.id).map(|other| &other.name).join(", ");
+    if !duplicated.is_empty() {
+      return Err(crate::Error::ExistingEntity(ErrorExistingEntity {
+        entity: TYPE.to_string(),
+        values: vec![(FIELD_NAME.to_string(), duplicated)],
+      }));
+    }
+    Ok(())
+  }
+}
+
+// The user names are often shorter than the names of the other entities, like `Bob`
+fn normalize_name(value: String) -> crate::Result<String> {
+  let value = value.trim().to_string();
+  if value.len() < MIN_SHORT_TEXT_LENGTH || value.len() > MAX_NAME_LENGTH {
+    Err(crate::Error::OutOfRange(ErrorOutOfRange {
+      entity: TYPE.to_string(),
+      field: FIELD_NAME.to_string(),
+      start: Some(MIN_SHORT_TEXT_LENGTH.to_string()),
+      end: Some(MAX_NAME_LENGTH.to_string()),
+    }))
+  } else {
+    Ok(value)
+  }
+}
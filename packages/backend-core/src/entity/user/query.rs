@@ -0,0 +1,38 @@
+use crate::entity::user;
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Cond, IntoCondition};
+use sea_orm::Condition;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Query {
+  #[serde(default)]
+  pub id: HashSet<Uuid>,
+  #[serde(default)]
+  pub name: HashSet<String>,
+}
+
+impl IntoCondition for Query {
+  fn into_condition(self) -> Condition {
+    let mut cond = Cond::all();
+
+    if !self.id.is_empty() {
+      cond = cond.add(user::Column::Id.is_in(self.id));
+    }
+
+    let name: HashSet<String> = self
+      .name
+      .into_iter()
+      .map(|name| name.trim().to_string())
+      .filter(|name| !name.is_empty())
+      .collect();
+    if !name.is_empty() {
+      cond = cond.add(user::Column::Name.is_in(name));
+    }
+
+    cond
+  }
+}
@@ -22,6 +22,12 @@ pub enum Error {
   #[error("{}", .0.detail())]
   RequiredField(ErrorRequiredField),
 
+  #[error("{}", .0.detail())]
+  Conflict(ErrorConflict),
+
+  #[error("{}", .0.detail())]
+  Forbidden(ErrorForbidden),
+
   #[error("{}", .0.detail())]
   Internal(ErrorInternal),
 }
@@ -33,6 +39,8 @@ impl From<Error> for ProblemDetailDef {
       Error::ExistingEntity(err) => ProblemDetailDef::from(err.clone()),
       Error::OutOfRange(err) => ProblemDetailDef::from(err.clone()),
       Error::RequiredField(err) => ProblemDetailDef::from(err.clone()),
+      Error::Conflict(err) => ProblemDetailDef::from(err.clone()),
+      Error::Forbidden(err) => ProblemDetailDef::from(err.clone()),
       Error::Internal(err) => ProblemDetailDef::from(err.clone()),
     }
   }
@@ -62,6 +70,10 @@ impl<'a> Deserialize<'a> for Error {
       Ok(Error::OutOfRange(serde_json::from_value(def.extra).unwrap()))
     } else if def.typ == ErrorRequiredField::typ() {
       Ok(Error::RequiredField(serde_json::from_value(def.extra).unwrap()))
+    } else if def.typ == ErrorConflict::typ() {
+      Ok(Error::Conflict(serde_json::from_value(def.extra).unwrap()))
+    } else if def.typ == ErrorForbidden::typ() {
+      Ok(Error::Forbidden(serde_json::from_value(def.extra).unwrap()))
     } else if def.typ == ErrorInternal::typ() {
       Ok(Error::Internal(serde_json::from_value(def.extra).unwrap()))
     } else {
@@ -181,6 +193,64 @@ impl ProblemDetail for ErrorRequiredField {
   }
 }
 
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ErrorConflict {
+  pub entity: String,
+  pub values: Vec<(String, String)>,
+}
+
+impl ProblemDetail for ErrorConflict {
+  fn typ() -> &'static str {
+    "urn:white-rabbit:error:conflict"
+  }
+
+  fn title() -> &'static str {
+    "Entity Changed Concurrently"
+  }
+
+  fn status() -> StatusCode {
+    StatusCode::CONFLICT
+  }
+
+  fn detail(&self) -> String {
+    format!(
+      "Entity[{}, {}] has been changed since",
+      self.entity,
+      self.values.iter().map(|(f, v)| format!("{} = {}", f, v)).join(", ")
+    )
+  }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ErrorForbidden {
+  pub actor: String,
+  pub entity: String,
+  pub values: Vec<(String, String)>,
+}
+
+impl ProblemDetail for ErrorForbidden {
+  fn typ() -> &'static str {
+    "urn:white-rabbit:error:forbidden"
+  }
+
+  fn title() -> &'static str {
+    "Access Forbidden"
+  }
+
+  fn status() -> StatusCode {
+    StatusCode::FORBIDDEN
+  }
+
+  fn detail(&self) -> String {
+    format!(
+      "Entity[{}, {}] is forbidden to Actor[{}]",
+      self.entity,
+      self.values.iter().map(|(f, v)| format!("{} = {}", f, v)).join(", "),
+      self.actor
+    )
+  }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ErrorInternal {
   pub message: String,
@@ -44,7 +44,8 @@ where
 mod test {
   use crate::entity::{journal, FIELD_ID, FIELD_NAME, MIN_NAME_LENGTH};
   use crate::error::{
-    ErrorExistingEntity, ErrorInternal, ErrorNotFound, ErrorOutOfRange, ErrorRequiredField,
+    ErrorConflict, ErrorExistingEntity, ErrorForbidden, ErrorInternal, ErrorNotFound,
+    ErrorOutOfRange, ErrorRequiredField,
   };
 
   #[test]
@@ -74,6 +75,15 @@ mod test {
         entity: journal::TYPE.to_string(),
         field: FIELD_NAME.to_string(),
       }),
+      crate::Error::Conflict(ErrorConflict {
+        entity: journal::TYPE.to_string(),
+        values: vec![(FIELD_ID.to_string(), "ID3".to_string())],
+      }),
+      crate::Error::Forbidden(ErrorForbidden {
+        actor: "User 1".to_string(),
+        entity: journal::TYPE.to_string(),
+        values: vec![(FIELD_ID.to_string(), "ID4".to_string())],
+      }),
       crate::Error::Internal(ErrorInternal { message: "Invalid DB Connection".to_string() }),
     ];
 
@@ -0,0 +1,3 @@
+//! Exporters rendering the journals into the formats of other tools
+
+pub mod plain_text;
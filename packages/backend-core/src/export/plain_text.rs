@@ -0,0 +1,421 @@
+//! Exporter of the journals as the plain text accounting files of beancount and ledger-cli
+
+use crate::entity::{account, entry, journal, ReadRoot, FIELD_ID};
+use crate::error::ErrorNotFound;
+pub use crate::import::plain_text::Format;
+use chrono::NaiveDate;
+use itertools::Itertools;
+use rust_decimal::Decimal;
+use sea_orm::ConnectionTrait;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Export {
+  pub journal_id: Uuid,
+  pub format: Format,
+}
+
+pub async fn export(db: &impl ConnectionTrait, export: Export) -> crate::Result<String> {
+  let journal = journal::Root::find_one(
+    db,
+    Some(journal::Query { id: HashSet::from_iter([export.journal_id]), ..Default::default() }),
+  )
+  .await?
+  .ok_or_else(|| {
+    crate::Error::NotFound(ErrorNotFound {
+      entity: journal::TYPE.to_string(),
+      values: vec![(FIELD_ID.to_string(), export.journal_id.to_string())],
+    })
+  })?;
+
+  let accounts = account::Root::find_all(
+    db,
+    Some(account::Query { journal_id: HashSet::from_iter([journal.id]), ..Default::default() }),
+    None,
+    Some(account::Sort::Name),
+  )
+  .await?;
+  let entries = entry::Root::find_all(
+    db,
+    Some(entry::Query { journal_id: HashSet::from_iter([journal.id]), ..Default::default() }),
+    None,
+    None,
+  )
+  .await?;
+
+  Ok(render(export.format, &journal, &accounts, &entries))
+}
+
+/// Render the journal, with the amounts signed as the plain text accounting tools expect, that is,
+/// positive for Assets and Expenses, negative for the others
+pub fn render(
+  format: Format,
+  journal: &journal::Root,
+  accounts: &[account::Root],
+  entries: &[entry::Root],
+) -> String {
+  let mut taken = HashSet::new();
+  let names: HashMap<_, _> = accounts
+    .iter()
+    .sorted_by(|a, b| a.name.cmp(&b.name))
+    .map(|account| (account.id, account_name(account, &mut taken)))
+    .collect();
+  let accounts: HashMap<_, _> = accounts.iter().map(|account| (account.id, account)).collect();
+  let journal_unit = commodity(&journal.unit);
+  // The Checks assert the balances at the end of the dates, so after the Records of the same dates
+  let entries: Vec<_> = entries
+    .iter()
+    .sorted_by_key(|entry| (entry.date, entry.typ == entry::Type::Check, &entry.name))
+    .collect();
+  // The accounts are opened on the date of the first entry, as they have no dates of their own
+  let open_date = entries.first().map(|entry| entry.date).unwrap_or_default();
+
+  let mut result = String::new();
+  let sorted_accounts = accounts.values().sorted_by_key(|account| &names[&account.id]);
+  match format {
+    Format::Beancount => {
+      let _ = writeln!(result, "option \"title\" {}", quote(&journal.name));
+      let _ = writeln!(result, "option \"operating_currency\" {}", quote(&journal_unit));
+      for account in sorted_accounts {
+        let _ = writeln!(
+          result,
+          "\n{} open {} {}",
+          open_date,
+          names[&account.id],
+          commodity(&account.unit)
+        );
+        write_metadata(&mut result, "  ", &account.description, &account.tags);
+      }
+    }
+    Format::Ledger => {
+      let _ = writeln!(result, "; {}", single_line(&journal.name));
+      for account in sorted_accounts {
+        let _ = writeln!(result, "\naccount {}", names[&account.id]);
+        if !account.description.is_empty() {
+          let _ = writeln!(result, "    note {}", single_line(&account.description));
+        }
+        if !account.tags.is_empty() {
+          let _ = writeln!(result, "    ; tags: {}", join_tags(&account.tags));
+        }
+      }
+    }
+  }
+
+  for entry in entries {
+    let signed = |item: &entry::Item| -> Option<(&account::Root, Decimal)> {
+      accounts
+        .get(&item.account)
+        .map(|account| (*account, if account.typ.is_debit() { item.amount } else { -item.amount }))
+    };
+
+    match (format, entry.typ) {
+      (Format::Beancount, entry::Type::Record) => {
+        let _ = writeln!(
+          result,
+          "\n{} {} {}",
+          entry.date,
+          if is_balanced(entry, &accounts) { "*" } else { "!" },
+          quote(&entry.name)
+        );
+        write_metadata(&mut result, "  ", &entry.description, &entry.tags);
+        for item in &entry.items {
+          if let Some((account, amount)) = signed(item) {
+            let _ = writeln!(
+              result,
+              "  {}  {}",
+              names[&account.id],
+              posting(account, amount, item.price, &journal_unit)
+            );
+          }
+        }
+      }
+      (Format::Beancount, entry::Type::Check) => {
+        // The balances are asserted at the beginning of the dates in beancount
+        let date = entry.date.succ_opt().unwrap_or(entry.date);
+        for item in &entry.items {
+          if let Some((account, amount)) = signed(item) {
+            let _ = writeln!(
+              result,
+              "\n{} balance {}  {} {}",
+              date,
+              names[&account.id],
+              number(amount),
+              commodity(&account.unit)
+            );
+            let _ = writeln!(result, "  name: {}", quote(&entry.name));
+            write_metadata(&mut result, "  ", &entry.description, &entry.tags);
+          }
+        }
+      }
+      (Format::Ledger, typ) => {
+        let _ = writeln!(result, "\n{} {}", ledger_date(entry.date), single_line(&entry.name));
+        if !entry.description.is_empty() {
+          let _ = writeln!(result, "    ; description: {}", single_line(&entry.description));
+        }
+        if !entry.tags.is_empty() {
+          let _ = writeln!(result, "    ; tags: {}", join_tags(&entry.tags));
+        }
+        for item in &entry.items {
+          if let Some((account, amount)) = signed(item) {
+            let amount = if typ == entry::Type::Record {
+              posting(account, amount, item.price, &journal_unit)
+            } else {
+              let unit = commodity(&account.unit);
+              format!("0 {} = {} {}", unit, number(amount), unit)
+            };
+            let _ = writeln!(result, "    {}    {}", names[&account.id], amount);
+          }
+        }
+      }
+    }
+  }
+
+  result
+}
+
+/// The amount of the posting, with the price in the unit of the journal if the unit of the
+/// account is different
+fn posting(account: &account::Root, amount: Decimal, price: Decimal, journal_unit: &str) -> String {
+  let unit = commodity(&account.unit);
+  if unit == journal_unit {
+    format!("{} {}", number(amount * price), unit)
+  } else {
+    format!("{} {} @ {} {}", number(amount), unit, number(price), journal_unit)
+  }
+}
+
+fn is_balanced(entry: &entry::Root, accounts: &HashMap<Uuid, &account::Root>) -> bool {
+  let total: Decimal = entry
+    .items
+    .iter()
+    .filter_map(|item| {
+      accounts.get(&item.account).map(|account| {
+        let value = item.amount * item.price;
+        if account.typ.is_debit() {
+          value
+        } else {
+          -value
+        }
+      })
+    })
+    .sum();
+  total.is_zero()
+}
+
+fn write_metadata(result: &mut String, indent: &str, description: &str, tags: &HashSet<String>) {
+  if !description.is_empty() {
+    let _ = writeln!(result, "{}description: {}", indent, quote(description));
+  }
+  if !tags.is_empty() {
+    let _ = writeln!(result, "{}tags: {}", indent, quote(&join_tags(tags)));
+  }
+}
+
+fn root_name(typ: account::Type) -> &'static str {
+  match typ {
+    account::Type::Asset => "Assets",
+    account::Type::Liability => "Liabilities",
+    account::Type::Equity => "Equity",
+    account::Type::Income => "Income",
+    account::Type::Expense => "Expenses",
+  }
+}
+
+/// Sanitize the name into the segments of the letters, digits and dashes, each starting with a
+/// capital letter or a digit, under the root of the type, and make it unique
+fn account_name(account: &account::Root, taken: &mut HashSet<String>) -> String {
+  let root = root_name(account.typ);
+  let mut segments: Vec<_> =
+    account.name.split(account::NAME_SPLITERATOR).filter_map(sanitize_segment).collect();
+  if segments.first().is_some_and(|first| first.eq_ignore_ascii_case(root)) {
+    segments.remove(0);
+  }
+  if segments.is_empty() {
+    segments.push("Account".to_string());
+  }
+
+  let name = format!("{}:{}", root, segments.join(":"));
+  let mut candidate = name.clone();
+  let mut idx = 1;
+  while taken.contains(&candidate) {
+    idx += 1;
+    candidate = format!("{}-{}", name, idx);
+  }
+  taken.insert(candidate.clone());
+  candidate
+}
+
+fn sanitize_segment(segment: &str) -> Option<String> {
+  let mut result = String::new();
+  for c in segment.chars() {
+    if c.is_ascii_alphanumeric() {
+      result.push(c);
+    } else if !result.is_empty() && !result.ends_with('-') {
+      result.push('-');
+    }
+  }
+  let result = result.trim_end_matches('-');
+  let mut chars = result.chars();
+  chars.next().map(|first| format!("{}{}", first.to_ascii_uppercase(), chars.as_str()))
+}
+
+/// The commodities are the capital letters, digits and `'._-`, starting with a letter and ending
+/// with a letter or a digit
+fn commodity(unit: &str) -> String {
+  let result: String = unit
+    .to_ascii_uppercase()
+    .chars()
+    .map(|c| if c.is_ascii_alphanumeric() || "'._-".contains(c) { c } else { '-' })
+    .collect();
+  let result = result.trim_end_matches(|c: char| !c.is_ascii_alphanumeric());
+  match result.chars().next() {
+    None => "UNKNOWN".to_string(),
+    Some(first) if first.is_ascii_alphabetic() => result.to_string(),
+    Some(_) => format!("C{}", result),
+  }
+}
+
+fn number(value: Decimal) -> String {
+  value.normalize().to_string()
+}
+
+fn quote(value: &str) -> String {
+  format!("\"{}\"", single_line(value).replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn single_line(value: &str) -> String {
+  value.split_whitespace().join(" ")
+}
+
+fn join_tags(tags: &HashSet<String>) -> String {
+  tags.iter().sorted().join(", ")
+}
+
+fn ledger_date(date: NaiveDate) -> String {
+  date.format("%Y/%m/%d").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::entity::{account, entry, journal};
+  use crate::export::plain_text::{account_name, commodity, render, Format};
+  use chrono::NaiveDate;
+  use rust_decimal_macros::dec;
+  use std::collections::HashSet;
+  use uuid::Uuid;
+
+  fn account(name: &str, unit: &str, typ: account::Type) -> account::Root {
+    account::Root {
+      id: Uuid::new_v4(),
+      journal_id: Uuid::nil(),
+      name: name.to_string(),
+      description: String::default(),
+      unit: unit.to_string(),
+      typ,
+      tags: HashSet::default(),
+      version: 0,
+      created_at: Default::default(),
+      updated_at: Default::default(),
+    }
+  }
+
+  #[test]
+  fn test_account_name() -> anyhow::Result<()> {
+    let mut taken = HashSet::new();
+    let bank = account("Assets::my bank (main)::", "USD", account::Type::Asset);
+    assert_eq!("Assets:My-bank-main", account_name(&bank, &mut taken));
+    assert_eq!("Assets:My-bank-main-2", account_name(&bank, &mut taken));
+    let other = account("银行", "USD", account::Type::Liability);
+    assert_eq!("Liabilities:Account", account_name(&other, &mut taken));
+
+    assert_eq!("USD", commodity("usd"));
+    assert_eq!("C1INCH", commodity("1inch"));
+    assert_eq!("UNKNOWN", commodity("$"));
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_render() -> anyhow::Result<()> {
+    let journal = journal::Root {
+      id: Uuid::nil(),
+      name: "Household".to_string(),
+      description: String::default(),
+      unit: "USD".to_string(),
+      tags: HashSet::default(),
+      version: 0,
+      created_at: Default::default(),
+      updated_at: Default::default(),
+    };
+    let bank = account("Bank", "USD", account::Type::Asset);
+    let salary = account("Salary", "EUR", account::Type::Income);
+    let date = NaiveDate::from_ymd_opt(2024, 1, 5).unwrap();
+    let entries = vec![
+      entry::Root {
+        id: Uuid::new_v4(),
+        journal_id: Uuid::nil(),
+        name: "Salary \"January\"".to_string(),
+        description: "Paid".to_string(),
+        typ: entry::Type::Record,
+        date,
+        tags: HashSet::from_iter(["work".to_string()]),
+        items: vec![
+          entry::Item { account: bank.id, amount: dec!(110), price: dec!(1) },
+          entry::Item { account: salary.id, amount: dec!(100), price: dec!(1.1) },
+        ],
+        payee_id: None,
+        version: 0,
+        created_at: Default::default(),
+        updated_at: Default::default(),
+      },
+      entry::Root {
+        id: Uuid::new_v4(),
+        journal_id: Uuid::nil(),
+        name: "Bank Balance".to_string(),
+        description: String::default(),
+        typ: entry::Type::Check,
+        date,
+        tags: HashSet::default(),
+        items: vec![entry::Item { account: bank.id, amount: dec!(110), price: dec!(1) }],
+        payee_id: None,
+        version: 0,
+        created_at: Default::default(),
+        updated_at: Default::default(),
+      },
+    ];
+    let accounts = vec![bank, salary];
+
+    let beancount = render(Format::Beancount, &journal, &accounts, &entries);
+    assert_eq!(
+      r#"option "title" "Household"
+option "operating_currency" "USD"
+
+2024-01-05 open Assets:Bank USD
+
+2024-01-05 open Income:Salary EUR
+
+2024-01-05 * "Salary \"January\""
+  description: "Paid"
+  tags: "work"
+  Assets:Bank  110 USD
+  Income:Salary  -100 EUR @ 1.1 USD
+
+2024-01-06 balance Assets:Bank  110 USD
+  name: "Bank Balance"
+"#,
+      beancount
+    );
+
+    let ledger = render(Format::Ledger, &journal, &accounts, &entries);
+    assert!(ledger.contains("\naccount Income:Salary\n"));
+    let record = ledger.find("    Income:Salary    -100 EUR @ 1.1 USD\n");
+    let check = ledger.find("    Assets:Bank    0 USD = 110 USD\n");
+    assert!(record.is_some() && check.is_some() && record < check);
+
+    Ok(())
+  }
+}
@@ -0,0 +1,256 @@
+use crate::entity::{account, csv_profile, entry, ReadRoot, FIELD_ID, MAX_DESCRIPTION_LENGTH};
+use crate::error::ErrorNotFound;
+use crate::import::{find_accounts, finish, normal_amount, truncate, ImportResult, Issue};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Import {
+  pub profile_id: Uuid,
+  pub content: String,
+  /// Only parse and validate the content, without creating any entries
+  #[serde(default)]
+  pub preview: bool,
+}
+
+pub async fn import(
+  db: &impl sea_orm::ConnectionTrait,
+  import: Import,
+) -> crate::Result<ImportResult> {
+  let profile = csv_profile::Root::find_one(
+    db,
+    Some(csv_profile::Query { id: HashSet::from_iter([import.profile_id]), ..Default::default() }),
+  )
+  .await?
+  .ok_or_else(|| {
+    crate::Error::NotFound(ErrorNotFound {
+      entity: csv_profile::TYPE.to_string(),
+      values: vec![(FIELD_ID.to_string(), import.profile_id.to_string())],
+    })
+  })?;
+
+  let accounts = find_accounts(db, profile.journal_id).await?;
+  let (commands, issues) = parse(profile.journal_id, &profile.mapping, &accounts, &import.content);
+  finish(db, profile.journal_id, commands, issues, import.preview).await
+}
+
+/// Parse the CSV with the header line into the commands, each with its line number. The amounts
+/// are signed as seen by the account of the mapping, so the positive ones are the inflows
+pub fn parse(
+  journal_id: Uuid,
+  mapping: &csv_profile::Mapping,
+  accounts: &HashMap<Uuid, account::Root>,
+  content: &str,
+) -> (Vec<(usize, entry::CommandCreate)>, Vec<Issue>) {
+  // The delimiter is checked to be ASCII when the profile is saved
+  let mut reader = ::csv::ReaderBuilder::new()
+    .delimiter(mapping.delimiter as u8)
+    .flexible(true)
+    .trim(::csv::Trim::All)
+    .from_reader(content.as_bytes());
+
+  let headers = match reader.headers() {
+    Ok(headers) => headers.clone(),
+    Err(err) => return (vec![], vec![Issue { line: 1, message: err.to_string() }]),
+  };
+  let find_column = |column: &Option<String>| -> Result<Option<usize>, Issue> {
+    match column {
+      Some(column) => headers
+        .iter()
+        .position(|header| header == column)
+        .map(Some)
+        .ok_or_else(|| Issue { line: 1, message: format!("Column not found: {}", column) }),
+      None => Ok(None),
+    }
+  };
+  let columns = (
+    find_column(&Some(mapping.date_column.clone())),
+    find_column(&mapping.amount_column),
+    find_column(&mapping.debit_column),
+    find_column(&mapping.credit_column),
+    find_column(&mapping.description_column),
+  );
+  let (Ok(Some(date)), Ok(amount), Ok(debit), Ok(credit), Ok(description)) = columns else {
+    let issues = [columns.0.map(|_| ()), columns.1.map(|_| ()), columns.2.map(|_| ())]
+      .into_iter()
+      .chain([columns.3.map(|_| ()), columns.4.map(|_| ())])
+      .filter_map(Result::err)
+      .collect();
+    return (vec![], issues);
+  };
+
+  let mut commands = Vec::new();
+  let mut issues = Vec::new();
+  for record in reader.records() {
+    let record = match record {
+      Ok(record) => record,
+      Err(err) => {
+        let line = err.position().map(|pos| pos.line() as usize).unwrap_or_default();
+        issues.push(Issue { line, message: err.to_string() });
+        continue;
+      }
+    };
+    let line = record.position().map(|pos| pos.line() as usize).unwrap_or_default();
+    let cell = |idx: Option<usize>| idx.and_then(|idx| record.get(idx)).unwrap_or_default();
+
+    let date = match NaiveDate::parse_from_str(cell(Some(date)), &mapping.date_format) {
+      Ok(date) => date,
+      Err(_) => {
+        issues.push(Issue {
+          line,
+          message: format!(
+            "Invalid date `{}` for format `{}`",
+            cell(Some(date)),
+            mapping.date_format
+          ),
+        });
+        continue;
+      }
+    };
+
+    let value = if amount.is_some() {
+      parse_amount(cell(amount), mapping.decimal_separator)
+    } else {
+      parse_amount(cell(credit), mapping.decimal_separator).and_then(|credit| {
+        parse_amount(cell(debit), mapping.decimal_separator).map(|debit| credit - debit)
+      })
+    };
+    let value = match value {
+      Some(value) if !value.is_zero() => value,
+      Some(_) => {
+        issues.push(Issue { line, message: "Zero amount".to_string() });
+        continue;
+      }
+      None => {
+        issues.push(Issue { line, message: "Invalid amount".to_string() });
+        continue;
+      }
+    };
+
+    // The inflows are on the debit side of the account, and the outflows on the credit side
+    let amounts = (
+      normal_amount(value, accounts.get(&mapping.account_id)),
+      normal_amount(-value, accounts.get(&mapping.counter_account_id)),
+    );
+    let (Some(amount), Some(counter_amount)) = amounts else {
+      let flow = if value.is_sign_positive() { "inflow" } else { "outflow" };
+      let message = format!("The {} does not fit the types of the accounts", flow);
+      issues.push(Issue { line, message });
+      continue;
+    };
+
+    let description = truncate(cell(description), MAX_DESCRIPTION_LENGTH).to_string();
+    commands.push((
+      line,
+      entry::CommandCreate {
+        journal_id,
+        name: format!("{} {}", date, description),
+        description,
+        typ: entry::Type::Record,
+        date,
+        tags: HashSet::default(),
+        items: vec![
+          entry::Item { account: mapping.account_id, amount, price: Decimal::ONE },
+          entry::Item {
+            account: mapping.counter_account_id,
+            amount: counter_amount,
+            price: Decimal::ONE,
+          },
+        ],
+        payee_id: None,
+      },
+    ));
+  }
+
+  (commands, issues)
+}
+
+/// Parse the amount with the thousands separators and currency symbols ignored, empty as zero
+fn parse_amount(value: &str, decimal_separator: char) -> Option<Decimal> {
+  let value: String = value
+    .chars()
+    .filter(|c| c.is_ascii_digit() || *c == '-' || *c == decimal_separator)
+    .map(|c| if c == decimal_separator { '.' } else { c })
+    .collect();
+  if value.is_empty() {
+    Some(Decimal::ZERO)
+  } else {
+    Decimal::from_str(&value).ok()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::entity::account;
+  use crate::entity::csv_profile::Mapping;
+  use crate::import::csv::{parse, parse_amount};
+  use chrono::NaiveDate;
+  use rust_decimal_macros::dec;
+  use std::collections::HashMap;
+  use uuid::Uuid;
+
+  #[test]
+  fn test_parse_amount() -> anyhow::Result<()> {
+    assert_eq!(Some(dec!(-1234.5)), parse_amount("-1,234.50", '.'));
+    assert_eq!(Some(dec!(1234.5)), parse_amount("1.234,50 €", ','));
+    assert_eq!(Some(dec!(0)), parse_amount("", '.'));
+    assert_eq!(None, parse_amount("1.2.3", '.'));
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse() -> anyhow::Result<()> {
+    let journal_id = Uuid::new_v4();
+    let account = |name: &str, typ| {
+      account::Builder::default().journal_id(journal_id).name(name).unit("EUR").typ(typ).build()
+    };
+    let card = account("Liabilities::Card", account::Type::Liability)?;
+    let expense = account("Expenses::Coffee", account::Type::Expense)?;
+    let accounts = HashMap::from_iter([(card.id, card.clone()), (expense.id, expense.clone())]);
+    let mapping = Mapping {
+      delimiter: ';',
+      date_column: "Booking Date".to_string(),
+      date_format: "%d.%m.%Y".to_string(),
+      amount_column: None,
+      debit_column: Some("Debit".to_string()),
+      credit_column: Some("Credit".to_string()),
+      description_column: Some("Text".to_string()),
+      decimal_separator: ',',
+      account_id: card.id,
+      counter_account_id: expense.id,
+    };
+    let content = [
+      "Booking Date;Text;Debit;Credit",
+      "05.01.2024;Coffee Shop;3,50;",
+      "2024-01-06;Bad Date;1,00;",
+      "07.01.2024;Salary;;1.000,00",
+      "08.01.2024;Nothing;;",
+    ]
+    .join("\n");
+
+    let (commands, issues) = parse(journal_id, &mapping, &accounts, &content);
+    assert_eq!(vec![2], commands.iter().map(|(line, _)| *line).collect::<Vec<_>>());
+    let (_, command) = &commands[0];
+    assert_eq!("2024-01-05 Coffee Shop", command.name);
+    assert_eq!(NaiveDate::from_ymd_opt(2024, 1, 5).unwrap(), command.date);
+    assert_eq!(
+      vec![(card.id, dec!(3.5)), (expense.id, dec!(3.5))],
+      command.items.iter().map(|item| (item.account, item.amount)).collect::<Vec<_>>()
+    );
+    assert_eq!(vec![3, 4, 5], issues.iter().map(|issue| issue.line).collect::<Vec<_>>());
+    assert_eq!("The inflow does not fit the types of the accounts", issues[1].message);
+
+    let mapping = Mapping { description_column: Some("Memo".to_string()), ..mapping };
+    let (commands, issues) = parse(journal_id, &mapping, &accounts, &content);
+    assert!(commands.is_empty());
+    assert_eq!("Column not found: Memo", issues[0].message);
+
+    Ok(())
+  }
+}
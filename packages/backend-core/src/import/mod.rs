@@ -0,0 +1,297 @@
+//! Importers turning the exports of banks and other tools into the commands of entries
+
+pub mod csv;
+pub mod ofx;
+pub mod plain_text;
+pub mod qif;
+
+use crate::entity::{account, entry, ReadRoot, WriteRoot, MAX_NAME_LENGTH};
+use rust_decimal::Decimal;
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QuerySelect};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// A line of the imported file which cannot be turned into an entry
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Issue {
+  pub line: usize,
+  pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportResult {
+  pub commands: Vec<entry::CommandCreate>,
+  pub issues: Vec<Issue>,
+  /// The lines not imported, such as the ones imported before or the unsupported directives
+  #[serde(default)]
+  pub skipped: Vec<Issue>,
+  /// The IDs of the created entries in the order of the commands, matched by the unique names,
+  /// always empty for previews or when there are any issues
+  pub created: Vec<Uuid>,
+}
+
+/// The result of the importers which also create the accounts missing in the journal
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportResultWithAccounts {
+  /// The accounts not in the journal yet
+  pub accounts: Vec<account::CommandCreate>,
+  /// The IDs of the created accounts in the order of the commands
+  pub created_accounts: Vec<Uuid>,
+  #[serde(flatten)]
+  pub entries: ImportResult,
+}
+
+/// Find the accounts of the journal by the names, or open them to be created along with the
+/// entries
+pub(crate) struct Opener {
+  journal_id: Uuid,
+  accounts: HashMap<Uuid, account::Root>,
+  names: HashMap<String, Uuid>,
+  pending: Vec<account::Root>,
+  commands: Vec<account::CommandCreate>,
+}
+
+impl Opener {
+  pub(crate) async fn new(db: &impl ConnectionTrait, journal_id: Uuid) -> crate::Result<Opener> {
+    let accounts = find_accounts(db, journal_id).await?;
+    let names = accounts.values().map(|account| (account.name.clone(), account.id)).collect();
+    Ok(Opener { journal_id, accounts, names, pending: vec![], commands: vec![] })
+  }
+
+  pub(crate) fn find(&self, name: &str) -> Option<Uuid> {
+    self.names.get(name).copied()
+  }
+
+  /// The account in the journal or opened, by the ID
+  pub(crate) fn account(&self, id: Uuid) -> Option<&account::Root> {
+    self.accounts.get(&id).or_else(|| self.pending.iter().find(|account| account.id == id))
+  }
+
+  pub(crate) fn open(
+    &mut self,
+    line: usize,
+    name: String,
+    typ: account::Type,
+    unit: String,
+    issues: &mut Vec<Issue>,
+  ) -> Option<Uuid> {
+    if let Some(id) = self.find(&name) {
+      return Some(id);
+    }
+    let command = account::CommandCreate {
+      journal_id: self.journal_id,
+      name,
+      description: String::default(),
+      unit,
+      typ,
+      tags: HashSet::default(),
+    };
+    let result = account::Builder::default()
+      .journal_id(command.journal_id)
+      .name(command.name.clone())
+      .unit(command.unit.clone())
+      .typ(command.typ)
+      .build();
+    match result {
+      Ok(account) => {
+        let id = account.id;
+        self.names.insert(command.name.clone(), id);
+        self.commands.push(command);
+        self.pending.push(account);
+        Some(id)
+      }
+      Err(err) => {
+        issues.push(Issue { line, message: err.to_string() });
+        None
+      }
+    }
+  }
+
+  /// Validate the commands against the opened accounts, and create both unless previewing or
+  /// there are any issues
+  pub(crate) async fn finish(
+    self,
+    db: &impl ConnectionTrait,
+    commands: Vec<(usize, entry::CommandCreate)>,
+    issues: Vec<Issue>,
+    skipped: Vec<Issue>,
+    preview: bool,
+  ) -> crate::Result<ImportResultWithAccounts> {
+    let Opener { journal_id, mut accounts, pending, commands: account_commands, .. } = self;
+    accounts.extend(pending.iter().map(|account| (account.id, account.clone())));
+    let mut entries = validate(db, journal_id, &accounts, commands, issues).await?;
+    entries.skipped = skipped;
+
+    let mut created_accounts = Vec::new();
+    if !preview && entries.issues.is_empty() {
+      created_accounts = pending.iter().map(|account| account.id).collect();
+      account::Root::save(db, pending).await?;
+      entries.created = create(db, &entries.commands).await?;
+    }
+
+    Ok(ImportResultWithAccounts { accounts: account_commands, created_accounts, entries })
+  }
+}
+
+/// Make the names unique in the journal, validate the commands, and create the entries unless
+/// previewing or there are any issues
+pub(crate) async fn finish(
+  db: &impl ConnectionTrait,
+  journal_id: Uuid,
+  commands: Vec<(usize, entry::CommandCreate)>,
+  issues: Vec<Issue>,
+  preview: bool,
+) -> crate::Result<ImportResult> {
+  let accounts = find_accounts(db, journal_id).await?;
+  let mut result = validate(db, journal_id, &accounts, commands, issues).await?;
+  if !preview && result.issues.is_empty() {
+    result.created = create(db, &result.commands).await?;
+  }
+  Ok(result)
+}
+
+/// Create the entries, and return the IDs in the order of the commands. The entries are found in
+/// the order of the database, so they are matched to the commands by the names, which are unique
+/// in the journal after the validation
+async fn create(
+  db: &impl ConnectionTrait,
+  commands: &[entry::CommandCreate],
+) -> crate::Result<Vec<Uuid>> {
+  let mut ids: HashMap<_, _> = entry::Root::create(db, commands.to_vec())
+    .await?
+    .into_iter()
+    .map(|entry| (entry.name, entry.id))
+    .collect();
+  Ok(commands.iter().filter_map(|command| ids.remove(&command.name)).collect())
+}
+
+/// Make the names unique in the journal, and validate the commands against the accounts, which
+/// may not be saved yet
+async fn validate(
+  db: &impl ConnectionTrait,
+  journal_id: Uuid,
+  accounts: &HashMap<Uuid, account::Root>,
+  commands: Vec<(usize, entry::CommandCreate)>,
+  mut issues: Vec<Issue>,
+) -> crate::Result<ImportResult> {
+  let mut taken = find_names(db, journal_id).await?;
+
+  let mut validated = Vec::new();
+  for (line, mut command) in commands {
+    command.name = unique_name(&command.name, &mut taken);
+    let result = entry::Builder::default()
+      .journal_id(command.journal_id)
+      .name(command.name.clone())
+      .description(command.description.clone())
+      .typ(command.typ)
+      .date(command.date)
+      .tags(command.tags.clone())
+      .items(command.items.clone())
+      .build(accounts);
+    match result {
+      Ok(_) => validated.push(command),
+      Err(err) => issues.push(Issue { line, message: err.to_string() }),
+    }
+  }
+  issues.sort_by_key(|issue| issue.line);
+
+  Ok(ImportResult { commands: validated, issues, skipped: vec![], created: vec![] })
+}
+
+/// The names of the entries in the journal, taken for the new names
+pub(crate) async fn find_names(
+  db: &impl ConnectionTrait,
+  journal_id: Uuid,
+) -> crate::Result<HashSet<String>> {
+  Ok(
+    entry::Entity::find()
+      .select_only()
+      .column(entry::Column::Name)
+      .filter(entry::Column::JournalId.eq(journal_id))
+      .into_tuple::<String>()
+      .all(db)
+      .await?
+      .into_iter()
+      .collect(),
+  )
+}
+
+async fn find_accounts(
+  db: &impl ConnectionTrait,
+  journal_id: Uuid,
+) -> crate::Result<HashMap<Uuid, account::Root>> {
+  Ok(
+    account::Root::find_all(
+      db,
+      Some(account::Query { journal_id: HashSet::from_iter([journal_id]), ..Default::default() }),
+      None,
+      None,
+    )
+    .await?
+    .into_iter()
+    .map(|account| (account.id, account))
+    .collect(),
+  )
+}
+
+/// The amount of the item on the normal side of the account, from the amount on the debit side as
+/// in the exports where the inflows of the bank accounts are positive. The items are unsigned, so
+/// `None` if the amount would be negative on the normal side. The accounts not found are taken as
+/// on the debit side, and reported by the validation later
+pub(crate) fn normal_amount(debit: Decimal, account: Option<&account::Root>) -> Option<Decimal> {
+  let amount = if account.is_none_or(|account| account.typ.is_debit()) { debit } else { -debit };
+  if amount.is_sign_negative() {
+    None
+  } else {
+    Some(amount)
+  }
+}
+
+/// Truncate the text to at most `max` bytes, without breaking any character
+pub(crate) fn truncate(value: &str, max: usize) -> &str {
+  let mut end = value.len().min(max);
+  while !value.is_char_boundary(end) {
+    end -= 1;
+  }
+  &value[..end]
+}
+
+/// Append ` #2`, ` #3`... to the name until it is not taken, and take it
+pub(crate) fn unique_name(name: &str, taken: &mut HashSet<String>) -> String {
+  let name = truncate(name.trim(), MAX_NAME_LENGTH).trim_end();
+  let mut candidate = name.to_string();
+  let mut idx = 1;
+  while taken.contains(&candidate) {
+    idx += 1;
+    let suffix = format!(" #{}", idx);
+    candidate = format!("{}{}", truncate(name, MAX_NAME_LENGTH - suffix.len()), suffix);
+  }
+  taken.insert(candidate.clone());
+  candidate
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::import::{truncate, unique_name};
+  use std::collections::HashSet;
+
+  #[test]
+  fn test_unique_name() -> anyhow::Result<()> {
+    let mut taken = HashSet::from_iter(["Entry Name".to_string()]);
+    assert_eq!("Entry Name #2", unique_name("Entry Name", &mut taken));
+    assert_eq!("Entry Name #3", unique_name("  Entry Name ", &mut taken));
+    assert_eq!("Other Name", unique_name("Other Name", &mut taken));
+
+    let long = "a".repeat(70);
+    assert_eq!(63, unique_name(&long, &mut taken).len());
+    assert_eq!(format!("{} #2", "a".repeat(60)), unique_name(&long, &mut taken));
+
+    assert_eq!("中", truncate("中文", 4));
+
+    Ok(())
+  }
+}
@@ -0,0 +1,411 @@
+use crate::entity::{
+  account, entry, imported_transaction, ReadRoot, FIELD_ID, MAX_DESCRIPTION_LENGTH,
+};
+use crate::error::ErrorNotFound;
+use crate::import::{find_accounts, finish, normal_amount, truncate, ImportResult, Issue};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel, QueryFilter};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use uuid::Uuid;
+
+const TAG_TRANSACTION: &str = "STMTTRN";
+const TAG_LEDGER_BALANCE: &str = "LEDGERBAL";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Import {
+  /// The account the statement is exported from
+  pub account_id: Uuid,
+  pub counter_account_id: Uuid,
+  pub content: String,
+  /// Only parse and validate the content, without creating any entries
+  #[serde(default)]
+  pub preview: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transaction {
+  pub line: usize,
+  pub fit_id: String,
+  pub date: NaiveDate,
+  pub amount: Decimal,
+  pub name: String,
+  pub memo: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedgerBalance {
+  pub line: usize,
+  pub date: NaiveDate,
+  pub amount: Decimal,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Statement {
+  pub transactions: Vec<Transaction>,
+  pub ledger_balance: Option<LedgerBalance>,
+}
+
+pub async fn import(db: &impl ConnectionTrait, import: Import) -> crate::Result<ImportResult> {
+  let account = account::Root::find_one(
+    db,
+    Some(account::Query { id: HashSet::from_iter([import.account_id]), ..Default::default() }),
+  )
+  .await?
+  .ok_or_else(|| {
+    crate::Error::NotFound(ErrorNotFound {
+      entity: account::TYPE.to_string(),
+      values: vec![(FIELD_ID.to_string(), import.account_id.to_string())],
+    })
+  })?;
+
+  let (statement, mut issues) = parse(&import.content);
+  let accounts = find_accounts(db, account.journal_id).await?;
+  let counter_account = accounts.get(&import.counter_account_id);
+
+  let mut taken: HashSet<String> = imported_transaction::Entity::find()
+    .filter(imported_transaction::Column::AccountId.eq(account.id))
+    .all(db)
+    .await?
+    .into_iter()
+    .map(|model| model.external_id)
+    .collect();
+
+  let mut commands = Vec::new();
+  let mut external_ids = Vec::new();
+  let mut skipped = Vec::new();
+  for transaction in statement.transactions {
+    if !taken.insert(transaction.fit_id.clone()) {
+      skipped.push(Issue {
+        line: transaction.line,
+        message: format!("Already imported: {}", transaction.fit_id),
+      });
+      continue;
+    }
+
+    // The credits of the statement are the inflows, on the debit side of the account
+    let amounts = (
+      normal_amount(transaction.amount, Some(&account)),
+      normal_amount(-transaction.amount, counter_account),
+    );
+    let (Some(amount), Some(counter_amount)) = amounts else {
+      let flow = if transaction.amount.is_sign_positive() { "inflow" } else { "outflow" };
+      let message = format!("The {} does not fit the types of the accounts", flow);
+      issues.push(Issue { line: transaction.line, message });
+      continue;
+    };
+    let title = if transaction.name.is_empty() { &transaction.memo } else { &transaction.name };
+    let description =
+      if transaction.memo.is_empty() { &transaction.name } else { &transaction.memo };
+    commands.push((
+      transaction.line,
+      entry::CommandCreate {
+        journal_id: account.journal_id,
+        name: format!("{} {}", transaction.date, title),
+        description: truncate(description, MAX_DESCRIPTION_LENGTH).to_string(),
+        typ: entry::Type::Record,
+        date: transaction.date,
+        tags: HashSet::default(),
+        items: vec![
+          entry::Item { account: account.id, amount, price: Decimal::ONE },
+          entry::Item {
+            account: import.counter_account_id,
+            amount: counter_amount,
+            price: Decimal::ONE,
+          },
+        ],
+        payee_id: None,
+      },
+    ));
+    external_ids.push(transaction.fit_id);
+  }
+
+  if let Some(balance) = statement.ledger_balance {
+    // The balance is remembered by its date, so that the same statement asserts it only once
+    let external_id = format!("{}:{}", TAG_LEDGER_BALANCE, balance.date);
+    if !taken.insert(external_id.clone()) {
+      skipped
+        .push(Issue { line: balance.line, message: format!("Already imported: {}", external_id) });
+    } else if let Some(amount) = normal_amount(balance.amount, Some(&account)) {
+      commands.push((
+        balance.line,
+        entry::CommandCreate {
+          journal_id: account.journal_id,
+          name: format!("{} {} Ledger Balance", balance.date, account.name),
+          description: String::default(),
+          typ: entry::Type::Check,
+          date: balance.date,
+          tags: HashSet::default(),
+          items: vec![entry::Item { account: account.id, amount, price: Decimal::ONE }],
+          payee_id: None,
+        },
+      ));
+      external_ids.push(external_id);
+    } else {
+      let message = "The balance does not fit the type of the account".to_string();
+      issues.push(Issue { line: balance.line, message });
+    }
+  }
+
+  let mut result = finish(db, account.journal_id, commands, issues, import.preview).await?;
+  result.skipped = skipped;
+
+  // The entries are only created when all the commands are valid, and in the order of them
+  if !result.created.is_empty() {
+    let models: Vec<_> = result
+      .created
+      .iter()
+      .zip(external_ids)
+      .map(|(entry_id, external_id)| {
+        imported_transaction::Model { account_id: account.id, external_id, entry_id: *entry_id }
+          .into_active_model()
+      })
+      .collect();
+    imported_transaction::Entity::insert_many(models).exec(db).await?;
+  }
+
+  Ok(result)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+  Open(String),
+  Close(String),
+  Text(String),
+}
+
+/// Parse both OFX 1.x (SGML, with the leaf elements unclosed) and OFX 2.x (XML)
+pub fn parse(content: &str) -> (Statement, Vec<Issue>) {
+  let mut statement = Statement::default();
+  let mut issues = Vec::new();
+
+  let mut current: Option<(String, usize, HashMap<String, String>)> = None;
+  let mut last_open: Option<String> = None;
+  for (line, token) in tokenize(content) {
+    match token {
+      Token::Open(tag) => {
+        if tag == TAG_TRANSACTION || tag == TAG_LEDGER_BALANCE {
+          current = Some((tag.clone(), line, HashMap::new()));
+        }
+        last_open = Some(tag);
+      }
+      Token::Text(text) => {
+        if let (Some(tag), Some((_, _, fields))) = (last_open.take(), current.as_mut()) {
+          fields.insert(tag, text);
+        }
+      }
+      Token::Close(tag) => {
+        last_open = None;
+        if current.as_ref().is_none_or(|(aggregate, _, _)| *aggregate != tag) {
+          continue;
+        }
+        let Some((_, line, fields)) = current.take() else { continue };
+        let result = if tag == TAG_TRANSACTION {
+          parse_transaction(line, &fields).map(|transaction| {
+            statement.transactions.push(transaction);
+          })
+        } else {
+          parse_ledger_balance(line, &fields).map(|balance| {
+            statement.ledger_balance = Some(balance);
+          })
+        };
+        if let Err(message) = result {
+          issues.push(Issue { line, message });
+        }
+      }
+    }
+  }
+
+  (statement, issues)
+}
+
+fn parse_transaction(line: usize, fields: &HashMap<String, String>) -> Result<Transaction, String> {
+  let field = |name: &str| fields.get(name).cloned().unwrap_or_default();
+  let fit_id = field("FITID");
+  if fit_id.is_empty() {
+    return Err("Missing FITID".to_string());
+  }
+  let amount = parse_amount(&field("TRNAMT"))?;
+  if amount.is_zero() {
+    return Err("Zero amount".to_string());
+  }
+  Ok(Transaction {
+    line,
+    fit_id,
+    date: parse_date(&field("DTPOSTED"))?,
+    amount,
+    name: field("NAME"),
+    memo: field("MEMO"),
+  })
+}
+
+fn parse_ledger_balance(
+  line: usize,
+  fields: &HashMap<String, String>,
+) -> Result<LedgerBalance, String> {
+  let field = |name: &str| fields.get(name).cloned().unwrap_or_default();
+  Ok(LedgerBalance {
+    line,
+    date: parse_date(&field("DTASOF"))?,
+    amount: parse_amount(&field("BALAMT"))?,
+  })
+}
+
+/// The dates are like `20240105`, `20240105120000` or `20240105120000.000[-5:EST]`
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+  value
+    .get(..8)
+    .and_then(|value| NaiveDate::parse_from_str(value, "%Y%m%d").ok())
+    .ok_or_else(|| format!("Invalid date `{}`", value))
+}
+
+/// Some banks use the comma as the decimal separator
+fn parse_amount(value: &str) -> Result<Decimal, String> {
+  Decimal::from_str(&value.replace(',', ".")).map_err(|_| format!("Invalid amount `{}`", value))
+}
+
+/// Split the content into the tags and the texts, each with its line number
+fn tokenize(content: &str) -> Vec<(usize, Token)> {
+  let mut tokens = Vec::new();
+  let mut rest = content;
+  let mut line = 1;
+  while let Some(start) = rest.find('<') {
+    let text = rest[..start].trim();
+    if !text.is_empty() {
+      tokens.push((line, Token::Text(decode(text))));
+    }
+    line += rest[..start].matches('\n').count();
+
+    let Some(end) = rest[start..].find('>').map(|end| start + end) else { break };
+    let tag = rest[start + 1..end].trim();
+    if let Some(name) = tag.strip_prefix('/') {
+      tokens.push((line, Token::Close(name.trim().to_uppercase())));
+    } else if !tag.starts_with('?') && !tag.starts_with('!') {
+      let name = tag.split_whitespace().next().unwrap_or_default();
+      if let Some(name) = name.strip_suffix('/') {
+        tokens.push((line, Token::Open(name.to_uppercase())));
+        tokens.push((line, Token::Close(name.to_uppercase())));
+      } else {
+        tokens.push((line, Token::Open(name.to_uppercase())));
+      }
+    }
+    line += rest[start..end].matches('\n').count();
+    rest = &rest[end + 1..];
+  }
+  tokens
+}
+
+fn decode(text: &str) -> String {
+  text
+    .replace("&lt;", "<")
+    .replace("&gt;", ">")
+    .replace("&quot;", "\"")
+    .replace("&apos;", "'")
+    .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::import::ofx::{parse, parse_date, LedgerBalance};
+  use chrono::NaiveDate;
+  use rust_decimal_macros::dec;
+
+  #[test]
+  fn test_parse_sgml() -> anyhow::Result<()> {
+    let content = r#"OFXHEADER:100
+DATA:OFXSGML
+VERSION:102
+
+<OFX>
+<BANKMSGSRSV1><STMTTRNRS><STMTRS>
+<BANKTRANLIST>
+<STMTTRN>
+<TRNTYPE>DEBIT
+<DTPOSTED>20240105120000.000[-5:EST]
+<TRNAMT>-3.50
+<FITID>0001
+<NAME>Coffee Shop
+</STMTTRN>
+<STMTTRN>
+<TRNTYPE>CREDIT
+<DTPOSTED>2024-01-06
+<TRNAMT>100
+<FITID>0002
+</STMTTRN>
+<STMTTRN>
+<TRNTYPE>CREDIT
+<DTPOSTED>20240107
+<TRNAMT>1000,00
+<FITID>0003
+<NAME>Salary
+<MEMO>AT&amp;T
+</STMTTRN>
+</BANKTRANLIST>
+<LEDGERBAL><BALAMT>1096.50<DTASOF>20240131</LEDGERBAL>
+</STMTRS></STMTTRNRS></BANKMSGSRSV1>
+</OFX>
+"#;
+    let (statement, issues) = parse(content);
+    assert_eq!(
+      vec!["0001", "0003"],
+      statement.transactions.iter().map(|t| t.fit_id.as_str()).collect::<Vec<_>>()
+    );
+    assert_eq!(8, statement.transactions[0].line);
+    assert_eq!(dec!(-3.5), statement.transactions[0].amount);
+    assert_eq!(NaiveDate::from_ymd_opt(2024, 1, 5).unwrap(), statement.transactions[0].date);
+    assert_eq!(dec!(1000), statement.transactions[1].amount);
+    assert_eq!("AT&T", statement.transactions[1].memo);
+    assert_eq!(vec![15], issues.iter().map(|issue| issue.line).collect::<Vec<_>>());
+    assert_eq!(
+      Some(LedgerBalance {
+        line: 30,
+        date: NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(),
+        amount: dec!(1096.5)
+      }),
+      statement.ledger_balance
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_xml() -> anyhow::Result<()> {
+    let content = r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>
+<?OFX OFXHEADER="200" VERSION="220" SECURITY="NONE" OLDFILEUID="NONE" NEWFILEUID="NONE"?>
+<OFX>
+  <CREDITCARDMSGSRSV1>
+    <CCSTMTTRNRS>
+      <CCSTMTRS>
+        <BANKTRANLIST>
+          <STMTTRN>
+            <TRNTYPE>DEBIT</TRNTYPE>
+            <DTPOSTED>20240210</DTPOSTED>
+            <TRNAMT>-42.10</TRNAMT>
+            <FITID>abc-1</FITID>
+            <NAME>Groceries</NAME>
+            <MEMO/>
+          </STMTTRN>
+        </BANKTRANLIST>
+        <LEDGERBAL>
+          <BALAMT>-42.10</BALAMT>
+          <DTASOF>20240229</DTASOF>
+        </LEDGERBAL>
+      </CCSTMTRS>
+    </CCSTMTTRNRS>
+  </CREDITCARDMSGSRSV1>
+</OFX>"#;
+    let (statement, issues) = parse(content);
+    assert!(issues.is_empty());
+    assert_eq!(1, statement.transactions.len());
+    assert_eq!("abc-1", statement.transactions[0].fit_id);
+    assert_eq!("", statement.transactions[0].memo);
+    assert_eq!(Some(dec!(-42.10)), statement.ledger_balance.map(|balance| balance.amount));
+
+    assert!(parse_date("2024").is_err());
+
+    Ok(())
+  }
+}
@@ -0,0 +1,661 @@
+//! Importer of the plain text accounting files of beancount and ledger-cli
+
+use crate::entity::{account, entry, journal, ReadRoot, FIELD_ID, MAX_NAME_LENGTH};
+use crate::error::ErrorNotFound;
+use crate::import::{normal_amount, truncate, ImportResultWithAccounts, Issue, Opener};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sea_orm::ConnectionTrait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+  #[serde(rename = "beancount")]
+  Beancount,
+  #[serde(rename = "ledger")]
+  Ledger,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Import {
+  pub journal_id: Uuid,
+  pub format: Format,
+  pub content: String,
+  /// Only parse and validate the content, without creating any accounts or entries
+  #[serde(default)]
+  pub preview: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Posting {
+  pub line: usize,
+  pub account: String,
+  /// Elided to balance the transaction if none
+  pub amount: Option<Decimal>,
+  pub unit: Option<String>,
+  pub price: Decimal,
+  /// The balance assertion of ledger-cli, like `Assets:Bank  $-50 = $100`
+  pub balance: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Directive {
+  Open {
+    line: usize,
+    account: String,
+    unit: Option<String>,
+  },
+  Transaction {
+    line: usize,
+    date: NaiveDate,
+    payee: String,
+    narration: String,
+    tags: HashSet<String>,
+    postings: Vec<Posting>,
+  },
+  Balance {
+    line: usize,
+    date: NaiveDate,
+    account: String,
+    amount: Decimal,
+  },
+}
+
+pub async fn import(
+  db: &impl ConnectionTrait,
+  import: Import,
+) -> crate::Result<ImportResultWithAccounts> {
+  let journal = journal::Root::find_one(
+    db,
+    Some(journal::Query { id: HashSet::from_iter([import.journal_id]), ..Default::default() }),
+  )
+  .await?
+  .ok_or_else(|| {
+    crate::Error::NotFound(ErrorNotFound {
+      entity: journal::TYPE.to_string(),
+      values: vec![(FIELD_ID.to_string(), import.journal_id.to_string())],
+    })
+  })?;
+
+  let (directives, mut issues, skipped) = parse(import.format, &import.content);
+
+  let mut opener = Opener::new(db, journal.id).await?;
+
+  let mut commands = Vec::new();
+  for directive in directives {
+    match directive {
+      Directive::Open { line, account, unit } => {
+        open(&mut opener, &journal, line, &account, unit.as_deref(), &mut issues);
+      }
+      Directive::Balance { line, date, account, amount } => {
+        let Some(id) = opener.find(&account_name(&account)) else {
+          issues.push(Issue { line, message: format!("Account not opened: {}", account) });
+          continue;
+        };
+        match check(&opener, journal.id, date, &account, id, amount) {
+          Ok(command) => commands.push((line, command)),
+          Err(message) => issues.push(Issue { line, message }),
+        }
+      }
+      Directive::Transaction { line, date, payee, narration, tags, postings } => {
+        let mut items: Vec<entry::Item> = Vec::new();
+        let mut total = Decimal::ZERO;
+        let mut elided = None;
+        let mut valid = true;
+        for posting in &postings {
+          let id = match opener.find(&account_name(&posting.account)) {
+            Some(id) => Some(id),
+            // The accounts are declared implicitly by the postings in ledger-cli
+            None if import.format == Format::Ledger => {
+              let unit = posting.unit.as_deref();
+              open(&mut opener, &journal, posting.line, &posting.account, unit, &mut issues)
+            }
+            None => {
+              issues.push(Issue {
+                line: posting.line,
+                message: format!("Account not opened: {}", posting.account),
+              });
+              None
+            }
+          };
+          let Some(id) = id else {
+            valid = false;
+            continue;
+          };
+
+          if let Some(balance) = posting.balance {
+            match check(&opener, journal.id, date, &posting.account, id, balance) {
+              Ok(command) => commands.push((posting.line, command)),
+              Err(message) => issues.push(Issue { line: posting.line, message }),
+            }
+          }
+          match posting.amount {
+            Some(amount) => {
+              total += amount * posting.price;
+              if let Err(message) = merge(&mut items, id, amount, posting.price) {
+                issues.push(Issue { line: posting.line, message });
+                valid = false;
+              }
+            }
+            None if elided.is_none() => elided = Some(id),
+            None => {
+              issues.push(Issue {
+                line: posting.line,
+                message: "Only one posting can elide the amount".to_string(),
+              });
+              valid = false;
+            }
+          }
+        }
+        if let Some(id) = elided {
+          if let Err(message) = merge(&mut items, id, -total, Decimal::ONE) {
+            issues.push(Issue { line, message });
+            valid = false;
+          }
+        }
+        if !valid {
+          continue;
+        }
+
+        // The postings are on the debit side when positive, while the items are unsigned on the
+        // normal sides of the accounts
+        for item in &mut items {
+          match normal_amount(item.amount, opener.account(item.account)) {
+            Some(amount) => item.amount = amount,
+            None => {
+              let name = opener.account(item.account).map(|account| account.name.clone());
+              let message = format!(
+                "The posting to {} does not fit the type of the account",
+                name.unwrap_or_default()
+              );
+              issues.push(Issue { line, message });
+              valid = false;
+            }
+          }
+        }
+        if !valid {
+          continue;
+        }
+        let title = if narration.is_empty() { &payee } else { &narration };
+        commands.push((
+          line,
+          entry::CommandCreate {
+            journal_id: journal.id,
+            name: format!("{} {}", date, title),
+            description: if narration.is_empty() { String::default() } else { payee.clone() },
+            typ: entry::Type::Record,
+            date,
+            tags,
+            items,
+            payee_id: None,
+          },
+        ));
+      }
+    }
+  }
+
+  opener.finish(db, commands, issues, skipped, import.preview).await
+}
+
+/// Find the account by the plain text name, or open it with the type by the root of the name
+fn open(
+  opener: &mut Opener,
+  journal: &journal::Root,
+  line: usize,
+  name: &str,
+  unit: Option<&str>,
+  issues: &mut Vec<Issue>,
+) -> Option<Uuid> {
+  let name = account_name(name);
+  if let Some(id) = opener.find(&name) {
+    return Some(id);
+  }
+  let Some(typ) = account_type(&name) else {
+    issues.push(Issue { line, message: format!("Unknown account type: {}", name) });
+    return None;
+  };
+  let unit = unit.map(unit_name).unwrap_or_else(|| journal.unit.clone());
+  opener.open(line, name, typ, unit, issues)
+}
+
+/// Parse the content into the directives, the issues, and the skipped unsupported directives
+pub fn parse(format: Format, content: &str) -> (Vec<Directive>, Vec<Issue>, Vec<Issue>) {
+  let mut directives = Vec::new();
+  let mut issues = Vec::new();
+  let mut skipped = Vec::new();
+
+  let lines: Vec<_> = content.lines().collect();
+  let mut idx = 0;
+  while idx < lines.len() {
+    let line = idx + 1;
+    let text = strip_comment(lines[idx]);
+    idx += 1;
+    if text.trim().is_empty() || lines[idx - 1].starts_with(['*', '#', '%', '|']) {
+      continue;
+    }
+    if text.starts_with([' ', '\t']) {
+      issues.push(Issue { line, message: "Unexpected indented line".to_string() });
+      continue;
+    }
+
+    let mut body = Vec::new();
+    while idx < lines.len() && lines[idx].starts_with([' ', '\t']) {
+      let text = strip_comment(lines[idx]).trim();
+      if !text.is_empty() {
+        body.push((idx + 1, text));
+      }
+      idx += 1;
+    }
+
+    let result = match format {
+      Format::Beancount => parse_beancount(line, text.trim(), &body),
+      Format::Ledger => parse_ledger(line, text.trim(), &body),
+    };
+    match result {
+      Ok(Some(mut parsed)) => directives.append(&mut parsed),
+      Ok(None) => skipped.push(Issue {
+        line,
+        message: format!("Unsupported directive: {}", truncate(text.trim(), MAX_NAME_LENGTH)),
+      }),
+      Err(mut errors) => issues.append(&mut errors),
+    }
+  }
+
+  (directives, issues, skipped)
+}
+
+type Parsed = Result<Option<Vec<Directive>>, Vec<Issue>>;
+
+fn parse_beancount(line: usize, text: &str, body: &[(usize, &str)]) -> Parsed {
+  let tokens = split_tokens(text);
+  let Some(date) = tokens.first().and_then(|token| parse_date(token)) else {
+    return Ok(None);
+  };
+  let error = |message: String| Err(vec![Issue { line, message }]);
+
+  match tokens.get(1).map(String::as_str) {
+    Some("open") => {
+      let Some(account) = tokens.get(2) else { return error("Missing account".to_string()) };
+      let unit = tokens.get(3).and_then(|unit| unit.split(',').next()).map(str::to_string);
+      Ok(Some(vec![Directive::Open { line, account: account.clone(), unit }]))
+    }
+    Some("balance") => {
+      let Some(account) = tokens.get(2) else { return error("Missing account".to_string()) };
+      match parse_amount(&tokens[3..].join(" ")) {
+        Some((amount, _)) => {
+          // The balances are asserted at the beginning of the dates in beancount, while the Check
+          // entries are at the end
+          let date = date.pred_opt().unwrap_or(date);
+          Ok(Some(vec![Directive::Balance { line, date, account: account.clone(), amount }]))
+        }
+        None => error(format!("Invalid amount: {}", tokens[3..].join(" "))),
+      }
+    }
+    Some("*" | "!" | "txn") => {
+      let strings: Vec<_> =
+        tokens[2..].iter().filter_map(|token| token.strip_prefix('"')).collect();
+      let (payee, narration) = match &strings[..] {
+        [payee, narration, ..] => (payee.to_string(), narration.to_string()),
+        [narration] => (String::default(), narration.to_string()),
+        [] => (String::default(), String::default()),
+      };
+      let tags = tokens[2..]
+        .iter()
+        .filter_map(|token| token.strip_prefix('#'))
+        .map(str::to_string)
+        .collect();
+
+      let mut postings = Vec::new();
+      let mut errors = Vec::new();
+      for (line, text) in body {
+        // The metadata like `key: "value"`
+        if text.split_whitespace().next().is_some_and(|key| {
+          key.ends_with(':') && key.starts_with(|c: char| c.is_ascii_lowercase())
+        }) {
+          continue;
+        }
+        let text = text.trim_start_matches(['*', '!']).trim_start();
+        let (account, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+        match parse_posting(*line, account, rest) {
+          Ok(posting) => postings.push(posting),
+          Err(message) => errors.push(Issue { line: *line, message }),
+        }
+      }
+      if !errors.is_empty() {
+        return Err(errors);
+      }
+      Ok(Some(vec![Directive::Transaction { line, date, payee, narration, tags, postings }]))
+    }
+    _ => Ok(None),
+  }
+}
+
+fn parse_ledger(line: usize, text: &str, body: &[(usize, &str)]) -> Parsed {
+  if let Some(account) = text.strip_prefix("account ") {
+    return Ok(Some(vec![Directive::Open {
+      line,
+      account: account.trim().to_string(),
+      unit: None,
+    }]));
+  }
+
+  let (date, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+  // The auxiliary date after `=` is ignored
+  let Some(date) = date.split('=').next().and_then(parse_date) else {
+    return Ok(None);
+  };
+  let mut payee = rest.trim().trim_start_matches(['*', '!']).trim_start();
+  if payee.starts_with('(') {
+    payee = payee.split_once(')').map(|(_, payee)| payee.trim()).unwrap_or(payee);
+  }
+
+  let mut postings = Vec::new();
+  let mut errors = Vec::new();
+  for (line, text) in body {
+    let text = text.trim_start_matches(['*', '!']).trim_start();
+    let split = [text.find("  "), text.find('\t')].into_iter().flatten().min();
+    let (account, rest) = match split {
+      Some(idx) => (&text[..idx], &text[idx..]),
+      None => (text, ""),
+    };
+    if account.starts_with(['(', '[']) {
+      errors.push(Issue { line: *line, message: "Virtual postings are not supported".to_string() });
+      continue;
+    }
+    match parse_posting(*line, account, rest) {
+      Ok(posting) => postings.push(posting),
+      Err(message) => errors.push(Issue { line: *line, message }),
+    }
+  }
+  if !errors.is_empty() {
+    return Err(errors);
+  }
+
+  Ok(Some(vec![Directive::Transaction {
+    line,
+    date,
+    payee: String::default(),
+    narration: payee.to_string(),
+    tags: HashSet::default(),
+    postings,
+  }]))
+}
+
+/// Parse the amount part like `-50.00 USD`, `10 AAPL {150 USD}`, `$-50 @ €0.9` or `$-50 = $100`
+fn parse_posting(line: usize, account: &str, rest: &str) -> Result<Posting, String> {
+  let invalid = |value: &str| format!("Invalid amount: {}", value.trim());
+  let (rest, balance) = match rest.split_once('=') {
+    Some((rest, balance)) => (rest, Some(parse_amount(balance).ok_or_else(|| invalid(balance))?.0)),
+    None => (rest, None),
+  };
+  let (rest, price) = if let Some((rest, total)) = rest.split_once("@@") {
+    (rest, Some((parse_amount(total).ok_or_else(|| invalid(total))?.0, true)))
+  } else if let Some((rest, price)) = rest.split_once('@') {
+    (rest, Some((parse_amount(price).ok_or_else(|| invalid(price))?.0, false)))
+  } else {
+    (rest, None)
+  };
+  let (rest, cost) = match rest.split_once('{') {
+    Some((rest, cost)) => {
+      let cost = cost.trim_end().trim_end_matches('}');
+      (rest, if cost.trim().is_empty() { None } else { parse_amount(cost).map(|(cost, _)| cost) })
+    }
+    None => (rest, None),
+  };
+
+  let (amount, unit) = if rest.trim().is_empty() {
+    if balance.is_some() {
+      return Err("Balance assignments are not supported".to_string());
+    }
+    (None, None)
+  } else {
+    let (amount, unit) = parse_amount(rest).ok_or_else(|| invalid(rest))?;
+    (Some(amount), unit)
+  };
+  let price = match (price, amount) {
+    (Some((total, true)), Some(amount)) if !amount.is_zero() => (total / amount).abs(),
+    (Some((price, false)), _) => price,
+    _ => cost.unwrap_or(Decimal::ONE),
+  };
+
+  Ok(Posting { line, account: account.trim().to_string(), amount, unit, price, balance })
+}
+
+/// Parse the amount with the unit before or after it, like `-1,000.00 USD` or `$-50`
+fn parse_amount(text: &str) -> Option<(Decimal, Option<String>)> {
+  let mut amount = None;
+  let mut unit = None;
+  for token in text.split_whitespace() {
+    if token.contains(|c: char| c.is_ascii_digit()) && amount.is_none() {
+      let number: String =
+        token.chars().filter(|c| c.is_ascii_digit() || *c == '.' || *c == '-').collect();
+      let symbol: String = token
+        .chars()
+        .filter(|c| !c.is_ascii_digit() && !matches!(c, '.' | '-' | '+' | ','))
+        .collect();
+      amount = Some(Decimal::from_str(&number).ok()?);
+      if !symbol.is_empty() {
+        unit = Some(symbol);
+      }
+    } else if unit.is_none() {
+      unit = Some(token.to_string());
+    } else {
+      return None;
+    }
+  }
+  amount.map(|amount| (amount, unit))
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+  NaiveDate::parse_from_str(value, "%Y-%m-%d")
+    .or_else(|_| NaiveDate::parse_from_str(value, "%Y/%m/%d"))
+    .ok()
+}
+
+/// Split by the whitespaces, keeping the quoted strings with the leading quotes as single tokens
+fn split_tokens(text: &str) -> Vec<String> {
+  let mut tokens = Vec::new();
+  let mut rest = text.trim_start();
+  while !rest.is_empty() {
+    if let Some(quoted) = rest.strip_prefix('"') {
+      let end = quoted.find('"').unwrap_or(quoted.len());
+      tokens.push(format!("\"{}", &quoted[..end]));
+      rest = quoted.get(end + 1..).unwrap_or_default();
+    } else {
+      let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+      tokens.push(rest[..end].to_string());
+      rest = &rest[end..];
+    }
+    rest = rest.trim_start();
+  }
+  tokens
+}
+
+/// Remove the comment after `;`, except inside the quoted strings
+fn strip_comment(text: &str) -> &str {
+  let mut quoted = false;
+  for (idx, c) in text.char_indices() {
+    match c {
+      '"' => quoted = !quoted,
+      ';' if !quoted => return &text[..idx],
+      _ => {}
+    }
+  }
+  text
+}
+
+fn merge(
+  items: &mut Vec<entry::Item>,
+  account: Uuid,
+  amount: Decimal,
+  price: Decimal,
+) -> Result<(), String> {
+  match items.iter_mut().find(|item| item.account == account) {
+    Some(item) if item.price == price => {
+      item.amount += amount;
+      Ok(())
+    }
+    Some(_) => Err("Multiple postings to the same account with different prices".to_string()),
+    None => {
+      items.push(entry::Item { account, amount, price });
+      Ok(())
+    }
+  }
+}
+
+/// The Check entry of the balance assertion, which is on the debit side when positive
+fn check(
+  opener: &Opener,
+  journal_id: Uuid,
+  date: NaiveDate,
+  name: &str,
+  account: Uuid,
+  amount: Decimal,
+) -> Result<entry::CommandCreate, String> {
+  let amount = normal_amount(amount, opener.account(account))
+    .ok_or_else(|| format!("The balance of {} does not fit the type of the account", name))?;
+  Ok(entry::CommandCreate {
+    journal_id,
+    name: format!("{} Balance {}", date, name),
+    description: String::default(),
+    typ: entry::Type::Check,
+    date,
+    tags: HashSet::default(),
+    items: vec![entry::Item { account, amount, price: Decimal::ONE }],
+    payee_id: None,
+  })
+}
+
+/// The segments of the plain text accounts are separated by `:`
+fn account_name(name: &str) -> String {
+  name.trim().split(':').collect::<Vec<_>>().join(account::NAME_SPLITERATOR)
+}
+
+fn account_type(name: &str) -> Option<account::Type> {
+  let root = name.split(account::NAME_SPLITERATOR).next().unwrap_or_default().to_lowercase();
+  match root.as_str() {
+    "assets" | "asset" => Some(account::Type::Asset),
+    "liabilities" | "liability" => Some(account::Type::Liability),
+    "equity" => Some(account::Type::Equity),
+    "income" | "revenue" | "revenues" => Some(account::Type::Income),
+    "expenses" | "expense" => Some(account::Type::Expense),
+    _ => None,
+  }
+}
+
+fn unit_name(unit: &str) -> String {
+  match unit {
+    "$" => "USD".to_string(),
+    "€" => "EUR".to_string(),
+    "£" => "GBP".to_string(),
+    "¥" => "JPY".to_string(),
+    _ => unit.to_string(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::import::plain_text::{parse, parse_amount, Directive, Format};
+  use chrono::NaiveDate;
+  use rust_decimal_macros::dec;
+
+  #[test]
+  fn test_parse_amount() -> anyhow::Result<()> {
+    assert_eq!(Some((dec!(-1000), Some("USD".to_string()))), parse_amount("-1,000.00 USD"));
+    assert_eq!(Some((dec!(-50), Some("$".to_string()))), parse_amount("$-50"));
+    assert_eq!(Some((dec!(50), None)), parse_amount(" 50 "));
+    assert_eq!(None, parse_amount("USD"));
+    assert_eq!(None, parse_amount("50 USD EUR"));
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_beancount() -> anyhow::Result<()> {
+    let content = r#"option "title" "Example"
+* Opening
+
+2024-01-01 open Assets:Bank USD,EUR
+2024-01-01 open Expenses:Food
+2024-01-05 * "Coffee Shop" "Breakfast; with friends" #food ^link
+  memo: "ignored"
+  Expenses:Food    3.50 USD ; comment
+  Assets:Bank
+2024-01-06 txn "Exchange"
+  Assets:Bank    10 EUR @@ 11 USD
+  Equity:Conversion
+2024-01-07 * "Bad"
+  Assets:Bank    abc USD
+2024-01-31 balance Assets:Bank  96.50 USD
+2024-02-01 close Assets:Bank
+"#;
+    let (directives, issues, skipped) = parse(Format::Beancount, content);
+    assert_eq!(vec![1, 16], skipped.iter().map(|issue| issue.line).collect::<Vec<_>>());
+    assert_eq!(vec![14], issues.iter().map(|issue| issue.line).collect::<Vec<_>>());
+    assert_eq!(5, directives.len());
+    match &directives[2] {
+      Directive::Transaction { line, payee, narration, tags, postings, .. } => {
+        assert_eq!(6, *line);
+        assert_eq!("Coffee Shop", payee);
+        assert_eq!("Breakfast; with friends", narration);
+        assert!(tags.contains("food"));
+        assert_eq!(2, postings.len());
+        assert_eq!(Some(dec!(3.5)), postings[0].amount);
+        assert_eq!(None, postings[1].amount);
+      }
+      directive => panic!("Unexpected directive: {:?}", directive),
+    }
+    match &directives[3] {
+      Directive::Transaction { postings, .. } => assert_eq!(dec!(1.1), postings[0].price),
+      directive => panic!("Unexpected directive: {:?}", directive),
+    }
+    // Asserted at the beginning of the date, so at the end of the day before for the Check entry
+    match &directives[4] {
+      Directive::Balance { date, amount, .. } => {
+        assert_eq!(NaiveDate::from_ymd_opt(2024, 1, 30).unwrap(), *date);
+        assert_eq!(dec!(96.5), *amount);
+      }
+      directive => panic!("Unexpected directive: {:?}", directive),
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_ledger() -> anyhow::Result<()> {
+    let content = r#"; A comment
+account Assets:Checking Account
+
+2024/01/05=2024/01/06 * (1024) Grocery Store
+    Expenses:Food:Groceries    $42.10
+    Assets:Checking Account  $-42.10 = $957.90
+
+P 2024/01/05 EUR $1.10
+2024/01/06 Virtual
+    (Budget:Food)    $-42.10
+"#;
+    let (directives, issues, skipped) = parse(Format::Ledger, content);
+    assert_eq!(vec![8], skipped.iter().map(|issue| issue.line).collect::<Vec<_>>());
+    assert_eq!(vec![10], issues.iter().map(|issue| issue.line).collect::<Vec<_>>());
+    assert_eq!(
+      Directive::Open { line: 2, account: "Assets:Checking Account".to_string(), unit: None },
+      directives[0]
+    );
+    match &directives[1] {
+      Directive::Transaction { narration, postings, .. } => {
+        assert_eq!("Grocery Store", narration);
+        assert_eq!("Expenses:Food:Groceries", postings[0].account);
+        assert_eq!("Assets:Checking Account", postings[1].account);
+        assert_eq!(Some(dec!(-42.10)), postings[1].amount);
+        assert_eq!(Some(dec!(957.90)), postings[1].balance);
+      }
+      directive => panic!("Unexpected directive: {:?}", directive),
+    }
+
+    Ok(())
+  }
+}
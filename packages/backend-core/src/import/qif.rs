@@ -0,0 +1,387 @@
+//! Importer of the Quicken Interchange Format, for the bank, cash and credit card registers
+
+use crate::entity::{account, entry, ReadRoot, FIELD_ID, MAX_DESCRIPTION_LENGTH};
+use crate::error::ErrorNotFound;
+use crate::import::{normal_amount, truncate, ImportResultWithAccounts, Issue, Opener};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sea_orm::ConnectionTrait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::str::FromStr;
+use uuid::Uuid;
+
+const SUPPORTED_TYPES: [&str; 3] = ["bank", "cash", "ccard"];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Import {
+  /// The account the register is exported from
+  pub account_id: Uuid,
+  pub content: String,
+  /// Create the accounts of the categories missing in the journal, instead of reporting them
+  #[serde(default)]
+  pub create_accounts: bool,
+  /// If the dates are like `31/12/2024` instead of `12/31/2024`
+  #[serde(default)]
+  pub day_first: bool,
+  /// Only parse and validate the content, without creating any accounts or entries
+  #[serde(default)]
+  pub preview: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Split {
+  pub category: String,
+  pub memo: String,
+  pub amount: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transaction {
+  pub line: usize,
+  pub date: NaiveDate,
+  pub amount: Decimal,
+  pub payee: String,
+  pub memo: String,
+  pub category: String,
+  pub splits: Vec<Split>,
+}
+
+pub async fn import(
+  db: &impl ConnectionTrait,
+  import: Import,
+) -> crate::Result<ImportResultWithAccounts> {
+  let register = account::Root::find_one(
+    db,
+    Some(account::Query { id: HashSet::from_iter([import.account_id]), ..Default::default() }),
+  )
+  .await?
+  .ok_or_else(|| {
+    crate::Error::NotFound(ErrorNotFound {
+      entity: account::TYPE.to_string(),
+      values: vec![(FIELD_ID.to_string(), import.account_id.to_string())],
+    })
+  })?;
+
+  let (transactions, mut issues, skipped) = parse(&import.content, import.day_first);
+  let mut opener = Opener::new(db, register.journal_id).await?;
+
+  let mut commands = Vec::new();
+  for transaction in transactions {
+    let line = transaction.line;
+    let splits = if transaction.splits.is_empty() {
+      vec![Split {
+        category: transaction.category.clone(),
+        memo: String::default(),
+        amount: transaction.amount,
+      }]
+    } else {
+      transaction.splits.clone()
+    };
+
+    // The amounts on the debit sides by the accounts, where the inflows of the register are
+    // positive, and the splits are netted per account before turned into the items
+    let mut debits = vec![(register.id, transaction.amount)];
+    let mut valid = true;
+    for split in splits {
+      let Some((name, transfer)) = account_name(&split.category) else {
+        issues.push(Issue { line, message: "Missing category".to_string() });
+        valid = false;
+        continue;
+      };
+      let id = match opener.find(&name) {
+        Some(id) => Some(id),
+        None if import.create_accounts => {
+          let typ = if transfer {
+            account::Type::Asset
+          } else if split.amount.is_sign_negative() {
+            account::Type::Expense
+          } else {
+            account::Type::Income
+          };
+          opener.open(line, name, typ, register.unit.clone(), &mut issues)
+        }
+        None => {
+          issues.push(Issue { line, message: format!("Account not found: {}", name) });
+          None
+        }
+      };
+      let Some(id) = id else {
+        valid = false;
+        continue;
+      };
+      match debits.iter_mut().find(|(account, _)| *account == id) {
+        Some((_, debit)) => *debit -= split.amount,
+        None => debits.push((id, -split.amount)),
+      }
+    }
+    if !valid {
+      continue;
+    }
+
+    let mut items = Vec::new();
+    for (id, debit) in debits {
+      match normal_amount(debit, opener.account(id)) {
+        Some(amount) => items.push(entry::Item { account: id, amount, price: Decimal::ONE }),
+        None => {
+          let name = opener.account(id).map(|account| account.name.clone()).unwrap_or_default();
+          let message = format!("The split to {} does not fit the type of the account", name);
+          issues.push(Issue { line, message });
+          valid = false;
+        }
+      }
+    }
+    if !valid {
+      continue;
+    }
+
+    let title = if transaction.payee.is_empty() { &transaction.memo } else { &transaction.payee };
+    let description = if transaction.payee.is_empty() { "" } else { &transaction.memo };
+    commands.push((
+      line,
+      entry::CommandCreate {
+        journal_id: register.journal_id,
+        name: format!("{} {}", transaction.date, title),
+        description: truncate(description, MAX_DESCRIPTION_LENGTH).to_string(),
+        typ: entry::Type::Record,
+        date: transaction.date,
+        tags: HashSet::default(),
+        items,
+        payee_id: None,
+      },
+    ));
+  }
+
+  opener.finish(db, commands, issues, skipped, import.preview).await
+}
+
+/// Parse the content into the transactions, the issues, and the skipped unsupported sections
+pub fn parse(content: &str, day_first: bool) -> (Vec<Transaction>, Vec<Issue>, Vec<Issue>) {
+  let mut transactions = Vec::new();
+  let mut issues = Vec::new();
+  let mut skipped = Vec::new();
+
+  let mut supported = false;
+  let mut fields: Vec<(usize, char, &str)> = Vec::new();
+  for (idx, text) in content.lines().enumerate() {
+    let line = idx + 1;
+    let text = text.trim();
+    if text.is_empty() {
+      continue;
+    }
+    if let Some(header) = text.strip_prefix('!') {
+      let header = header.trim();
+      supported = header
+        .to_lowercase()
+        .strip_prefix("type:")
+        .is_some_and(|typ| SUPPORTED_TYPES.contains(&typ.trim()));
+      if !supported {
+        skipped.push(Issue { line, message: format!("Unsupported section: {}", header) });
+      }
+      fields.clear();
+      continue;
+    }
+    if !supported {
+      if transactions.is_empty() && issues.is_empty() && skipped.is_empty() {
+        skipped.push(Issue { line, message: "Missing section header".to_string() });
+      }
+      continue;
+    }
+
+    if text.starts_with('^') {
+      if let Some(result) = parse_transaction(&fields, day_first) {
+        match result {
+          Ok(transaction) => transactions.push(transaction),
+          Err(issue) => issues.push(issue),
+        }
+      }
+      fields.clear();
+    } else if let Some(code) = text.chars().next() {
+      fields.push((line, code, &text[code.len_utf8()..]));
+    }
+  }
+  if let Some(result) = parse_transaction(&fields, day_first) {
+    match result {
+      Ok(transaction) => transactions.push(transaction),
+      Err(issue) => issues.push(issue),
+    }
+  }
+
+  (transactions, issues, skipped)
+}
+
+fn parse_transaction(
+  fields: &[(usize, char, &str)],
+  day_first: bool,
+) -> Option<Result<Transaction, Issue>> {
+  let (line, _, _) = fields.first()?;
+  let line = *line;
+  let error = |message: String| Some(Err(Issue { line, message }));
+
+  let mut date = None;
+  let mut amount = None;
+  let mut payee = String::default();
+  let mut memo = String::default();
+  let mut category = String::default();
+  let mut splits: Vec<Split> = Vec::new();
+  for (_, code, value) in fields {
+    let value = value.trim();
+    match code {
+      'D' => match parse_date(value, day_first) {
+        Some(value) => date = Some(value),
+        None => return error(format!("Invalid date: {}", value)),
+      },
+      'T' | 'U' if amount.is_none() => match parse_amount(value) {
+        Some(value) => amount = Some(value),
+        None => return error(format!("Invalid amount: {}", value)),
+      },
+      'P' => payee = value.to_string(),
+      'M' => memo = value.to_string(),
+      'L' => category = value.to_string(),
+      'S' => splits.push(Split {
+        category: value.to_string(),
+        memo: String::default(),
+        amount: Decimal::ZERO,
+      }),
+      'E' => {
+        if let Some(split) = splits.last_mut() {
+          split.memo = value.to_string();
+        }
+      }
+      '$' => match (splits.last_mut(), parse_amount(value)) {
+        (Some(split), Some(value)) => split.amount = value,
+        (_, None) => return error(format!("Invalid amount: {}", value)),
+        (None, _) => return error("Split amount without category".to_string()),
+      },
+      // The check numbers, cleared statuses, addresses and others
+      _ => {}
+    }
+  }
+
+  let Some(date) = date else { return error("Missing date".to_string()) };
+  let Some(amount) = amount else { return error("Missing amount".to_string()) };
+  if !splits.is_empty() {
+    let total: Decimal = splits.iter().map(|split| split.amount).sum();
+    if total != amount {
+      return error(format!("The splits total {} instead of {}", total, amount));
+    }
+  }
+  Some(Ok(Transaction { line, date, amount, payee, memo, category, splits }))
+}
+
+/// The dates are like `1/5/2024`, `01/05/24`, `1/ 5'24` or `2024-01-05`
+fn parse_date(value: &str, day_first: bool) -> Option<NaiveDate> {
+  let normalized: String =
+    value.chars().filter(|c| !c.is_whitespace()).map(|c| if c == '\'' { '/' } else { c }).collect();
+  let parts: Vec<_> = normalized.split(['/', '-', '.']).filter(|part| !part.is_empty()).collect();
+  let [first, second, third] = parts[..] else { return None };
+  let (year, month, day) = if first.len() == 4 {
+    (first, second, third)
+  } else if day_first {
+    (third, second, first)
+  } else {
+    (third, first, second)
+  };
+
+  let year: i32 = year.parse().ok()?;
+  let year = match year {
+    0..=69 => 2000 + year,
+    70..=99 => 1900 + year,
+    _ => year,
+  };
+  NaiveDate::from_ymd_opt(year, month.parse().ok()?, day.parse().ok()?)
+}
+
+fn parse_amount(value: &str) -> Option<Decimal> {
+  Decimal::from_str(&value.replace(',', "")).ok()
+}
+
+/// The name of the account of the category, and if it is a transfer like `[Checking]`, with the
+/// class after `/` ignored
+fn account_name(category: &str) -> Option<(String, bool)> {
+  let category = category.split('/').next().unwrap_or_default().trim();
+  if category.is_empty() {
+    return None;
+  }
+  match category.strip_prefix('[').and_then(|category| category.strip_suffix(']')) {
+    Some(transfer) => Some((transfer.trim().to_string(), true)),
+    None => Some((
+      category.split(':').map(str::trim).collect::<Vec<_>>().join(account::NAME_SPLITERATOR),
+      false,
+    )),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::import::qif::{account_name, parse, parse_date};
+  use chrono::NaiveDate;
+  use rust_decimal_macros::dec;
+
+  #[test]
+  fn test_parse_date() -> anyhow::Result<()> {
+    let date = NaiveDate::from_ymd_opt(2024, 1, 5);
+    assert_eq!(date, parse_date("1/5/2024", false));
+    assert_eq!(date, parse_date("01/05/24", false));
+    assert_eq!(date, parse_date("1/ 5'24", false));
+    assert_eq!(date, parse_date("5.1.2024", true));
+    assert_eq!(date, parse_date("2024-01-05", true));
+    assert_eq!(NaiveDate::from_ymd_opt(1994, 12, 31), parse_date("12/31/94", false));
+    assert_eq!(None, parse_date("13/31/2024", false));
+
+    assert_eq!(Some(("Food::Dining".to_string(), false)), account_name("Food:Dining/Trip"));
+    assert_eq!(Some(("Savings".to_string(), true)), account_name("[Savings]"));
+    assert_eq!(None, account_name(""));
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse() -> anyhow::Result<()> {
+    let content = r#"!Account
+NChecking
+TBank
+^
+!Type:Bank
+D01/05/2024
+T-1,003.50
+PGrocery Store
+MWeekly
+LFood:Groceries
+^
+D01/06/2024
+T-100.00
+PSupermarket
+SFood
+EBread
+$-60.00
+SHousehold
+$-40.00
+^
+D01/07/2024
+T-10.00
+SFood
+$-5.00
+^
+DXX
+T1
+^
+!Type:Invst
+D01/08/2024
+^
+"#;
+    let (transactions, issues, skipped) = parse(content, false);
+    assert_eq!(vec![1, 29], skipped.iter().map(|issue| issue.line).collect::<Vec<_>>());
+    assert_eq!(vec![21, 26], issues.iter().map(|issue| issue.line).collect::<Vec<_>>());
+    assert_eq!(2, transactions.len());
+    assert_eq!(6, transactions[0].line);
+    assert_eq!(dec!(-1003.5), transactions[0].amount);
+    assert_eq!("Food:Groceries", transactions[0].category);
+    assert_eq!(2, transactions[1].splits.len());
+    assert_eq!("Bread", transactions[1].splits[0].memo);
+    assert_eq!(dec!(-40), transactions[1].splits[1].amount);
+
+    Ok(())
+  }
+}
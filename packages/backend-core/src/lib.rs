@@ -4,8 +4,13 @@ use sea_orm::{ConnectOptions, Database, DatabaseConnection};
 
 use std::env;
 
+pub mod actor;
+pub mod backup;
 pub mod entity;
 pub mod error;
+pub mod export;
+pub mod import;
+pub mod undo;
 
 pub use error::{Error, Result};
 
@@ -0,0 +1,383 @@
+use crate::actor::Actor;
+use crate::entity::membership::{self, Role};
+use crate::entity::{account, entry, history, journal, payee, WriteRoot, FIELD_ID, FIELD_VERSION};
+use crate::error::{ErrorConflict, ErrorInternal};
+use itertools::Itertools;
+use sea_orm::{ConnectionTrait, TransactionTrait};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+pub const MAX_UNDO_LENGTH: usize = 100;
+
+/// The change to put an entity back to a snapshot, as long as the entity still matches the one
+/// left by the command
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Change {
+  pub entity: String,
+  pub id: Uuid,
+  /// `None` if the entity should not exist
+  pub expected: Option<Value>,
+  /// `None` to delete the entity
+  pub restored: Option<Value>,
+}
+
+/// The inverse of a handled command, as the changes in the order to apply.
+///
+/// The accounts, the payees and the entries removed by deleting the journals, and the entries
+/// removed by deleting the accounts, are restored as well, but not the other entities cascaded,
+/// such as the reconciliations
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Inverse {
+  pub changes: Vec<Change>,
+}
+
+impl Inverse {
+  /// Record the snapshots of the entities before and after a command, keyed by the IDs. The ones
+  /// recorded later are reverted earlier
+  pub(crate) fn record<'a, R: Serialize + 'a>(
+    &mut self,
+    entity: &str,
+    befores: impl IntoIterator<Item = (Uuid, &'a R)>,
+    afters: impl IntoIterator<Item = (Uuid, &'a R)>,
+  ) -> crate::Result<()> {
+    let befores: Vec<_> = befores.into_iter().collect();
+    let afters: Vec<_> = afters.into_iter().collect();
+    let ids: Vec<_> = befores.iter().chain(afters.iter()).map(|(id, _)| *id).unique().collect();
+    let mut befores = to_values(befores)?;
+    let mut afters = to_values(afters)?;
+
+    let changes = ids.into_iter().map(|id| Change {
+      entity: entity.to_string(),
+      id,
+      expected: afters.remove(&id),
+      restored: befores.remove(&id),
+    });
+    self.changes.splice(0..0, changes);
+    Ok(())
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.changes.is_empty()
+  }
+
+  /// The journals of the entities changed, except the ones restored by the changes
+  fn journal_ids(&self) -> HashSet<Uuid> {
+    let restored_ids: HashSet<_> = self
+      .changes
+      .iter()
+      .filter(|change| change.entity == journal::TYPE && change.expected.is_none())
+      .map(|change| change.id)
+      .collect();
+    self
+      .changes
+      .iter()
+      .flat_map(|change| {
+        if change.entity == journal::TYPE {
+          vec![change.id]
+        } else {
+          change
+            .expected
+            .iter()
+            .chain(change.restored.iter())
+            .filter_map(|value| value[history::FIELD_JOURNAL_ID].as_str())
+            .filter_map(|id| Uuid::parse_str(id).ok())
+            .collect()
+        }
+      })
+      .filter(|id| !restored_ids.contains(id))
+      .collect()
+  }
+
+  /// Apply the changes in a transaction, and return the inverse of this one to apply it back. The
+  /// actor should be an editor of the journals, and becomes the owner of the journals restored
+  pub async fn apply(
+    self,
+    db: &(impl ConnectionTrait + TransactionTrait),
+    actor: &Actor,
+    command: &str,
+  ) -> crate::Result<Inverse> {
+    actor.check_journals(db, self.journal_ids(), Role::Editor).await?;
+
+    let mut inverse = Inverse::default();
+    let txn = db.begin().await?;
+    for (entity, changes) in &self.changes.iter().chunk_by(|change| change.entity.clone()) {
+      let changes: Vec<_> = changes.collect();
+      let ids: HashSet<_> = changes.iter().map(|change| change.id).collect();
+      match entity.as_str() {
+        journal::TYPE => {
+          let query = journal::Query { id: ids, ..Default::default() };
+          apply_changes::<journal::Root>(
+            &txn,
+            actor,
+            command,
+            &entity,
+            query,
+            changes,
+            &mut inverse,
+          )
+          .await?
+        }
+        account::TYPE => {
+          let query = account::Query { id: ids, ..Default::default() };
+          apply_changes::<account::Root>(
+            &txn,
+            actor,
+            command,
+            &entity,
+            query,
+            changes,
+            &mut inverse,
+          )
+          .await?
+        }
+        payee::TYPE => {
+          let query = payee::Query { id: ids, ..Default::default() };
+          apply_changes::<payee::Root>(&txn, actor, command, &entity, query, changes, &mut inverse)
+            .await?
+        }
+        entry::TYPE => {
+          let query = entry::Query { id: ids, ..Default::default() };
+          apply_changes::<entry::Root>(&txn, actor, command, &entity, query, changes, &mut inverse)
+            .await?
+        }
+        _ => {
+          return Err(
+            ErrorInternal { message: format!("Entity[{}] cannot be undone", entity) }.into(),
+          )
+        }
+      }
+    }
+    if let Actor::User(user_id) = actor {
+      let restored = self
+        .changes
+        .iter()
+        .filter(|change| change.entity == journal::TYPE && change.expected.is_none())
+        .filter(|change| change.restored.is_some());
+      let memberships = membership::Root::save(
+        &txn,
+        restored.map(|change| membership::Root {
+          journal_id: change.id,
+          user_id: *user_id,
+          role: Role::Owner,
+        }),
+      )
+      .await?;
+      membership::Root::record(
+        &txn,
+        Uuid::new_v4(),
+        actor,
+        &json!({ "commandType": command }),
+        &[],
+        &memberships,
+      )
+      .await?;
+    }
+    txn.commit().await?;
+
+    Ok(inverse)
+  }
+}
+
+/// The undo and the redo stacks of a session, such as a window of the desktop app
+#[derive(Debug, Default)]
+pub struct Session {
+  undos: Vec<Inverse>,
+  redos: Vec<Inverse>,
+}
+
+impl Session {
+  /// Push the inverse of a newly handled command, which discards the ones to redo
+  pub fn push(&mut self, inverse: Inverse) {
+    if inverse.is_empty() {
+      return;
+    }
+
+    self.undos.push(inverse);
+    if self.undos.len() > MAX_UNDO_LENGTH {
+      self.undos.remove(0);
+    }
+    self.redos.clear();
+  }
+
+  pub fn can_undo(&self) -> bool {
+    !self.undos.is_empty()
+  }
+
+  pub fn can_redo(&self) -> bool {
+    !self.redos.is_empty()
+  }
+
+  /// Undo the last command, and return if there is any. The stacks are kept as they are on errors,
+  /// such as the entities having been changed since
+  pub async fn undo(
+    &mut self,
+    db: &(impl ConnectionTrait + TransactionTrait),
+    actor: &Actor,
+  ) -> crate::Result<bool> {
+    let Some(inverse) = self.undos.last().cloned() else {
+      return Ok(false);
+    };
+
+    let redo = inverse.apply(db, actor, "undo").await?;
+    self.undos.pop();
+    self.redos.push(redo);
+    Ok(true)
+  }
+
+  /// Redo the last undone command, and return if there is any
+  pub async fn redo(
+    &mut self,
+    db: &(impl ConnectionTrait + TransactionTrait),
+    actor: &Actor,
+  ) -> crate::Result<bool> {
+    let Some(inverse) = self.redos.last().cloned() else {
+      return Ok(false);
+    };
+
+    let undo = inverse.apply(db, actor, "redo").await?;
+    self.redos.pop();
+    self.undos.push(undo);
+    Ok(true)
+  }
+
+  pub fn clear(&mut self) {
+    self.undos.clear();
+    self.redos.clear();
+  }
+}
+
+async fn apply_changes<R>(
+  db: &impl ConnectionTrait,
+  actor: &Actor,
+  command: &str,
+  entity: &str,
+  query: R::Query,
+  changes: Vec<&Change>,
+  inverse: &mut Inverse,
+) -> crate::Result<()>
+where
+  R: WriteRoot + Serialize + DeserializeOwned,
+{
+  let mut currents: HashMap<_, _> = R::find_all(db, Some(query), None, None)
+    .await?
+    .into_iter()
+    .map(|root| (root.id(), root))
+    .collect();
+
+  let mut befores = Vec::new();
+  let mut restoreds = Vec::new();
+  let mut deleted_ids = Vec::new();
+  for change in changes {
+    let current = currents.remove(&change.id.to_string());
+    let value =
+      current.as_ref().map(serde_json::to_value).transpose().map_err(ErrorInternal::from)?;
+    if value.clone().map(normalize) != change.expected.clone().map(normalize) {
+      return Err(crate::Error::Conflict(ErrorConflict {
+        entity: entity.to_string(),
+        values: vec![(FIELD_ID.to_string(), change.id.to_string())],
+      }));
+    }
+
+    match change.restored.clone() {
+      Some(mut restored) => {
+        // Take the place of the current version, so that restoring bumps it as any other save
+        if let (Some(restored), Some(value)) = (restored.as_object_mut(), value) {
+          restored.insert(FIELD_VERSION.to_string(), value[FIELD_VERSION].clone());
+        }
+        restoreds.push(serde_json::from_value::<R>(restored).map_err(ErrorInternal::from)?);
+      }
+      None => deleted_ids.push(change.id),
+    }
+    if let Some(current) = current {
+      befores.push((change.id, current));
+    }
+  }
+
+  R::delete(db, deleted_ids).await?;
+  let afters = R::save(db, restoreds).await?;
+  let afters: Vec<_> = afters
+    .iter()
+    .map(|root| Ok((Uuid::parse_str(&root.id()).map_err(ErrorInternal::from)?, root)))
+    .collect::<crate::Result<_>>()?;
+  history::Root::record(
+    db,
+    Uuid::new_v4(),
+    entity,
+    actor,
+    &json!({ "commandType": command }),
+    befores.iter().map(|(id, root)| (*id, root)),
+    afters.clone(),
+  )
+  .await?;
+  inverse.record(entity, befores.iter().map(|(id, root)| (*id, root)), afters)
+}
+
+/// Sort the arrays recursively, since the sets of the roots, such as the tags, are serialized in
+/// any order
+fn normalize(value: Value) -> Value {
+  match value {
+    Value::Array(values) => Value::Array(
+      values.into_iter().map(normalize).sorted_by_key(|value| value.to_string()).collect(),
+    ),
+    Value::Object(values) => {
+      Value::Object(values.into_iter().map(|(key, value)| (key, normalize(value))).collect())
+    }
+    value => value,
+  }
+}
+
+fn to_values<R: Serialize>(roots: Vec<(Uuid, &R)>) -> crate::Result<HashMap<Uuid, Value>> {
+  let mut values = HashMap::new();
+  for (id, root) in roots {
+    values.insert(id, serde_json::to_value(root).map_err(ErrorInternal::from)?);
+  }
+  Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::undo::{normalize, Inverse};
+  use serde_json::json;
+  use uuid::uuid;
+
+  #[test]
+  fn test_normalize() {
+    assert_eq!(
+      normalize(json!({ "tags": ["b", "a"], "items": [{ "tags": ["d", "c"] }, { "amount": 1 }] })),
+      normalize(json!({ "tags": ["a", "b"], "items": [{ "amount": 1 }, { "tags": ["c", "d"] }] })),
+    );
+    assert_ne!(normalize(json!({ "tags": ["a"] })), normalize(json!({ "tags": ["a", "b"] })));
+  }
+
+  #[test]
+  fn test_record() -> anyhow::Result<()> {
+    let id1 = uuid!("7aaec70c-adbc-47d1-8b74-a3e21f387d21");
+    let id2 = uuid!("7aaec70c-adbc-47d1-8b74-a3e21f387d22");
+    let mut inverse = Inverse::default();
+    inverse.record("Entry", [(id1, &"Entry 1")], [(id2, &"Entry 2")])?;
+    inverse.record("Account", [(id1, &"Account 1")], [])?;
+
+    assert_eq!(
+      vec![
+        ("Account", id1, None, Some(json!("Account 1"))),
+        ("Entry", id1, None, Some(json!("Entry 1"))),
+        ("Entry", id2, Some(json!("Entry 2")), None),
+      ],
+      inverse
+        .changes
+        .iter()
+        .map(|change| (
+          change.entity.as_str(),
+          change.id,
+          change.expected.clone(),
+          change.restored.clone()
+        ))
+        .collect::<Vec<_>>()
+    );
+    Ok(())
+  }
+}
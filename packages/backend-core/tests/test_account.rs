@@ -71,6 +71,7 @@ pub async fn test_swap_name() -> anyhow::Result<()> {
         unit: "".to_string(),
         typ: None,
         tags: None,
+        expected_version: None,
       },
       account::CommandUpdate {
         id: accounts[1].id,
@@ -79,6 +80,7 @@ pub async fn test_swap_name() -> anyhow::Result<()> {
         unit: "".to_string(),
         typ: None,
         tags: None,
+        expected_version: None,
       },
     ],
   )
@@ -118,6 +120,7 @@ pub async fn test_swap_name2() -> anyhow::Result<()> {
       unit: "".to_string(),
       typ: None,
       tags: None,
+      expected_version: None,
     }],
   )
   .await
@@ -157,6 +160,7 @@ pub async fn test_multi_update() -> anyhow::Result<()> {
         unit: "".to_string(),
         typ: None,
         tags: None,
+        expected_version: None,
       },
       account::CommandUpdate {
         id: account.id,
@@ -165,6 +169,7 @@ pub async fn test_multi_update() -> anyhow::Result<()> {
         unit: "".to_string(),
         typ: None,
         tags: None,
+        expected_version: None,
       },
     ],
   )
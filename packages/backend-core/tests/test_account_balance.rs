@@ -0,0 +1,111 @@
+use backend_core::entity::{account, account_balance, entry, journal, ReadRoot, WriteRoot};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sea_orm::DbConn;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+async fn expected_balances(
+  db: &DbConn,
+  journal_id: Uuid,
+  date: Option<NaiveDate>,
+) -> anyhow::Result<HashMap<Uuid, Decimal>> {
+  let entries = entry::Root::find_all(
+    db,
+    Some(entry::Query {
+      journal_id: HashSet::from_iter([journal_id]),
+      typ: Some(entry::Type::Record),
+      end: date,
+      ..Default::default()
+    }),
+    None,
+    None,
+  )
+  .await?;
+  let mut balances = HashMap::<_, Decimal>::new();
+  for item in entries.iter().flat_map(|entry| &entry.items) {
+    *balances.entry(item.account).or_default() += item.amount * item.price;
+  }
+  Ok(balances)
+}
+
+async fn assert_balances(db: &DbConn, journal_id: Uuid) -> anyhow::Result<()> {
+  let account_ids: HashSet<_> = account::Root::find_all(
+    db,
+    Some(account::Query { journal_id: HashSet::from_iter([journal_id]), ..Default::default() }),
+    None,
+    None,
+  )
+  .await?
+  .into_iter()
+  .map(|account| account.id)
+  .collect();
+
+  for date in [None, NaiveDate::from_ymd_opt(2000, 1, 1)] {
+    let expected = expected_balances(db, journal_id, date).await?;
+    let actual = account_balance::find_balances(db, account_ids.clone(), date).await?;
+    for account_id in &account_ids {
+      assert_eq!(
+        expected.get(account_id).copied().unwrap_or_default(),
+        actual.get(account_id).copied().unwrap_or_default()
+      );
+    }
+  }
+
+  Ok(())
+}
+
+#[tokio::test]
+pub async fn test_maintain_on_write() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  let journal = journal::Root::find_one(&db, None).await?.unwrap();
+  assert_balances(&db, journal.id).await?;
+
+  let entries = entry::Root::find_all(
+    &db,
+    Some(entry::Query {
+      journal_id: HashSet::from_iter([journal.id]),
+      typ: Some(entry::Type::Record),
+      ..Default::default()
+    }),
+    Some(2),
+    None,
+  )
+  .await?;
+
+  let _ = entry::Root::update(
+    &db,
+    vec![entry::CommandUpdate {
+      id: entries[0].id,
+      name: "".to_string(),
+      description: None,
+      typ: None,
+      date: NaiveDate::from_ymd_opt(1999, 12, 31),
+      tags: None,
+      items: entries[1].items.clone(),
+      payee_id: None,
+      expected_version: None,
+    }],
+  )
+  .await?;
+  assert_balances(&db, journal.id).await?;
+
+  entry::Root::delete(&db, [entries[1].id]).await?;
+  assert_balances(&db, journal.id).await?;
+
+  Ok(())
+}
+
+#[tokio::test]
+pub async fn test_rebuild() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  let journal = journal::Root::find_one(&db, None).await?.unwrap();
+
+  account_balance::rebuild(&db, HashSet::from_iter([journal.id])).await?;
+  assert_balances(&db, journal.id).await?;
+
+  account_balance::rebuild(&db, HashSet::default()).await?;
+  assert_balances(&db, journal.id).await?;
+
+  Ok(())
+}
@@ -0,0 +1,44 @@
+use backend_core::actor::Actor;
+use backend_core::entity::{api_token, user, ReadRoot};
+use backend_core::Error;
+use std::collections::HashSet;
+
+#[tokio::test]
+pub async fn test_api_token() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+
+  let mut users = Vec::new();
+  for name in ["Alice", "Bob"] {
+    let user = user::Root::handle(
+      &db,
+      &Actor::system("admin"),
+      user::Command::Create(user::CommandCreate { name: name.to_string() }),
+    )
+    .await?
+    .remove(0);
+    users.push(user);
+  }
+  let alice = Actor::User(users[0].id);
+  let bob = Actor::User(users[1].id);
+
+  let command = api_token::CommandIssue { user_id: users[0].id, name: "CLI".to_string() };
+  let result = api_token::Root::issue(&db, &bob, command.clone()).await;
+  assert!(matches!(result, Err(Error::Forbidden(_))));
+
+  let issued = api_token::Root::issue(&db, &alice, command).await?;
+  assert!(issued.token.starts_with(api_token::TOKEN_PREFIX));
+  assert_eq!(Some(issued.root.clone()), api_token::Root::authenticate(&db, &issued.token).await?);
+  assert_eq!(None, api_token::Root::authenticate(&db, "wr_unknown").await?);
+  assert_eq!(None, api_token::Root::authenticate(&db, "Bearer something").await?);
+
+  let delete = api_token::Command::Delete(api_token::CommandDelete {
+    id: HashSet::from_iter([issued.root.id]),
+  });
+  let result = api_token::Root::handle(&db, &bob, delete.clone()).await;
+  assert!(matches!(result, Err(Error::Forbidden(_))));
+  api_token::Root::handle(&db, &alice, delete).await?;
+  assert_eq!(None, api_token::Root::authenticate(&db, &issued.token).await?);
+  assert!(api_token::Root::find_all(&db, None, None, None).await?.is_empty());
+
+  Ok(())
+}
@@ -0,0 +1,92 @@
+use backend_core::backup::{self, Mode, Restore};
+use backend_core::entity::{account, entry, journal, ReadRoot, WriteRoot};
+use backend_core::Error;
+use std::collections::HashSet;
+
+#[tokio::test]
+pub async fn test_backup_and_restore() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  let source = journal::Root::find_one(&db, None).await?.unwrap();
+
+  let document = serde_json::to_string(&backup::backup(&db, source.id).await?)?;
+  let backup: backup::Backup = serde_json::from_str(&document)?;
+  assert_eq!(backup::VERSION, backup.version);
+  assert!(!backup.accounts.is_empty());
+  assert!(!backup.entries.is_empty());
+
+  let restore = Restore { backup: backup.clone(), mode: Mode::Preserve, name: None };
+  let result = backup::restore(&db, restore.clone()).await;
+  assert!(matches!(result, Err(Error::ExistingEntity(_))));
+
+  let result = backup::restore(&db, Restore { mode: Mode::Remap, ..restore.clone() }).await;
+  assert!(matches!(result, Err(Error::ExistingEntity(_))));
+
+  let copy = backup::restore(
+    &db,
+    Restore { mode: Mode::Remap, name: Some("Restored Copy".to_string()), ..restore.clone() },
+  )
+  .await?;
+  assert_ne!(source.id, copy.id);
+  let copied = backup::backup(&db, copy.id).await?;
+  assert_eq!(backup.accounts.len(), copied.accounts.len());
+  assert_eq!(backup.entries.len(), copied.entries.len());
+  assert!(copied
+    .accounts
+    .iter()
+    .all(|account| !backup.accounts.iter().any(|a| a.id == account.id)));
+
+  journal::Root::delete(&db, [source.id]).await?;
+  let restored = backup::restore(&db, restore).await?;
+  assert_eq!(journal::Root { updated_at: restored.updated_at, ..source }, restored);
+  let restored = backup::backup(&db, restored.id).await?;
+  assert_eq!(
+    backup.entries.iter().map(|entry| entry.id).collect::<HashSet<_>>(),
+    restored.entries.iter().map(|entry| entry.id).collect::<HashSet<_>>()
+  );
+
+  Ok(())
+}
+
+#[tokio::test]
+pub async fn test_restore_rolls_back() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  let source = journal::Root::find_one(&db, None).await?.unwrap();
+  let mut backup = backup::backup(&db, source.id).await?;
+  let accounts = account::Root::find_all(&db, None, None, None).await?.len();
+
+  let result = backup::restore(
+    &db,
+    Restore {
+      backup: backup::Backup { version: 0, ..backup.clone() },
+      mode: Mode::Remap,
+      name: None,
+    },
+  )
+  .await;
+  assert!(matches!(result, Err(Error::OutOfRange(_))));
+
+  // An entry of an account missing in the document fails after the journal is checked
+  backup.entries[0].items.push(entry::Item {
+    account: uuid::Uuid::new_v4(),
+    amount: 1.into(),
+    price: 1.into(),
+  });
+  let result = backup::restore(
+    &db,
+    Restore { backup, mode: Mode::Remap, name: Some("Broken Copy".to_string()) },
+  )
+  .await;
+  assert!(matches!(result, Err(Error::NotFound(_))));
+  assert!(journal::Root::find_one(
+    &db,
+    Some(journal::Query {
+      name: HashSet::from_iter(["Broken Copy".to_string()]),
+      ..Default::default()
+    })
+  )
+  .await?
+  .is_none());
+  assert_eq!(accounts, account::Root::find_all(&db, None, None, None).await?.len());
+
+  Ok(())
+}
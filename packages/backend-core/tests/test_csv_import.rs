@@ -0,0 +1,95 @@
+use backend_core::actor::Actor;
+use backend_core::entity::{account, csv_profile, entry, ReadRoot};
+use backend_core::import;
+use backend_core::Error;
+use rust_decimal_macros::dec;
+
+#[tokio::test]
+pub async fn test_csv_import() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  // The outflows of the card are recorded as the expenses, both on the normal sides
+  let accounts = account::Root::find_all(&db, None, None, None).await?;
+  let account = accounts.iter().find(|account| account.typ == account::Type::Liability).unwrap();
+  let counter_account = accounts
+    .iter()
+    .find(|other| other.journal_id == account.journal_id && other.typ == account::Type::Expense)
+    .unwrap();
+
+  let command = csv_profile::CommandCreate {
+    journal_id: account.journal_id,
+    name: "Bank Export".to_string(),
+    mapping: csv_profile::Mapping {
+      delimiter: ',',
+      date_column: "Date".to_string(),
+      date_format: "".to_string(),
+      amount_column: Some("Amount".to_string()),
+      debit_column: None,
+      credit_column: None,
+      description_column: Some("Description".to_string()),
+      decimal_separator: '.',
+      account_id: account.id,
+      counter_account_id: counter_account.id,
+    },
+  };
+  let profiles = csv_profile::Root::handle(
+    &db,
+    &Actor::system("tester"),
+    csv_profile::Command::Create(command.clone()),
+  )
+  .await?;
+  let profile = &profiles[0];
+  assert_eq!(csv_profile::Mapping::DEFAULT_DATE_FORMAT, profile.mapping.date_format);
+
+  let result = csv_profile::Root::handle(
+    &db,
+    &Actor::system("tester"),
+    csv_profile::Command::Create(command.clone()),
+  )
+  .await;
+  assert!(matches!(result, Err(Error::ExistingEntity(_))));
+
+  let mut invalid = command;
+  invalid.name = "Other Bank Export".to_string();
+  invalid.mapping.delimiter = '；';
+  let result =
+    csv_profile::Root::handle(&db, &Actor::system("tester"), csv_profile::Command::Create(invalid))
+      .await;
+  assert!(matches!(result, Err(Error::OutOfRange(_))));
+
+  let content =
+    "Date,Description,Amount\n2024-02-01,Groceries,-42.10\n2024-02-01,Groceries,-42.10\n";
+  let import =
+    import::csv::Import { profile_id: profile.id, content: content.to_string(), preview: true };
+  let preview = import::csv::import(&db, import.clone()).await?;
+  assert!(preview.issues.is_empty());
+  assert!(preview.created.is_empty());
+  assert_eq!(
+    vec!["2024-02-01 Groceries", "2024-02-01 Groceries #2"],
+    preview.commands.iter().map(|command| command.name.as_str()).collect::<Vec<_>>()
+  );
+
+  let result = import::csv::import(&db, import::csv::Import { preview: false, ..import }).await?;
+  assert_eq!(2, result.created.len());
+  let entries = entry::Root::find_all(
+    &db,
+    Some(entry::Query { id: result.created.iter().copied().collect(), ..Default::default() }),
+    None,
+    None,
+  )
+  .await?;
+  assert_eq!(2, entries.len());
+  for entry in entries {
+    assert!(entry.items.iter().all(|item| item.amount == dec!(42.10)));
+  }
+
+  let bad = "Date,Description,Amount\n2024-02-01,Rent,abc\n2024-02-02,Refund,5.00\n";
+  let result = import::csv::import(
+    &db,
+    import::csv::Import { profile_id: profile.id, content: bad.to_string(), preview: false },
+  )
+  .await?;
+  assert_eq!(vec![2, 3], result.issues.iter().map(|issue| issue.line).collect::<Vec<_>>());
+  assert!(result.created.is_empty());
+
+  Ok(())
+}
@@ -0,0 +1,114 @@
+use backend_core::entity::{account, entry, hierarchy_report, journal, ReadRoot};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::{HashMap, HashSet};
+
+#[tokio::test]
+pub async fn test_find_all() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  let journal = journal::Root::find_one(&db, None).await?.unwrap();
+
+  let entries = entry::Root::find_all(
+    &db,
+    Some(entry::Query {
+      journal_id: HashSet::from_iter([journal.id]),
+      typ: Some(entry::Type::Record),
+      ..Default::default()
+    }),
+    None,
+    None,
+  )
+  .await?;
+  let mut expected = HashMap::<_, Decimal>::new();
+  for item in entries.iter().flat_map(|entry| &entry.items) {
+    *expected.entry(item.account).or_default() += item.amount * item.price;
+  }
+
+  let roots = hierarchy_report::Root::find_all(
+    &db,
+    Some(hierarchy_report::Query {
+      journal_id: HashSet::from_iter([journal.id]),
+      max_depth: Some(1),
+      ..Default::default()
+    }),
+    None,
+    None,
+  )
+  .await?;
+
+  let actual: HashMap<_, _> = roots.into_iter().flat_map(|root| root.values).collect();
+  for (account_id, value) in actual {
+    assert_eq!(expected.get(&account_id).copied().unwrap_or_default(), value);
+  }
+
+  Ok(())
+}
+
+#[tokio::test]
+pub async fn test_fractional_values() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  let journal = journal::Root::find_one(&db, None).await?.unwrap();
+  let accounts = account::Root::create(
+    &db,
+    ["Assets::Fraction", "Income::Fraction"]
+      .into_iter()
+      .map(|name| account::CommandCreate {
+        journal_id: journal.id,
+        name: name.to_string(),
+        description: String::default(),
+        unit: journal.unit.clone(),
+        typ: account::Type::Asset,
+        tags: HashSet::default(),
+      })
+      .collect(),
+  )
+  .await?;
+
+  let commands = [
+    (dec!(0.1), dec!(1)),
+    (dec!(0.2), dec!(1)),
+    (dec!(0.7), dec!(1.3)),
+    (dec!(1234567.89), dec!(1.1)),
+  ]
+  .into_iter()
+  .enumerate()
+  .map(|(idx, (amount, price))| entry::CommandCreate {
+    journal_id: journal.id,
+    name: format!("Fractional Entry {}", idx),
+    description: String::default(),
+    typ: entry::Type::Record,
+    date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+    tags: HashSet::from_iter(["fraction".to_string()]),
+    items: accounts
+      .iter()
+      .map(|account| entry::Item { account: account.id, amount, price })
+      .collect(),
+    payee_id: None,
+  })
+  .collect();
+  entry::Root::create(&db, commands).await?;
+
+  // 0.1 + 0.2 + 0.7 * 1.3 + 1234567.89 * 1.1
+  let expected = dec!(1358025.889);
+  // From the balance snapshots, and from the entries filtered by the tags
+  for entry_tags in [HashSet::default(), HashSet::from_iter(["fraction".to_string()])] {
+    let roots = hierarchy_report::Root::find_all(
+      &db,
+      Some(hierarchy_report::Query {
+        journal_id: HashSet::from_iter([journal.id]),
+        entry_tags,
+        ..Default::default()
+      }),
+      None,
+      None,
+    )
+    .await?;
+    let values: HashMap<_, _> = roots.into_iter().flat_map(|root| root.values).collect();
+    for account in &accounts {
+      assert_eq!(expected, values[&account.id]);
+    }
+  }
+
+  Ok(())
+}
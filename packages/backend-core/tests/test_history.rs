@@ -0,0 +1,186 @@
+use backend_core::actor::Actor;
+use backend_core::entity::{account, entry, history, journal, ReadRoot};
+use std::collections::{HashMap, HashSet};
+
+#[tokio::test]
+pub async fn test_history() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+
+  let created = journal::Root::handle(
+    &db,
+    &Actor::system("alice"),
+    journal::Command::Create(journal::CommandCreate {
+      name: "History Journal".to_string(),
+      description: String::default(),
+      unit: "USD".to_string(),
+      tags: HashSet::default(),
+    }),
+  )
+  .await?
+  .remove(0);
+  let updated = journal::Root::handle(
+    &db,
+    &Actor::system("bob"),
+    journal::Command::Update(journal::CommandUpdate {
+      id: created.id,
+      name: "Renamed Journal".to_string(),
+      description: None,
+      unit: String::default(),
+      tags: None,
+      expected_version: None,
+    }),
+  )
+  .await?
+  .remove(0);
+  journal::Root::handle(
+    &db,
+    &Actor::system("alice"),
+    journal::Command::Delete(journal::CommandDelete {
+      id: HashSet::from_iter([created.id]),
+      expected_version: HashMap::default(),
+    }),
+  )
+  .await?;
+
+  let histories = history::Root::find_all(
+    &db,
+    Some(history::Query { entity_id: HashSet::from_iter([created.id]), ..Default::default() }),
+    None,
+    Some(history::Sort::Timestamp),
+  )
+  .await?;
+  assert_eq!(
+    vec!["alice", "bob", "alice"],
+    histories.iter().map(|history| history.actor.as_str()).collect::<Vec<_>>()
+  );
+  assert!(histories.iter().all(|history| history.entity == journal::TYPE));
+  assert_eq!("journals:update", histories[1].command["commandType"]);
+
+  let snapshot = |value: &Option<serde_json::Value>| -> anyhow::Result<Option<journal::Root>> {
+    Ok(value.clone().map(serde_json::from_value).transpose()?)
+  };
+  assert_eq!(None, snapshot(&histories[0].before)?);
+  assert_eq!(Some(created.clone()), snapshot(&histories[0].after)?);
+  assert_eq!(Some(created), snapshot(&histories[1].before)?);
+  assert_eq!(Some(updated.clone()), snapshot(&histories[1].after)?);
+  assert_eq!(Some(updated), snapshot(&histories[2].before)?);
+  assert_eq!(None, snapshot(&histories[2].after)?);
+
+  let entries = entry::Root::find_all(&db, None, Some(2), None).await?;
+  entry::Root::handle(
+    &db,
+    &Actor::system("carol"),
+    entry::Command::Delete(entry::CommandDelete {
+      id: entries.iter().map(|entry| entry.id).collect(),
+      expected_version: HashMap::default(),
+    }),
+  )
+  .await?;
+  let histories = history::Root::find_all(
+    &db,
+    Some(history::Query { actor: HashSet::from_iter(["carol".to_string()]), ..Default::default() }),
+    None,
+    None,
+  )
+  .await?;
+  assert_eq!(2, histories.len());
+  assert_eq!(1, histories.iter().map(|history| history.command_id).collect::<HashSet<_>>().len());
+  for entry in entries {
+    let history = histories.iter().find(|history| history.entity_id == entry.id).unwrap();
+    assert_eq!(
+      Some(entry.clone()),
+      history.before.clone().map(serde_json::from_value).transpose()?
+    );
+    assert_eq!(Some(entry.journal_id), history.journal_id());
+  }
+
+  Ok(())
+}
+
+#[tokio::test]
+pub async fn test_history_cascade() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  let account_id = entry::Root::find_one(&db, None).await?.unwrap().items[0].account;
+  let entries = entry::Root::find_all(
+    &db,
+    Some(entry::Query { account_id: HashSet::from_iter([account_id]), ..Default::default() }),
+    None,
+    None,
+  )
+  .await?;
+
+  account::Root::handle(
+    &db,
+    &Actor::system("dave"),
+    account::Command::Delete(account::CommandDelete {
+      id: HashSet::from_iter([account_id]),
+      expected_version: HashMap::default(),
+    }),
+  )
+  .await?;
+  let histories = history::Root::find_all(
+    &db,
+    Some(history::Query { actor: HashSet::from_iter(["dave".to_string()]), ..Default::default() }),
+    None,
+    None,
+  )
+  .await?;
+  assert_eq!(entries.len() + 1, histories.len());
+  assert_eq!(1, histories.iter().map(|history| history.command_id).collect::<HashSet<_>>().len());
+
+  let deleted = histories.iter().find(|history| history.entity == account::TYPE).unwrap();
+  assert_eq!(account_id, deleted.entity_id);
+  assert!(deleted.after.is_none());
+  for entry in entries {
+    let history = histories.iter().find(|history| history.entity_id == entry.id).unwrap();
+    assert_eq!(entry::TYPE, history.entity);
+    assert_eq!(Some(entry), history.before.clone().map(serde_json::from_value).transpose()?);
+    let after: Option<entry::Root> =
+      history.after.clone().map(serde_json::from_value).transpose()?;
+    assert!(after.is_none_or(|after| after.items.iter().all(|item| item.account != account_id)));
+  }
+
+  Ok(())
+}
+
+#[tokio::test]
+pub async fn test_find_after() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+
+  let mut ids = Vec::new();
+  for name in ["First Journal", "Second Journal", "Third Journal"] {
+    let journal = journal::Root::handle(
+      &db,
+      &Actor::system("alice"),
+      journal::Command::Create(journal::CommandCreate {
+        name: name.to_string(),
+        description: String::default(),
+        unit: "USD".to_string(),
+        tags: HashSet::default(),
+      }),
+    )
+    .await?
+    .remove(0);
+    ids.push(journal.id);
+  }
+
+  let query = history::Query {
+    entity: HashSet::from_iter([journal::TYPE.to_string()]),
+    ..Default::default()
+  };
+  let histories = history::Root::find_after(&db, query.clone(), 0, Some(10)).await?;
+  assert_eq!(ids, histories.iter().map(|history| history.entity_id).collect::<Vec<_>>());
+  assert!(histories.iter().zip(&ids).all(|(history, id)| history.journal_id() == Some(*id)));
+  assert_eq!(vec![1, 2, 3], histories.iter().map(|history| history.sequence).collect::<Vec<_>>());
+  assert_eq!(3, history::Root::find_last_sequence(&db).await?);
+
+  let histories =
+    history::Root::find_after(&db, query.clone(), histories[0].sequence, Some(1)).await?;
+  assert_eq!(vec![ids[1]], histories.iter().map(|history| history.entity_id).collect::<Vec<_>>());
+  let histories =
+    history::Root::find_after(&db, query.clone(), histories[0].sequence, Some(10)).await?;
+  assert_eq!(vec![ids[2]], histories.iter().map(|history| history.entity_id).collect::<Vec<_>>());
+  assert!(history::Root::find_after(&db, query, histories[0].sequence, Some(10)).await?.is_empty());
+
+  Ok(())
+}
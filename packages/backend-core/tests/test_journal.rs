@@ -24,6 +24,7 @@ pub async fn test_swap_name() -> anyhow::Result<()> {
         description: None,
         unit: "".to_string(),
         tags: None,
+        expected_version: None,
       },
       journal::CommandUpdate {
         id: journals[1].id,
@@ -31,6 +32,7 @@ pub async fn test_swap_name() -> anyhow::Result<()> {
         description: None,
         unit: "".to_string(),
         tags: None,
+        expected_version: None,
       },
     ],
   )
@@ -72,6 +74,7 @@ pub async fn test_swap_name2() -> anyhow::Result<()> {
         description: None,
         unit: "".to_string(),
         tags: None,
+        expected_version: None,
       },
       journal::CommandUpdate {
         id: journals[1].id,
@@ -79,6 +82,7 @@ pub async fn test_swap_name2() -> anyhow::Result<()> {
         description: None,
         unit: "".to_string(),
         tags: None,
+        expected_version: None,
       },
     ],
   )
@@ -106,6 +110,7 @@ pub async fn test_multi_update() -> anyhow::Result<()> {
         description: None,
         unit: "".to_string(),
         tags: None,
+        expected_version: None,
       },
       journal::CommandUpdate {
         id: journal.id,
@@ -113,6 +118,7 @@ pub async fn test_multi_update() -> anyhow::Result<()> {
         description: Some("New Description".to_string()),
         unit: "".to_string(),
         tags: None,
+        expected_version: None,
       },
     ],
   )
@@ -0,0 +1,112 @@
+use backend_core::actor::Actor;
+use backend_core::entity::{account, journal, membership, user, ReadRoot};
+use backend_core::Error;
+use std::collections::HashSet;
+use test_suite::create_user;
+
+fn rename(id: uuid::Uuid, name: &str) -> journal::Command {
+  journal::Command::Update(journal::CommandUpdate {
+    id,
+    name: name.to_string(),
+    description: None,
+    unit: String::default(),
+    tags: None,
+    expected_version: None,
+  })
+}
+
+#[tokio::test]
+pub async fn test_membership() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+
+  let alice = create_user(&db, "Alice").await?;
+  let bob = create_user(&db, "Bob").await?;
+  let Actor::User(bob_id) = bob else { unreachable!() };
+
+  let result = user::Root::handle(
+    &db,
+    &alice,
+    user::Command::Create(user::CommandCreate { name: "Carol".to_string() }),
+  )
+  .await;
+  assert!(matches!(result, Err(Error::Forbidden(_))));
+
+  let journal = journal::Root::handle(
+    &db,
+    &alice,
+    journal::Command::Create(journal::CommandCreate {
+      name: "Shared Journal".to_string(),
+      description: String::default(),
+      unit: "USD".to_string(),
+      tags: HashSet::default(),
+    }),
+  )
+  .await?
+  .remove(0);
+  let query = journal::Query { id: HashSet::from_iter([journal.id]), ..Default::default() };
+
+  assert!(journal::Root::find_one_as(&db, &alice, Some(query.clone())).await?.is_some());
+  assert!(journal::Root::find_all_as(&db, &bob, None, None, None).await?.is_empty());
+  let result = journal::Root::handle(&db, &bob, rename(journal.id, "Bob's Journal")).await;
+  assert!(matches!(result, Err(Error::Forbidden(_))));
+
+  let grant = |role| {
+    membership::Command::Grant(membership::CommandGrant {
+      journal_id: journal.id,
+      user_id: bob_id,
+      role,
+    })
+  };
+  let result = membership::Root::handle(&db, &bob, grant(membership::Role::Owner)).await;
+  assert!(matches!(result, Err(Error::Forbidden(_))));
+
+  membership::Root::handle(&db, &alice, grant(membership::Role::Viewer)).await?;
+  assert!(journal::Root::find_one_as(&db, &bob, Some(query)).await?.is_some());
+  let result = account::Root::handle(
+    &db,
+    &bob,
+    account::Command::Create(account::CommandCreate {
+      journal_id: journal.id,
+      name: "Assets::Cash".to_string(),
+      description: String::default(),
+      unit: "USD".to_string(),
+      typ: account::Type::Asset,
+      tags: HashSet::default(),
+    }),
+  )
+  .await;
+  assert!(matches!(result, Err(Error::Forbidden(_))));
+
+  membership::Root::handle(&db, &alice, grant(membership::Role::Editor)).await?;
+  journal::Root::handle(&db, &bob, rename(journal.id, "Bob's Journal")).await?;
+  let delete = journal::Command::Delete(journal::CommandDelete {
+    id: HashSet::from_iter([journal.id]),
+    expected_version: Default::default(),
+  });
+  let result = journal::Root::handle(&db, &bob, delete.clone()).await;
+  assert!(matches!(result, Err(Error::Forbidden(_))));
+
+  let Actor::User(alice_id) = alice else { unreachable!() };
+  let result = membership::Root::handle(
+    &db,
+    &alice,
+    membership::Command::Revoke(membership::CommandRevoke {
+      journal_id: journal.id,
+      user_id: alice_id,
+    }),
+  )
+  .await;
+  assert!(matches!(result, Err(Error::RequiredField(_))));
+
+  journal::Root::handle(&db, &alice, delete).await?;
+  let memberships = membership::Root::find_all(
+    &db,
+    Some(membership::Query { journal_id: HashSet::from_iter([journal.id]), ..Default::default() }),
+    None,
+    None,
+  )
+  .await?;
+  assert!(memberships.is_empty());
+
+  Ok(())
+}
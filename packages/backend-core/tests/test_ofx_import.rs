@@ -0,0 +1,76 @@
+use backend_core::entity::{account, entry, imported_transaction, ReadRoot};
+use backend_core::import;
+use chrono::NaiveDate;
+use rust_decimal_macros::dec;
+use sea_orm::EntityTrait;
+use std::collections::HashMap;
+
+const CONTENT: &str = r#"OFXHEADER:100
+DATA:OFXSGML
+VERSION:102
+
+<OFX>
+<BANKMSGSRSV1><STMTTRNRS><STMTRS>
+<BANKTRANLIST>
+<STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20240106<TRNAMT>-3.50<FITID>0001<NAME>Coffee Shop</STMTTRN>
+<STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20240105<TRNAMT>-4.50<FITID>0002<NAME>Coffee Shop</STMTTRN>
+</BANKTRANLIST>
+<LEDGERBAL><BALAMT>-100<DTASOF>20240131</LEDGERBAL>
+</STMTRS></STMTTRNRS></BANKMSGSRSV1>
+</OFX>
+"#;
+
+#[tokio::test]
+pub async fn test_ofx_import() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  // The card statement, with the outflows to the expenses and the balance owed negative
+  let accounts = account::Root::find_all(&db, None, None, None).await?;
+  let account = accounts.iter().find(|account| account.typ == account::Type::Liability).unwrap();
+  let counter_account = accounts
+    .iter()
+    .find(|other| other.journal_id == account.journal_id && other.typ == account::Type::Expense)
+    .unwrap();
+
+  let import = import::ofx::Import {
+    account_id: account.id,
+    counter_account_id: counter_account.id,
+    content: CONTENT.to_string(),
+    preview: false,
+  };
+  let result = import::ofx::import(&db, import.clone()).await?;
+  assert!(result.issues.is_empty());
+  assert_eq!(3, result.created.len());
+
+  let entries = entry::Root::find_all(
+    &db,
+    Some(entry::Query { id: result.created.iter().copied().collect(), ..Default::default() }),
+    None,
+    None,
+  )
+  .await?;
+  let check = entries.iter().find(|entry| entry.typ == entry::Type::Check).unwrap();
+  assert_eq!(account.id, check.items[0].account);
+  assert_eq!(dec!(100), check.items[0].amount);
+
+  let external_ids: HashMap<_, _> = imported_transaction::Entity::find()
+    .all(&db)
+    .await?
+    .into_iter()
+    .map(|model| (model.external_id, model.entry_id))
+    .collect();
+  let date = |external_id: &str| {
+    entries.iter().find(|entry| entry.id == external_ids[external_id]).map(|entry| entry.date)
+  };
+  assert_eq!(NaiveDate::from_ymd_opt(2024, 1, 6), date("0001"));
+  assert_eq!(NaiveDate::from_ymd_opt(2024, 1, 5), date("0002"));
+  assert_eq!(NaiveDate::from_ymd_opt(2024, 1, 31), date("LEDGERBAL:2024-01-31"));
+  let record = entries.iter().find(|entry| entry.id == external_ids["0002"]).unwrap();
+  assert!(record.items.iter().all(|item| item.amount == dec!(4.50)));
+
+  let result = import::ofx::import(&db, import).await?;
+  assert!(result.created.is_empty());
+  assert!(result.commands.is_empty());
+  assert_eq!(vec![8, 9, 11], result.skipped.iter().map(|issue| issue.line).collect::<Vec<_>>());
+
+  Ok(())
+}
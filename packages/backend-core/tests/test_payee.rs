@@ -0,0 +1,132 @@
+use backend_core::actor::Actor;
+use backend_core::entity::{account, entry, payee, payee_report, ReadRoot};
+use rust_decimal::Decimal;
+use std::collections::HashSet;
+
+#[tokio::test]
+pub async fn test_payee() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  let entry = entry::Root::find_one(
+    &db,
+    Some(entry::Query { typ: Some(entry::Type::Record), ..Default::default() }),
+  )
+  .await?
+  .unwrap();
+
+  let payee = payee::Root::handle(
+    &db,
+    &Actor::system("tester"),
+    payee::Command::Create(payee::CommandCreate {
+      journal_id: entry.journal_id,
+      name: "Coffee Shop".to_string(),
+      aliases: HashSet::from_iter([" COFFEE SHOP #42 ".to_string(), "Coffee Shop".to_string()]),
+      default_account_id: None,
+      tags: HashSet::from_iter(["food".to_string()]),
+    }),
+  )
+  .await?
+  .remove(0);
+  assert_eq!(HashSet::from_iter(["COFFEE SHOP #42".to_string()]), payee.aliases);
+
+  let found = payee::Root::find_one(
+    &db,
+    Some(payee::Query {
+      journal_id: HashSet::from_iter([entry.journal_id]),
+      name: HashSet::from_iter(["COFFEE SHOP #42".to_string()]),
+      ..Default::default()
+    }),
+  )
+  .await?;
+  assert_eq!(Some(payee.id), found.map(|found| found.id));
+
+  let result = payee::Root::handle(
+    &db,
+    &Actor::system("tester"),
+    payee::Command::Create(payee::CommandCreate {
+      journal_id: entry.journal_id,
+      name: "Another Shop".to_string(),
+      aliases: HashSet::from_iter(["COFFEE SHOP #42".to_string()]),
+      default_account_id: None,
+      tags: HashSet::default(),
+    }),
+  )
+  .await;
+  assert!(matches!(result, Err(backend_core::Error::ExistingEntity(_))));
+
+  entry::Root::handle(
+    &db,
+    &Actor::system("tester"),
+    entry::Command::Update(entry::CommandUpdate {
+      id: entry.id,
+      name: String::default(),
+      description: None,
+      typ: None,
+      date: None,
+      tags: None,
+      items: Vec::default(),
+      payee_id: Some(Some(payee.id)),
+      expected_version: None,
+    }),
+  )
+  .await?;
+  let entries = entry::Root::find_all(
+    &db,
+    Some(entry::Query { payee_id: HashSet::from_iter([payee.id]), ..Default::default() }),
+    None,
+    None,
+  )
+  .await?;
+  assert_eq!(vec![entry.id], entries.iter().map(|entry| entry.id).collect::<Vec<_>>());
+
+  let debits: HashSet<_> = account::Root::find_all(
+    &db,
+    Some(account::Query {
+      journal_id: HashSet::from_iter([entry.journal_id]),
+      ..Default::default()
+    }),
+    None,
+    None,
+  )
+  .await?
+  .into_iter()
+  .filter(|account| account.typ.is_debit())
+  .map(|account| account.id)
+  .collect();
+  let report = payee_report::Root::find_one(
+    &db,
+    Some(payee_report::Query {
+      journal_id: HashSet::from_iter([entry.journal_id]),
+      payee_id: HashSet::from_iter([payee.id]),
+      ..Default::default()
+    }),
+  )
+  .await?
+  .unwrap();
+  assert_eq!(1, report.items.len());
+  assert_eq!(1, report.items[0].count);
+  assert_eq!(
+    entry
+      .items
+      .iter()
+      .filter(|item| debits.contains(&item.account))
+      .map(|item| item.amount * item.price)
+      .sum::<Decimal>(),
+    report.items[0].total
+  );
+
+  payee::Root::handle(
+    &db,
+    &Actor::system("tester"),
+    payee::Command::Delete(payee::CommandDelete { id: HashSet::from_iter([payee.id]) }),
+  )
+  .await?;
+  let saved = entry::Root::find_one(
+    &db,
+    Some(entry::Query { id: HashSet::from_iter([entry.id]), ..Default::default() }),
+  )
+  .await?
+  .unwrap();
+  assert_eq!(None, saved.payee_id);
+
+  Ok(())
+}
@@ -0,0 +1,83 @@
+use backend_core::entity::{account, entry, journal, ReadRoot};
+use backend_core::export;
+use backend_core::import;
+use std::collections::HashSet;
+
+#[tokio::test]
+pub async fn test_beancount_round_trip() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  let source = journal::Root::find_one(&db, None).await?.unwrap();
+
+  let content = export::plain_text::export(
+    &db,
+    export::plain_text::Export {
+      journal_id: source.id,
+      format: export::plain_text::Format::Beancount,
+    },
+  )
+  .await?;
+
+  let target = journal::Root::create(
+    &db,
+    vec![journal::CommandCreate {
+      name: "Round Trip Journal".to_string(),
+      description: String::default(),
+      unit: source.unit.clone(),
+      tags: HashSet::default(),
+    }],
+  )
+  .await?
+  .remove(0);
+  let result = import::plain_text::import(
+    &db,
+    import::plain_text::Import {
+      journal_id: target.id,
+      format: import::plain_text::Format::Beancount,
+      content,
+      preview: false,
+    },
+  )
+  .await?;
+  assert!(result.entries.issues.is_empty(), "{:?}", result.entries.issues);
+  assert_eq!(vec![1, 2], result.entries.skipped.iter().map(|issue| issue.line).collect::<Vec<_>>());
+
+  let query = |journal_id| account::Query {
+    journal_id: HashSet::from_iter([journal_id]),
+    ..Default::default()
+  };
+  let accounts = account::Root::find_all(&db, Some(query(source.id)), None, None).await?;
+  assert_eq!(accounts.len(), result.created_accounts.len());
+
+  let entries = entry::Root::find_all(
+    &db,
+    Some(entry::Query { journal_id: HashSet::from_iter([source.id]), ..Default::default() }),
+    None,
+    None,
+  )
+  .await?;
+  let expected: usize = entries
+    .iter()
+    .map(|entry| if entry.typ == entry::Type::Record { 1 } else { entry.items.len() })
+    .sum();
+  assert_eq!(expected, result.entries.created.len());
+
+  let checks = entry::Root::find_all(
+    &db,
+    Some(entry::Query {
+      journal_id: HashSet::from_iter([target.id]),
+      typ: Some(entry::Type::Check),
+      ..Default::default()
+    }),
+    None,
+    None,
+  )
+  .await?;
+  let dates: HashSet<_> = entries
+    .iter()
+    .filter(|entry| entry.typ == entry::Type::Check)
+    .map(|entry| entry.date)
+    .collect();
+  assert!(checks.iter().all(|check| dates.contains(&check.date)));
+
+  Ok(())
+}
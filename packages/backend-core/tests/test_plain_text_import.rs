@@ -0,0 +1,143 @@
+use backend_core::entity::{account, entry, journal, ReadRoot};
+use backend_core::import::plain_text::{self, Format};
+use chrono::NaiveDate;
+use rust_decimal_macros::dec;
+use std::collections::HashSet;
+
+const BEANCOUNT: &str = r#"option "operating_currency" "USD"
+
+2024-01-01 open Assets:Checking USD
+2024-01-01 open Income:Salary USD
+2024-01-05 * "Employer" "January Salary" #salary
+  Assets:Checking    1000.00 USD
+  Income:Salary
+2024-01-31 balance Assets:Checking  1000.00 USD
+2024-02-01 price EUR 1.10 USD
+"#;
+
+#[tokio::test]
+pub async fn test_beancount_import() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  let journal = journal::Root::find_one(&db, None).await?.unwrap();
+
+  let import = plain_text::Import {
+    journal_id: journal.id,
+    format: Format::Beancount,
+    content: BEANCOUNT.to_string(),
+    preview: true,
+  };
+  let preview = plain_text::import(&db, import.clone()).await?;
+  assert!(preview.entries.issues.is_empty(), "{:?}", preview.entries.issues);
+  assert_eq!(2, preview.accounts.len());
+  assert_eq!(2, preview.entries.commands.len());
+  assert_eq!(
+    vec![1, 9],
+    preview.entries.skipped.iter().map(|issue| issue.line).collect::<Vec<_>>()
+  );
+  assert!(preview.created_accounts.is_empty());
+
+  let result = plain_text::import(&db, plain_text::Import { preview: false, ..import }).await?;
+  assert_eq!(2, result.created_accounts.len());
+  assert_eq!(2, result.entries.created.len());
+
+  let accounts = account::Root::find_all(
+    &db,
+    Some(account::Query {
+      id: result.created_accounts.iter().copied().collect(),
+      ..Default::default()
+    }),
+    None,
+    None,
+  )
+  .await?;
+  let checking = accounts.iter().find(|account| account.name == "Assets::Checking").unwrap();
+  assert_eq!(account::Type::Asset, checking.typ);
+  assert_eq!("USD", checking.unit);
+
+  let entries = entry::Root::find_all(
+    &db,
+    Some(entry::Query {
+      id: result.entries.created.iter().copied().collect(),
+      ..Default::default()
+    }),
+    None,
+    None,
+  )
+  .await?;
+  let record = entries.iter().find(|entry| entry.typ == entry::Type::Record).unwrap();
+  assert_eq!(HashSet::from_iter(["salary".to_string()]), record.tags);
+  assert!(record.items.iter().all(|item| item.amount == dec!(1000)));
+  let check = entries.iter().find(|entry| entry.typ == entry::Type::Check).unwrap();
+  assert_eq!(NaiveDate::from_ymd_opt(2024, 1, 30).unwrap(), check.date);
+
+  Ok(())
+}
+
+#[tokio::test]
+pub async fn test_beancount_import_signs() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  let journal = journal::Root::find_one(&db, None).await?.unwrap();
+
+  let content = r#"2024-01-01 open Assets:Checking USD
+2024-01-01 open Liabilities:Card USD
+2024-01-01 open Expenses:Food USD
+2024-01-05 * "Store" "Groceries"
+  Expenses:Food    50.00 USD
+  Liabilities:Card
+2024-01-31 balance Liabilities:Card  -50.00 USD
+2024-01-31 balance Assets:Checking  -10.00 USD
+"#;
+  let result = plain_text::import(
+    &db,
+    plain_text::Import {
+      journal_id: journal.id,
+      format: Format::Beancount,
+      content: content.to_string(),
+      preview: true,
+    },
+  )
+  .await?;
+
+  // The overdraft cannot be asserted by the unsigned Check entries
+  assert_eq!(vec![8], result.entries.issues.iter().map(|issue| issue.line).collect::<Vec<_>>());
+  assert_eq!(2, result.entries.commands.len());
+  let record = &result.entries.commands[0];
+  assert!(record.items.iter().all(|item| item.amount == dec!(50)));
+  let check = &result.entries.commands[1];
+  assert_eq!(entry::Type::Check, check.typ);
+  assert_eq!(NaiveDate::from_ymd_opt(2024, 1, 30).unwrap(), check.date);
+  assert_eq!(dec!(50), check.items[0].amount);
+
+  Ok(())
+}
+
+#[tokio::test]
+pub async fn test_ledger_import() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  let journal = journal::Root::find_one(&db, None).await?.unwrap();
+
+  let content = r#"2024/01/05 * Grocery Store
+    Expenses:Food    50 EUR
+    Liabilities:Credit Card
+"#;
+  let result = plain_text::import(
+    &db,
+    plain_text::Import {
+      journal_id: journal.id,
+      format: Format::Ledger,
+      content: content.to_string(),
+      preview: false,
+    },
+  )
+  .await?;
+  assert!(result.entries.issues.is_empty(), "{:?}", result.entries.issues);
+  assert_eq!(
+    vec!["Expenses::Food", "Liabilities::Credit Card"],
+    result.accounts.iter().map(|account| account.name.as_str()).collect::<Vec<_>>()
+  );
+  assert_eq!("EUR", result.accounts[0].unit);
+  assert_eq!(journal.unit, result.accounts[1].unit);
+  assert_eq!(1, result.entries.created.len());
+
+  Ok(())
+}
@@ -0,0 +1,128 @@
+use backend_core::entity::{account, entry, ReadRoot};
+use backend_core::import::qif;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+const CONTENT: &str = r#"!Type:CCard
+D01/05/2024
+T-50.00
+PGrocery Store
+LFood:Groceries
+^
+D01/06/2024
+T-100.00
+PSupermarket
+SFood:Groceries
+$-60.00
+SHousehold
+$-40.00
+^
+D01/07/2024
+T-30.00
+PSupermarket
+SFood:Groceries
+$-40.00
+SCashback
+$10.00
+^
+D01/08/2024
+T-5.00
+PBakery
+SFood:Groceries
+$-8.00
+SFood:Groceries
+$3.00
+^
+!Type:Memorized
+KC
+T-10.00
+^
+"#;
+
+#[tokio::test]
+pub async fn test_qif_import() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  // The register of a credit card, whose outflows are on the credit side
+  let register = account::Root::find_all(&db, None, None, None)
+    .await?
+    .into_iter()
+    .find(|account| account.typ == account::Type::Liability)
+    .unwrap();
+
+  let import = qif::Import {
+    account_id: register.id,
+    content: CONTENT.to_string(),
+    create_accounts: false,
+    day_first: false,
+    preview: false,
+  };
+  let result = qif::import(&db, import.clone()).await?;
+  assert_eq!(
+    vec![2, 7, 7, 15, 15, 23, 23],
+    result.entries.issues.iter().map(|issue| issue.line).collect::<Vec<_>>()
+  );
+  assert_eq!(vec![31], result.entries.skipped.iter().map(|issue| issue.line).collect::<Vec<_>>());
+  assert!(result.entries.created.is_empty());
+
+  let result = qif::import(&db, qif::Import { create_accounts: true, ..import }).await?;
+  assert!(result.entries.issues.is_empty(), "{:?}", result.entries.issues);
+  assert_eq!(3, result.created_accounts.len());
+  assert_eq!(4, result.entries.created.len());
+
+  let accounts = account::Root::find_all(
+    &db,
+    Some(account::Query {
+      id: result.created_accounts.iter().copied().collect(),
+      ..Default::default()
+    }),
+    None,
+    None,
+  )
+  .await?;
+  let find = |name: &str| accounts.iter().find(|account| account.name == name).unwrap();
+  let groceries = find("Food::Groceries");
+  assert_eq!(account::Type::Expense, groceries.typ);
+  assert_eq!(register.unit, groceries.unit);
+  assert_eq!(account::Type::Income, find("Cashback").typ);
+
+  let entries = entry::Root::find_all(
+    &db,
+    Some(entry::Query {
+      id: result.entries.created.iter().copied().collect(),
+      ..Default::default()
+    }),
+    None,
+    None,
+  )
+  .await?;
+  let amounts = |day: u32| -> HashMap<Uuid, Decimal> {
+    let date = NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+    let entry = entries.iter().find(|entry| entry.date == date).unwrap();
+    entry.items.iter().map(|item| (item.account, item.amount)).collect()
+  };
+  assert_eq!(
+    accounts
+      .iter()
+      .filter(|account| account.name != "Cashback")
+      .map(|account| account.id)
+      .chain([register.id])
+      .collect::<HashSet<_>>(),
+    amounts(6).into_keys().collect::<HashSet<_>>()
+  );
+  // The splits of the different signs are on the different sides
+  assert_eq!(
+    HashMap::from_iter([
+      (register.id, dec!(30)),
+      (groceries.id, dec!(40)),
+      (find("Cashback").id, dec!(10))
+    ]),
+    amounts(7)
+  );
+  // The splits to the same account are netted
+  assert_eq!(HashMap::from_iter([(register.id, dec!(5)), (groceries.id, dec!(5))]), amounts(8));
+
+  Ok(())
+}
@@ -0,0 +1,110 @@
+use backend_core::actor::Actor;
+use backend_core::entity::{entry, reconciliation, Presentation, ReadRoot};
+use backend_core::Error;
+use std::collections::{HashMap, HashSet};
+
+#[tokio::test]
+pub async fn test_reconcile() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  let record = entry::Root::find_one(
+    &db,
+    Some(entry::Query { typ: Some(entry::Type::Record), ..Default::default() }),
+  )
+  .await?
+  .unwrap();
+  let item = record.items[0];
+
+  let proposal = reconciliation::Root::propose(
+    &db,
+    reconciliation::Statement {
+      account_id: item.account,
+      date: record.date,
+      balance: item.amount * item.price,
+      lines: vec![reconciliation::StatementLine {
+        date: record.date,
+        amount: item.amount * item.price,
+        description: "".to_string(),
+      }],
+      window_days: None,
+    },
+  )
+  .await?;
+  assert_eq!(vec![reconciliation::Match { line: 0, entry_id: record.id }], proposal.matches);
+  assert!(!proposal.unmatched_entries.contains(&record.id));
+
+  let command = reconciliation::CommandCreate {
+    account_id: item.account,
+    date: record.date,
+    balance: proposal.actual_balance,
+    entries: HashSet::from_iter([record.id]),
+  };
+  let roots = reconciliation::Root::handle(
+    &db,
+    &Actor::system("tester"),
+    reconciliation::Command::Create(command.clone()),
+  )
+  .await?;
+  assert_eq!(1, roots.len());
+  let root = &roots[0];
+
+  let check = entry::Root::find_all(
+    &db,
+    Some(entry::Query { id: HashSet::from_iter([root.check_id]), ..Default::default() }),
+    None,
+    None,
+  )
+  .await?;
+  match &entry::Presentation::from_roots(&db, check).await?[..] {
+    [entry::Presentation::Check(check)] => {
+      assert_eq!("Valid", serde_json::to_value(check.state[&item.account])?["type"])
+    }
+    _ => panic!("The Check entry of the reconciliation is not found"),
+  }
+
+  let reconciled = entry::Root::find_all(
+    &db,
+    Some(entry::Query { reconciled: Some(true), ..Default::default() }),
+    None,
+    None,
+  )
+  .await?;
+  assert_eq!(vec![record.id], reconciled.iter().map(|entry| entry.id).collect::<Vec<_>>());
+
+  let result = reconciliation::Root::handle(
+    &db,
+    &Actor::system("tester"),
+    reconciliation::Command::Create(command.clone()),
+  )
+  .await;
+  assert!(matches!(result, Err(Error::ExistingEntity(_))));
+
+  let delete_record = entry::Command::Delete(entry::CommandDelete {
+    id: HashSet::from_iter([record.id]),
+    expected_version: HashMap::default(),
+  });
+  let result = entry::Root::handle(&db, &Actor::system("tester"), delete_record.clone()).await;
+  assert!(matches!(result, Err(Error::ExistingEntity(_))));
+
+  reconciliation::Root::handle(
+    &db,
+    &Actor::system("tester"),
+    reconciliation::Command::Delete(reconciliation::CommandDelete {
+      id: HashSet::from_iter([root.id]),
+    }),
+  )
+  .await?;
+  assert!(reconciliation::Root::find_one(&db, None).await?.is_none());
+  assert!(entry::Root::find_one(
+    &db,
+    Some(entry::Query { id: HashSet::from_iter([root.check_id]), ..Default::default() })
+  )
+  .await?
+  .is_none());
+
+  let result = reconciliation::Root::create(&db, vec![command.clone(), command]).await;
+  assert!(matches!(result, Err(Error::ExistingEntity(_))));
+
+  entry::Root::handle(&db, &Actor::system("tester"), delete_record).await?;
+
+  Ok(())
+}
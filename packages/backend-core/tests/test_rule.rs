@@ -0,0 +1,264 @@
+use backend_core::actor::Actor;
+use backend_core::entity::{
+  account, entry, history, reconciliation, rule, ReadRoot, MAX_TAGS_LENGTH,
+};
+use chrono::NaiveDate;
+use rust_decimal_macros::dec;
+use std::collections::HashSet;
+
+#[tokio::test]
+pub async fn test_rule_apply() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  let entry = entry::Root::find_all(
+    &db,
+    Some(entry::Query { typ: Some(entry::Type::Record), ..Default::default() }),
+    None,
+    None,
+  )
+  .await?
+  .into_iter()
+  .find(|entry| entry.items.len() >= 2 && entry.tags.len() < MAX_TAGS_LENGTH)
+  .unwrap();
+  let source = entry.items[0];
+  let counter_account = account::Root::find_all(
+    &db,
+    Some(account::Query {
+      journal_id: HashSet::from_iter([entry.journal_id]),
+      ..Default::default()
+    }),
+    None,
+    None,
+  )
+  .await?
+  .into_iter()
+  .find(|account| entry.items.iter().all(|item| item.account != account.id))
+  .unwrap();
+
+  let rule = rule::Root::handle(
+    &db,
+    &Actor::system("tester"),
+    rule::Command::Create(rule::CommandCreate {
+      journal_id: entry.journal_id,
+      name: "Categorize Entry".to_string(),
+      conditions: rule::Conditions {
+        name: Some(entry.name.to_uppercase()),
+        min_amount: Some(source.amount),
+        account_id: Some(source.account),
+        ..Default::default()
+      },
+      actions: rule::Actions {
+        counter_account_id: Some(counter_account.id),
+        tags: HashSet::from_iter(["ruled".to_string()]),
+        ..Default::default()
+      },
+    }),
+  )
+  .await?
+  .remove(0);
+  assert_eq!(HashSet::from_iter(["ruled".to_string()]), rule.actions.tags);
+
+  let command = rule::CommandApply {
+    journal_id: entry.journal_id,
+    rule_id: HashSet::default(),
+    query: entry::Query { id: HashSet::from_iter([entry.id]), ..Default::default() },
+    preview: true,
+  };
+  let changes = rule::Root::apply(&db, &Actor::system("tester"), command.clone()).await?;
+  assert_eq!(1, changes.len());
+  assert_eq!(vec![rule.id], changes[0].rule_ids);
+  assert_eq!(entry, changes[0].before);
+  let saved = entry::Root::find_one(
+    &db,
+    Some(entry::Query { id: HashSet::from_iter([entry.id]), ..Default::default() }),
+  )
+  .await?
+  .unwrap();
+  assert!(!saved.tags.contains("ruled"));
+
+  rule::Root::apply(
+    &db,
+    &Actor::system("tester"),
+    rule::CommandApply { preview: false, ..command.clone() },
+  )
+  .await?;
+  let saved = entry::Root::find_one(
+    &db,
+    Some(entry::Query { id: HashSet::from_iter([entry.id]), ..Default::default() }),
+  )
+  .await?
+  .unwrap();
+  assert!(saved.tags.contains("ruled"));
+  assert_eq!(
+    HashSet::from_iter([source.account, counter_account.id]),
+    saved.items.iter().map(|item| item.account).collect::<HashSet<_>>()
+  );
+
+  assert!(rule::Root::apply(&db, &Actor::system("tester"), command).await?.is_empty());
+
+  let result = rule::Root::handle(
+    &db,
+    &Actor::system("tester"),
+    rule::Command::Create(rule::CommandCreate {
+      journal_id: entry.journal_id,
+      name: "Invalid Rule".to_string(),
+      conditions: rule::Conditions::default(),
+      actions: rule::Actions { rename: Some("Renamed Entry".to_string()), ..Default::default() },
+    }),
+  )
+  .await;
+  assert!(matches!(result, Err(backend_core::Error::RequiredField(_))));
+
+  Ok(())
+}
+
+#[tokio::test]
+pub async fn test_rule_rename() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  let account = account::Root::find_one(&db, None).await?.unwrap();
+  let commands = ["Coffee Shop Mon", "Coffee Shop Tue"].map(|name| entry::CommandCreate {
+    journal_id: account.journal_id,
+    name: name.to_string(),
+    description: String::default(),
+    typ: entry::Type::Record,
+    date: NaiveDate::from_ymd_opt(2024, 1, 5).unwrap(),
+    tags: HashSet::default(),
+    items: vec![entry::Item { account: account.id, amount: dec!(3.5), price: dec!(1) }],
+    payee_id: None,
+  });
+  let entries = entry::Root::create(&db, commands.to_vec()).await?;
+
+  let rule = rule::Root::handle(
+    &db,
+    &Actor::system("tester"),
+    rule::Command::Create(rule::CommandCreate {
+      journal_id: account.journal_id,
+      name: "Rename Coffee".to_string(),
+      conditions: rule::Conditions { name: Some("coffee".to_string()), ..Default::default() },
+      actions: rule::Actions { rename: Some("Coffee".to_string()), ..Default::default() },
+    }),
+  )
+  .await?
+  .remove(0);
+
+  let command = rule::CommandApply {
+    journal_id: account.journal_id,
+    rule_id: HashSet::from_iter([rule.id]),
+    query: entry::Query {
+      id: entries.iter().map(|entry| entry.id).collect(),
+      ..Default::default()
+    },
+    preview: false,
+  };
+  let changes = rule::Root::apply(&db, &Actor::system("tester"), command.clone()).await?;
+  assert_eq!(
+    HashSet::from_iter(["Coffee", "Coffee #2"]),
+    changes.iter().map(|change| change.after.name.as_str()).collect::<HashSet<_>>()
+  );
+
+  // The names are kept when applied again, since the entries are named by the rule already
+  let saved = entry::Root::find_all(
+    &db,
+    Some(entry::Query { id: command.query.id.clone(), ..Default::default() }),
+    None,
+    None,
+  )
+  .await?;
+  assert_eq!(
+    HashSet::from_iter(["Coffee", "Coffee #2"]),
+    saved.iter().map(|entry| entry.name.as_str()).collect::<HashSet<_>>()
+  );
+  assert!(rule::Root::apply(&db, &Actor::system("tester"), command).await?.is_empty());
+
+  Ok(())
+}
+
+#[tokio::test]
+pub async fn test_rule_reconciled() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  let account = account::Root::find_one(&db, None).await?.unwrap();
+  let date = NaiveDate::from_ymd_opt(2024, 1, 5).unwrap();
+  let commands = ["Coffee Shop Mon", "Coffee Shop Tue"].map(|name| entry::CommandCreate {
+    journal_id: account.journal_id,
+    name: name.to_string(),
+    description: String::default(),
+    typ: entry::Type::Record,
+    date,
+    tags: HashSet::default(),
+    items: vec![entry::Item { account: account.id, amount: dec!(3.5), price: dec!(1) }],
+    payee_id: None,
+  });
+  let entries = entry::Root::create(&db, commands.to_vec()).await?;
+  let (reconciled, unreconciled) = (&entries[0], &entries[1]);
+  let reconciliation = reconciliation::Root::handle(
+    &db,
+    &Actor::system("tester"),
+    reconciliation::Command::Create(reconciliation::CommandCreate {
+      account_id: account.id,
+      date,
+      balance: dec!(3.5),
+      entries: HashSet::from_iter([reconciled.id]),
+    }),
+  )
+  .await?
+  .remove(0);
+
+  let rule = rule::Root::handle(
+    &db,
+    &Actor::system("tester"),
+    rule::Command::Create(rule::CommandCreate {
+      journal_id: account.journal_id,
+      name: "Rename Coffee".to_string(),
+      conditions: rule::Conditions { name: Some("coffee shop".to_string()), ..Default::default() },
+      actions: rule::Actions { rename: Some("Coffee".to_string()), ..Default::default() },
+    }),
+  )
+  .await?
+  .remove(0);
+  let query =
+    entry::Query { id: entries.iter().map(|entry| entry.id).collect(), ..Default::default() };
+  let changes = rule::Root::apply(
+    &db,
+    &Actor::system("tester"),
+    rule::CommandApply {
+      journal_id: account.journal_id,
+      rule_id: HashSet::from_iter([rule.id]),
+      query: query.clone(),
+      preview: false,
+    },
+  )
+  .await?;
+  assert_eq!(
+    HashSet::from_iter([(reconciled.id, Some(reconciliation.id)), (unreconciled.id, None)]),
+    changes
+      .iter()
+      .map(|change| (change.before.id, change.reconciliation_id))
+      .collect::<HashSet<_>>()
+  );
+
+  // The reconciled entries are kept, and the others saved are recorded in the history
+  let saved = entry::Root::find_all(&db, Some(query), None, None).await?;
+  assert_eq!(
+    HashSet::from_iter([(reconciled.id, "Coffee Shop Mon"), (unreconciled.id, "Coffee")]),
+    saved.iter().map(|entry| (entry.id, entry.name.as_str())).collect::<HashSet<_>>()
+  );
+  let histories = history::Root::find_all(
+    &db,
+    Some(history::Query {
+      entity_id: entries.iter().map(|entry| entry.id).collect(),
+      ..Default::default()
+    }),
+    None,
+    None,
+  )
+  .await?;
+  assert_eq!(
+    vec![(unreconciled.id, "entries:batch")],
+    histories
+      .iter()
+      .map(|history| (history.entity_id, history.command["commandType"].as_str().unwrap()))
+      .collect::<Vec<_>>()
+  );
+  assert_eq!(Some("Coffee"), histories[0].after.as_ref().and_then(|after| after["name"].as_str()));
+
+  Ok(())
+}
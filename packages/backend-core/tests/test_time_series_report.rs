@@ -0,0 +1,37 @@
+use backend_core::entity::{journal, time_series_report, ReadRoot};
+use std::collections::HashSet;
+use test_suite::create_user;
+
+#[tokio::test]
+pub async fn test_find_all_as() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  let alice = create_user(&db, "Alice").await?;
+  journal::Root::handle(
+    &db,
+    &alice,
+    journal::Command::Create(journal::CommandCreate {
+      name: "Alice's Journal".to_string(),
+      description: String::default(),
+      unit: "USD".to_string(),
+      tags: HashSet::default(),
+    }),
+  )
+  .await?;
+
+  // The reports of the journals not allowed cannot be reached by the IDs
+  let foreign = time_series_report::Root::find_all(&db, None, None, None).await?;
+  assert!(!foreign.is_empty());
+  let query = time_series_report::Query {
+    id: foreign.iter().map(|report| report.id()).collect(),
+    ..Default::default()
+  };
+  assert_eq!(
+    foreign.len(),
+    time_series_report::Root::find_all(&db, Some(query.clone()), None, None).await?.len()
+  );
+  assert!(time_series_report::Root::find_all_as(&db, &alice, Some(query), None, None)
+    .await?
+    .is_empty());
+
+  Ok(())
+}
@@ -0,0 +1,62 @@
+use backend_core::actor::Actor;
+use backend_core::entity::{entry, journal, ReadRoot};
+use chrono::Utc;
+use std::collections::HashSet;
+
+#[tokio::test]
+pub async fn test_timestamp() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  let started_at = Utc::now();
+
+  let created = journal::Root::handle(
+    &db,
+    &Actor::system("tester"),
+    journal::Command::Create(journal::CommandCreate {
+      name: "Timestamped Journal".to_string(),
+      description: String::default(),
+      unit: "USD".to_string(),
+      tags: HashSet::default(),
+    }),
+  )
+  .await?
+  .remove(0);
+  assert!(created.created_at >= started_at);
+  assert_eq!(created.created_at, created.updated_at);
+
+  let updated = journal::Root::handle(
+    &db,
+    &Actor::system("tester"),
+    journal::Command::Update(journal::CommandUpdate {
+      id: created.id,
+      name: "Updated Journal".to_string(),
+      description: None,
+      unit: String::default(),
+      tags: None,
+      expected_version: None,
+    }),
+  )
+  .await?
+  .remove(0);
+  assert_eq!(created.created_at, updated.created_at);
+  assert!(updated.updated_at > created.updated_at);
+
+  let journals = journal::Root::find_all(
+    &db,
+    Some(journal::Query { updated_since: Some(started_at), ..Default::default() }),
+    None,
+    None,
+  )
+  .await?;
+  assert_eq!(vec![created.id], journals.iter().map(|journal| journal.id).collect::<Vec<_>>());
+
+  let entries = entry::Root::find_all(
+    &db,
+    Some(entry::Query { updated_since: Some(Utc::now()), ..Default::default() }),
+    None,
+    None,
+  )
+  .await?;
+  assert!(entries.is_empty());
+
+  Ok(())
+}
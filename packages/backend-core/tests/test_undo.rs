@@ -0,0 +1,203 @@
+use backend_core::actor::Actor;
+use backend_core::entity::{account, account_balance, entry, journal, payee, ReadRoot};
+use backend_core::undo;
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+
+fn rename(id: uuid::Uuid, name: &str) -> entry::Command {
+  entry::Command::Update(entry::CommandUpdate {
+    id,
+    name: name.to_string(),
+    description: None,
+    typ: None,
+    date: None,
+    tags: None,
+    items: Vec::default(),
+    payee_id: None,
+    expected_version: None,
+  })
+}
+
+async fn find_entry(
+  db: &impl sea_orm::ConnectionTrait,
+  id: uuid::Uuid,
+) -> anyhow::Result<Option<entry::Root>> {
+  Ok(
+    entry::Root::find_one(
+      db,
+      Some(entry::Query { id: HashSet::from_iter([id]), ..Default::default() }),
+    )
+    .await?,
+  )
+}
+
+#[tokio::test]
+pub async fn test_undo_redo() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  let mut session = undo::Session::default();
+  let entry = entry::Root::find_one(&db, None).await?.unwrap();
+
+  let (roots, inverse) = entry::Root::handle_with_inverse(
+    &db,
+    &Actor::system("tester"),
+    rename(entry.id, "Renamed Entry"),
+  )
+  .await?;
+  session.push(inverse);
+  let renamed = roots[0].clone();
+  assert_eq!("Renamed Entry", renamed.name);
+
+  assert!(session.undo(&db, &Actor::system("tester")).await?);
+  let undone = find_entry(&db, entry.id).await?.unwrap();
+  assert_eq!(
+    entry::Root { version: renamed.version + 1, updated_at: undone.updated_at, ..entry.clone() },
+    undone
+  );
+  assert!(!session.can_undo());
+
+  assert!(session.redo(&db, &Actor::system("tester")).await?);
+  let redone = find_entry(&db, entry.id).await?.unwrap();
+  assert_eq!(
+    entry::Root { version: renamed.version + 2, updated_at: redone.updated_at, ..renamed },
+    redone
+  );
+  assert!(!session.redo(&db, &Actor::system("tester")).await?);
+
+  entry::Root::handle(&db, &Actor::system("other"), rename(entry.id, "Changed Elsewhere")).await?;
+  let result = session.undo(&db, &Actor::system("tester")).await;
+  assert!(matches!(result, Err(backend_core::Error::Conflict(_))));
+  assert_eq!("Changed Elsewhere", find_entry(&db, entry.id).await?.unwrap().name);
+  assert!(session.can_undo());
+
+  Ok(())
+}
+
+#[tokio::test]
+pub async fn test_undo_cascaded() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  let mut session = undo::Session::default();
+  let entry = entry::Root::find_one(&db, None).await?.unwrap();
+  let account_id = entry.items[0].account;
+  let account = account::Root::find_one(
+    &db,
+    Some(account::Query { id: HashSet::from_iter([account_id]), ..Default::default() }),
+  )
+  .await?
+  .unwrap();
+  let entries = entry::Root::find_all(
+    &db,
+    Some(entry::Query { account_id: HashSet::from_iter([account_id]), ..Default::default() }),
+    None,
+    None,
+  )
+  .await?;
+
+  let (_, inverse) = account::Root::handle_with_inverse(
+    &db,
+    &Actor::system("tester"),
+    account::Command::Delete(account::CommandDelete {
+      id: HashSet::from_iter([account_id]),
+      expected_version: HashMap::default(),
+    }),
+  )
+  .await?;
+  session.push(inverse);
+  assert!(find_entry(&db, entry.id)
+    .await?
+    .is_none_or(|entry| entry.items.iter().all(|item| item.account != account_id)));
+
+  assert!(session.undo(&db, &Actor::system("tester")).await?);
+  let restored = account::Root::find_one(
+    &db,
+    Some(account::Query { id: HashSet::from_iter([account_id]), ..Default::default() }),
+  )
+  .await?
+  .unwrap();
+  assert_eq!(
+    account::Root { version: account.version + 1, updated_at: restored.updated_at, ..account },
+    restored
+  );
+  let balances =
+    account_balance::find_balances(&db, HashSet::from_iter([account_id]), None).await?;
+  assert_eq!(
+    entries
+      .iter()
+      .filter(|entry| entry.typ == entry::Type::Record)
+      .flat_map(|entry| &entry.items)
+      .filter(|item| item.account == account_id)
+      .map(|item| item.amount * item.price)
+      .sum::<Decimal>(),
+    balances.get(&account_id).copied().unwrap_or_default()
+  );
+  for entry in entries {
+    let mut restored = find_entry(&db, entry.id).await?.unwrap();
+    let mut expected =
+      entry::Root { version: entry.version + 1, updated_at: restored.updated_at, ..entry };
+    restored.items.sort_by_key(|item| item.account);
+    expected.items.sort_by_key(|item| item.account);
+    assert_eq!(expected, restored);
+  }
+
+  Ok(())
+}
+
+#[tokio::test]
+pub async fn test_undo_journal_payees() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  let mut session = undo::Session::default();
+  let entry = entry::Root::find_one(&db, None).await?.unwrap();
+  let payee = payee::Root::handle(
+    &db,
+    &Actor::system("tester"),
+    payee::Command::Create(payee::CommandCreate {
+      journal_id: entry.journal_id,
+      name: "Coffee Shop".to_string(),
+      aliases: HashSet::from_iter(["COFFEE SHOP #42".to_string()]),
+      default_account_id: Some(entry.items[0].account),
+      tags: HashSet::from_iter(["food".to_string()]),
+    }),
+  )
+  .await?
+  .remove(0);
+  let entry = entry::Root::handle(
+    &db,
+    &Actor::system("tester"),
+    entry::Command::Update(entry::CommandUpdate {
+      id: entry.id,
+      name: String::default(),
+      description: None,
+      typ: None,
+      date: None,
+      tags: None,
+      items: Vec::default(),
+      payee_id: Some(Some(payee.id)),
+      expected_version: None,
+    }),
+  )
+  .await?
+  .remove(0);
+
+  let (_, inverse) = journal::Root::handle_with_inverse(
+    &db,
+    &Actor::system("tester"),
+    journal::Command::Delete(journal::CommandDelete {
+      id: HashSet::from_iter([entry.journal_id]),
+      expected_version: HashMap::default(),
+    }),
+  )
+  .await?;
+  session.push(inverse);
+  assert_eq!(None, find_entry(&db, entry.id).await?);
+
+  assert!(session.undo(&db, &Actor::system("tester")).await?);
+  let restored = payee::Root::find_one(
+    &db,
+    Some(payee::Query { id: HashSet::from_iter([payee.id]), ..Default::default() }),
+  )
+  .await?;
+  assert_eq!(Some(payee.clone()), restored);
+  let restored = find_entry(&db, entry.id).await?.unwrap();
+  assert_eq!(Some(payee.id), restored.payee_id);
+
+  Ok(())
+}
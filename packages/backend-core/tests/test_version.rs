@@ -0,0 +1,81 @@
+use backend_core::actor::Actor;
+use backend_core::entity::{entry, journal, ReadRoot, WriteRoot};
+use std::collections::{HashMap, HashSet};
+
+fn update(id: uuid::Uuid, name: &str, expected_version: Option<i32>) -> journal::Command {
+  journal::Command::Update(journal::CommandUpdate {
+    id,
+    name: name.to_string(),
+    description: None,
+    unit: String::default(),
+    tags: None,
+    expected_version,
+  })
+}
+
+#[tokio::test]
+pub async fn test_expected_version() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+
+  let journal = journal::Root::handle(
+    &db,
+    &Actor::system("tester"),
+    journal::Command::Create(journal::CommandCreate {
+      name: "Versioned Journal".to_string(),
+      description: String::default(),
+      unit: "USD".to_string(),
+      tags: HashSet::default(),
+    }),
+  )
+  .await?
+  .remove(0);
+  assert_eq!(1, journal.version);
+
+  let updated = journal::Root::handle(
+    &db,
+    &Actor::system("tester"),
+    update(journal.id, "Updated Journal", Some(1)),
+  )
+  .await?;
+  assert_eq!(2, updated[0].version);
+
+  let result = journal::Root::handle(
+    &db,
+    &Actor::system("tester"),
+    update(journal.id, "Stale Journal", Some(1)),
+  )
+  .await;
+  assert!(matches!(result, Err(backend_core::Error::Conflict(_))));
+
+  let delete = |version| {
+    journal::Command::Delete(journal::CommandDelete {
+      id: HashSet::from_iter([journal.id]),
+      expected_version: HashMap::from_iter([(journal.id, version)]),
+    })
+  };
+  let result = journal::Root::handle(&db, &Actor::system("tester"), delete(1)).await;
+  assert!(matches!(result, Err(backend_core::Error::Conflict(_))));
+  journal::Root::handle(&db, &Actor::system("tester"), delete(2)).await?;
+  let result = journal::Root::handle(&db, &Actor::system("tester"), delete(2)).await;
+  assert!(matches!(result, Err(backend_core::Error::Conflict(_))));
+
+  Ok(())
+}
+
+#[tokio::test]
+pub async fn test_save_stale() -> anyhow::Result<()> {
+  let db = test_suite::init().await?;
+  let entry = entry::Root::find_one(&db, None).await?.unwrap();
+
+  let saved =
+    entry::Root::save(&db, [entry::Root { name: "Saved First".to_string(), ..entry.clone() }])
+      .await?;
+  assert_eq!(entry.version + 1, saved[0].version);
+
+  let result =
+    entry::Root::save(&db, [entry::Root { name: "Saved Later".to_string(), ..entry.clone() }])
+      .await;
+  assert!(matches!(result, Err(backend_core::Error::Conflict(_))));
+
+  Ok(())
+}
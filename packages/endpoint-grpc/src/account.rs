@@ -0,0 +1,188 @@
+use crate::pb::account_command::Command;
+use crate::pb::account_service_server::AccountService;
+use crate::pb::{
+  Account, AccountCommand, AccountCommandCreate, AccountCommandUpdate, AccountQuery, AccountType,
+  AccountsResponse,
+};
+use crate::{
+  actor, map_err, not_found, parse_expected_versions, parse_id, parse_ids, parse_timestamp,
+  to_timestamp, transaction,
+};
+use backend_core::entity::{account, ReadRoot};
+use sea_orm::DatabaseConnection;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+impl From<account::Type> for AccountType {
+  fn from(value: account::Type) -> Self {
+    match value {
+      account::Type::Income => AccountType::Income,
+      account::Type::Expense => AccountType::Expense,
+      account::Type::Asset => AccountType::Asset,
+      account::Type::Liability => AccountType::Liability,
+      account::Type::Equity => AccountType::Equity,
+    }
+  }
+}
+
+impl TryFrom<AccountType> for account::Type {
+  type Error = Status;
+
+  fn try_from(value: AccountType) -> Result<Self, Self::Error> {
+    match value {
+      AccountType::Unspecified => Err(Status::invalid_argument("Unspecified account type")),
+      AccountType::Income => Ok(account::Type::Income),
+      AccountType::Expense => Ok(account::Type::Expense),
+      AccountType::Asset => Ok(account::Type::Asset),
+      AccountType::Liability => Ok(account::Type::Liability),
+      AccountType::Equity => Ok(account::Type::Equity),
+    }
+  }
+}
+
+/// The enums are plain integers in the messages
+pub(crate) fn parse_account_type(value: i32) -> Result<account::Type, Status> {
+  AccountType::try_from(value)
+    .map_err(|_| Status::invalid_argument("Invalid account type"))?
+    .try_into()
+}
+
+impl From<Vec<account::Root>> for AccountsResponse {
+  fn from(results: Vec<account::Root>) -> Self {
+    Self { values: results.into_iter().map(|model| model.into()).collect() }
+  }
+}
+
+impl From<account::Root> for Account {
+  fn from(model: account::Root) -> Self {
+    Self {
+      id: model.id.to_string(),
+      journal_id: model.journal_id.to_string(),
+      name: model.name,
+      description: model.description,
+      unit: model.unit,
+      r#type: AccountType::from(model.typ).into(),
+      tags: Vec::from_iter(model.tags),
+      version: model.version,
+      created_date: to_timestamp(model.created_at),
+      updated_date: to_timestamp(model.updated_at),
+    }
+  }
+}
+
+impl TryFrom<AccountQuery> for account::Query {
+  type Error = Status;
+
+  fn try_from(value: AccountQuery) -> Result<Self, Self::Error> {
+    Ok(Self {
+      id: parse_ids(&value.id)?,
+      journal_id: parse_ids(&value.journal_id)?,
+      name: HashSet::from_iter(value.name),
+      unit: value.unit,
+      typ: value.r#type.map(parse_account_type).transpose()?,
+      tags: HashSet::from_iter(value.tags),
+      full_text: value.full_text,
+      updated_since: parse_timestamp(value.updated_since)?,
+    })
+  }
+}
+
+impl TryFrom<AccountCommandCreate> for account::CommandCreate {
+  type Error = Status;
+
+  fn try_from(value: AccountCommandCreate) -> Result<Self, Self::Error> {
+    Ok(Self {
+      journal_id: parse_id(&value.journal_id)?,
+      name: value.name,
+      description: value.description,
+      unit: value.unit,
+      typ: parse_account_type(value.r#type)?,
+      tags: HashSet::from_iter(value.tags),
+    })
+  }
+}
+
+impl TryFrom<AccountCommandUpdate> for account::CommandUpdate {
+  type Error = Status;
+
+  fn try_from(value: AccountCommandUpdate) -> Result<Self, Self::Error> {
+    Ok(Self {
+      id: parse_id(&value.id)?,
+      name: value.name,
+      description: value.description,
+      unit: value.unit,
+      typ: value.r#type.map(parse_account_type).transpose()?,
+      tags: value.tags.map(|tags| HashSet::from_iter(tags.values)),
+      expected_version: value.expected_version,
+    })
+  }
+}
+
+impl TryFrom<AccountCommand> for account::Command {
+  type Error = Status;
+
+  fn try_from(value: AccountCommand) -> Result<Self, Self::Error> {
+    Ok(match value.command.ok_or_else(|| Status::invalid_argument("Missing command"))? {
+      Command::Create(command) => account::Command::Create(command.try_into()?),
+      Command::Update(command) => account::Command::Update(command.try_into()?),
+      Command::Delete(command) => account::Command::Delete(account::CommandDelete {
+        id: parse_ids(&command.id)?,
+        expected_version: parse_expected_versions(command.expected_version)?,
+      }),
+      Command::Batch(command) => account::Command::Batch(account::CommandBatch {
+        create: command.create.into_iter().map(TryInto::try_into).collect::<Result<_, _>>()?,
+        update: command.update.into_iter().map(TryInto::try_into).collect::<Result<_, _>>()?,
+        delete: parse_ids(&command.delete)?,
+      }),
+    })
+  }
+}
+
+#[derive(Debug)]
+pub struct AccountServiceImpl {
+  pub db: Arc<DatabaseConnection>,
+}
+
+#[tonic::async_trait]
+impl AccountService for AccountServiceImpl {
+  async fn find_all(
+    &self,
+    request: Request<AccountQuery>,
+  ) -> Result<Response<AccountsResponse>, Status> {
+    let actor = actor(&request)?;
+    let query = request.get_ref().clone().try_into()?;
+    let results = account::Root::find_all_as(self.db.as_ref(), actor, Some(query), None, None)
+      .await
+      .map_err(map_err)?;
+
+    Ok(Response::new(results.into()))
+  }
+
+  async fn find_by_id(&self, request: Request<String>) -> Result<Response<Account>, Status> {
+    let id = parse_id(request.get_ref())?;
+    account::Root::find_one_as(
+      self.db.as_ref(),
+      actor(&request)?,
+      Some(account::Query { id: HashSet::from_iter([id]), ..Default::default() }),
+    )
+    .await
+    .map_err(map_err)?
+    .map(|model| Response::new(model.into()))
+    .ok_or_else(|| not_found(account::TYPE, id))
+  }
+
+  async fn handle(
+    &self,
+    request: Request<AccountCommand>,
+  ) -> Result<Response<AccountsResponse>, Status> {
+    let actor = actor(&request)?.clone();
+    let command = request.into_inner().try_into()?;
+    let results = transaction(self.db.as_ref(), |tx| {
+      Box::pin(async move { account::Root::handle(tx, &actor, command).await })
+    })
+    .await?;
+
+    Ok(Response::new(results.into()))
+  }
+}
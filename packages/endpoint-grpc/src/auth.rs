@@ -0,0 +1,260 @@
+use backend_core::actor::Actor;
+use backend_core::entity::{api_token, user, ReadRoot};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use futures::future::BoxFuture;
+use hmac::{Hmac, Mac};
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use sha2::Sha256;
+use std::collections::HashSet;
+use std::env;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tonic::server::NamedService;
+use tonic::Status;
+use tower_layer::Layer;
+use tower_service::Service;
+use uuid::Uuid;
+
+pub const ENV_JWT_SECRET: &str = "WHITE_RABBIT_JWT_SECRET";
+pub const ENV_JWT_ISSUER: &str = "WHITE_RABBIT_JWT_ISSUER";
+
+const BEARER: &str = "Bearer ";
+
+#[derive(Debug, Deserialize)]
+struct Header {
+  alg: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Claims {
+  sub: String,
+  exp: u64,
+  #[serde(default)]
+  nbf: Option<u64>,
+  #[serde(default)]
+  iss: Option<String>,
+}
+
+/// Authenticate the requests by the bearer tokens, either the API tokens saved in the database, or
+/// the JWTs signed by HS256 with the secret configured
+pub struct Authenticator {
+  db: Arc<DatabaseConnection>,
+  jwt_secret: Option<Vec<u8>>,
+  jwt_issuer: Option<String>,
+}
+
+impl Authenticator {
+  pub fn new(
+    db: Arc<DatabaseConnection>,
+    jwt_secret: Option<Vec<u8>>,
+    jwt_issuer: Option<String>,
+  ) -> Self {
+    Self { db, jwt_secret: jwt_secret.filter(|secret| !secret.is_empty()), jwt_issuer }
+  }
+
+  /// Only the API tokens are accepted if the JWT secret is not configured
+  pub fn from_env(db: Arc<DatabaseConnection>) -> Self {
+    Self::new(
+      db,
+      env::var(ENV_JWT_SECRET).ok().map(String::into_bytes),
+      env::var(ENV_JWT_ISSUER).ok(),
+    )
+  }
+
+  /// Find the actor by the `authorization` header. The reasons are logged without the tokens, and
+  /// not returned to the clients
+  pub async fn authenticate(&self, headers: &http::HeaderMap) -> Result<Actor, Status> {
+    let token = headers
+      .get(http::header::AUTHORIZATION)
+      .and_then(|value| value.to_str().ok())
+      .and_then(|value| value.strip_prefix(BEARER))
+      .map(str::trim)
+      .filter(|token| !token.is_empty())
+      .ok_or_else(|| Status::unauthenticated("Missing bearer token"))?;
+
+    let user_id = if token.starts_with(api_token::TOKEN_PREFIX) {
+      match api_token::Root::authenticate(self.db.as_ref(), token).await {
+        Ok(Some(root)) => root.user_id,
+        Ok(None) => return Err(unauthenticated("unknown API token")),
+        Err(err) => return Err(unauthenticated(&err.to_string())),
+      }
+    } else if let Some(secret) = &self.jwt_secret {
+      let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+      verify_jwt(token, secret, self.jwt_issuer.as_deref(), now).map_err(unauthenticated)?
+    } else {
+      return Err(unauthenticated("JWT secret not configured"));
+    };
+
+    // The users may have been deleted since the tokens are issued
+    match user::Root::find_one(
+      self.db.as_ref(),
+      Some(user::Query { id: HashSet::from_iter([user_id]), ..Default::default() }),
+    )
+    .await
+    {
+      Ok(Some(_)) => Ok(Actor::User(user_id)),
+      Ok(None) => Err(unauthenticated("unknown user")),
+      Err(err) => Err(unauthenticated(&err.to_string())),
+    }
+  }
+}
+
+fn unauthenticated(reason: &str) -> Status {
+  log::info!("Authentication failed: {}", reason);
+  Status::unauthenticated("Invalid bearer token")
+}
+
+/// Verify the signature and the claims of the JWT, and return the user ID in the subject
+fn verify_jwt(
+  token: &str,
+  secret: &[u8],
+  issuer: Option<&str>,
+  now: u64,
+) -> Result<Uuid, &'static str> {
+  let mut parts = token.split('.');
+  let (Some(header), Some(claims), Some(signature), None) =
+    (parts.next(), parts.next(), parts.next(), parts.next())
+  else {
+    return Err("malformed JWT");
+  };
+
+  let decode = |part: &str| URL_SAFE_NO_PAD.decode(part).map_err(|_| "malformed JWT");
+  let parsed: Header = serde_json::from_slice(&decode(header)?).map_err(|_| "malformed JWT")?;
+  if parsed.alg != "HS256" {
+    return Err("unsupported JWT algorithm");
+  }
+
+  let mut mac = Hmac::<Sha256>::new_from_slice(secret).map_err(|_| "invalid JWT secret")?;
+  mac.update(header.as_bytes());
+  mac.update(b".");
+  mac.update(claims.as_bytes());
+  mac.verify_slice(&decode(signature)?).map_err(|_| "invalid JWT signature")?;
+
+  let claims: Claims = serde_json::from_slice(&decode(claims)?).map_err(|_| "malformed JWT")?;
+  if claims.exp <= now {
+    return Err("expired JWT");
+  }
+  if claims.nbf.is_some_and(|nbf| nbf > now) {
+    return Err("JWT not valid yet");
+  }
+  if let Some(issuer) = issuer {
+    if claims.iss.as_deref() != Some(issuer) {
+      return Err("unexpected JWT issuer");
+    }
+  }
+  claims.sub.parse().map_err(|_| "invalid JWT subject")
+}
+
+/// Authenticate the requests before the services, and pass the [`Actor`] to them in the
+/// extensions
+#[derive(Clone)]
+pub struct AuthLayer {
+  authenticator: Arc<Authenticator>,
+}
+
+impl AuthLayer {
+  pub fn new(authenticator: Authenticator) -> Self {
+    Self { authenticator: Arc::new(authenticator) }
+  }
+}
+
+impl<S> Layer<S> for AuthLayer {
+  type Service = AuthService<S>;
+
+  fn layer(&self, inner: S) -> Self::Service {
+    AuthService { inner, authenticator: self.authenticator.clone() }
+  }
+}
+
+#[derive(Clone)]
+pub struct AuthService<S> {
+  inner: S,
+  authenticator: Arc<Authenticator>,
+}
+
+impl<S: NamedService> NamedService for AuthService<S> {
+  const NAME: &'static str = S::NAME;
+}
+
+impl<S, B> Service<http::Request<B>> for AuthService<S>
+where
+  S: Service<http::Request<B>, Response = http::Response<tonic::body::BoxBody>>
+    + Clone
+    + Send
+    + 'static,
+  S::Future: Send,
+  B: Send + 'static,
+{
+  type Response = S::Response;
+  type Error = S::Error;
+  type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+  fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    self.inner.poll_ready(cx)
+  }
+
+  fn call(&mut self, mut request: http::Request<B>) -> Self::Future {
+    // The ready one is taken, leaving the clone for the next request
+    let clone = self.inner.clone();
+    let mut inner = std::mem::replace(&mut self.inner, clone);
+    let authenticator = self.authenticator.clone();
+    Box::pin(async move {
+      match authenticator.authenticate(request.headers()).await {
+        Ok(actor) => {
+          request.extensions_mut().insert(actor);
+          inner.call(request).await
+        }
+        Err(status) => Ok(status.into_http()),
+      }
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::auth::verify_jwt;
+  use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+  use base64::Engine;
+  use hmac::{Hmac, Mac};
+  use serde_json::{json, Value};
+  use sha2::Sha256;
+  use uuid::uuid;
+
+  const SECRET: &[u8] = b"local-secret";
+
+  fn sign(header: Value, claims: Value, secret: &[u8]) -> String {
+    let content = format!(
+      "{}.{}",
+      URL_SAFE_NO_PAD.encode(header.to_string()),
+      URL_SAFE_NO_PAD.encode(claims.to_string())
+    );
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).unwrap();
+    mac.update(content.as_bytes());
+    format!("{}.{}", content, URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes()))
+  }
+
+  #[test]
+  fn test_verify_jwt() {
+    let user_id = uuid!("7aaec70c-adbc-47d1-8b74-a3e21f387d21");
+    let header = json!({ "alg": "HS256", "typ": "JWT" });
+    let claims = json!({ "sub": user_id, "exp": 2000, "iss": "white-rabbit" });
+
+    let token = sign(header.clone(), claims.clone(), SECRET);
+    assert_eq!(Ok(user_id), verify_jwt(&token, SECRET, None, 1000));
+    assert_eq!(Ok(user_id), verify_jwt(&token, SECRET, Some("white-rabbit"), 1000));
+    assert_eq!(Err("unexpected JWT issuer"), verify_jwt(&token, SECRET, Some("other"), 1000));
+    assert_eq!(Err("expired JWT"), verify_jwt(&token, SECRET, None, 2000));
+    assert_eq!(Err("invalid JWT signature"), verify_jwt(&token, b"other-secret", None, 1000));
+
+    let token = sign(json!({ "alg": "none" }), claims.clone(), SECRET);
+    assert_eq!(Err("unsupported JWT algorithm"), verify_jwt(&token, SECRET, None, 1000));
+
+    let token = sign(header, json!({ "sub": user_id, "exp": 2000, "nbf": 1500 }), SECRET);
+    assert_eq!(Err("JWT not valid yet"), verify_jwt(&token, SECRET, None, 1000));
+
+    assert_eq!(Err("malformed JWT"), verify_jwt("a.b", SECRET, None, 1000));
+  }
+}
@@ -0,0 +1,239 @@
+use crate::pb::change_event::Value;
+use crate::pb::change_service_server::ChangeService;
+use crate::pb::{ChangeEvent, ChangeType, WatchQuery};
+use crate::{actor, map_err, parse_ids, to_timestamp};
+use backend_core::actor::Actor;
+use backend_core::entity::{account, entry, history, journal, membership, Presentation, ReadRoot};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use futures::stream::BoxStream;
+use sea_orm::DatabaseConnection;
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::{Request, Response, Status};
+use uuid::Uuid;
+
+pub const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+const WATCH_LIMIT: u64 = 100;
+
+fn encode_token(sequence: i64) -> String {
+  URL_SAFE_NO_PAD.encode(serde_json::to_vec(&sequence).unwrap_or_default())
+}
+
+fn parse_token(value: &str) -> Result<i64, Status> {
+  URL_SAFE_NO_PAD
+    .decode(value)
+    .ok()
+    .and_then(|value| serde_json::from_slice(&value).ok())
+    .ok_or_else(|| Status::invalid_argument("Invalid resume token"))
+}
+
+/// The snapshots are converted by the entity types, and the unknown ones are sent without values
+async fn to_value(
+  db: &DatabaseConnection,
+  entity: &str,
+  snapshot: serde_json::Value,
+) -> Result<Option<Value>, Status> {
+  let value = match entity {
+    journal::TYPE => {
+      serde_json::from_value::<journal::Root>(snapshot).ok().map(|root| Value::Journal(root.into()))
+    }
+    account::TYPE => {
+      serde_json::from_value::<account::Root>(snapshot).ok().map(|root| Value::Account(root.into()))
+    }
+    entry::TYPE => match serde_json::from_value::<entry::Root>(snapshot) {
+      Ok(root) => entry::Presentation::from_roots(db, vec![root])
+        .await
+        .map_err(map_err)?
+        .pop()
+        .map(|presentation| Value::Entry(presentation.into())),
+      Err(_) => None,
+    },
+    _ => None,
+  };
+  Ok(value)
+}
+
+/// Poll the histories after the sequence, and queue the events of the journals allowed to the actor
+struct Watcher {
+  db: Arc<DatabaseConnection>,
+  actor: Actor,
+  query: history::Query,
+  journal_ids: HashSet<Uuid>,
+  /// The journals allowed to the actor since the resume token or the start of the watch, so that
+  /// their deletions are still sent after the memberships are deleted with them
+  followed: HashSet<Uuid>,
+  sequence: i64,
+  interval: Duration,
+  pending: VecDeque<ChangeEvent>,
+}
+
+impl Watcher {
+  /// The journals allowed to the actor, or `None` for all of them. Checked on every poll, since the
+  /// memberships may have changed since the last one
+  async fn allowed(&mut self) -> Result<Option<HashSet<Uuid>>, Status> {
+    let db = self.db.as_ref();
+    let Some(mut allowed) =
+      self.actor.find_journal_ids(db, membership::Role::Viewer).await.map_err(map_err)?
+    else {
+      return Ok(None);
+    };
+
+    // The followed journals no longer allowed are either deleted, or the memberships are revoked
+    let missing: HashSet<_> = self.followed.difference(&allowed).copied().collect();
+    let existing: HashSet<_> = if missing.is_empty() {
+      HashSet::default()
+    } else {
+      let query = journal::Query { id: missing.clone(), ..Default::default() };
+      journal::Root::find_all(db, Some(query), None, None)
+        .await
+        .map_err(map_err)?
+        .into_iter()
+        .map(|journal| journal.id)
+        .collect()
+    };
+    self.followed.retain(|journal_id| !existing.contains(journal_id));
+    self.followed.extend(allowed.iter().copied());
+    allowed.extend(missing.into_iter().filter(|journal_id| !existing.contains(journal_id)));
+    Ok(Some(allowed))
+  }
+
+  async fn next(&mut self) -> Result<ChangeEvent, Status> {
+    loop {
+      if let Some(event) = self.pending.pop_front() {
+        return Ok(event);
+      }
+
+      let histories = history::Root::find_after(
+        self.db.as_ref(),
+        self.query.clone(),
+        self.sequence,
+        Some(WATCH_LIMIT),
+      )
+      .await
+      .map_err(map_err)?;
+      if histories.is_empty() {
+        tokio::time::sleep(self.interval).await;
+        continue;
+      }
+
+      let allowed = self.allowed().await?;
+      let db = self.db.as_ref();
+      for history in histories {
+        self.sequence = history.sequence;
+        // The memberships are recorded for the journals to follow only
+        if history.entity == membership::TYPE {
+          continue;
+        }
+        let Some(journal_id) = history.journal_id() else {
+          continue;
+        };
+        if allowed.as_ref().is_some_and(|allowed| !allowed.contains(&journal_id))
+          || (!self.journal_ids.is_empty() && !self.journal_ids.contains(&journal_id))
+        {
+          continue;
+        }
+
+        let typ = match (&history.before, &history.after) {
+          (None, _) => ChangeType::Created,
+          (_, None) => ChangeType::Deleted,
+          _ => ChangeType::Updated,
+        };
+        let value = match history.after {
+          Some(after) => to_value(db, &history.entity, after).await?,
+          None => None,
+        };
+        self.pending.push_back(ChangeEvent {
+          resume_token: encode_token(history.sequence),
+          r#type: typ.into(),
+          entity: history.entity,
+          entity_id: history.entity_id.to_string(),
+          journal_id: journal_id.to_string(),
+          command_id: history.command_id.to_string(),
+          timestamp: to_timestamp(history.timestamp),
+          value,
+        });
+      }
+    }
+  }
+}
+
+#[derive(Debug)]
+pub struct ChangeServiceImpl {
+  pub db: Arc<DatabaseConnection>,
+  pub interval: Duration,
+}
+
+#[tonic::async_trait]
+impl ChangeService for ChangeServiceImpl {
+  type WatchStream = BoxStream<'static, Result<ChangeEvent, Status>>;
+
+  /// Stream the changes committed after the resume token, or after the call without the token.
+  ///
+  /// The deletions of the journals allowed as of the resume token, when the watch started, or
+  /// since, are sent with the entities cascaded, even though the memberships are deleted with them
+  async fn watch(
+    &self,
+    request: Request<WatchQuery>,
+  ) -> Result<Response<Self::WatchStream>, Status> {
+    let actor = actor(&request)?.clone();
+    let value = request.into_inner();
+    let query = history::Query {
+      entity: HashSet::from_iter(value.entity),
+      entity_id: parse_ids(&value.entity_id)?,
+      ..Default::default()
+    };
+    let db = self.db.as_ref();
+    let sequence = if value.resume_token.is_empty() {
+      history::Root::find_last_sequence(db).await.map_err(map_err)?
+    } else {
+      parse_token(&value.resume_token)?
+    };
+    let mut followed = actor
+      .find_journal_ids(db, membership::Role::Viewer)
+      .await
+      .map_err(map_err)?
+      .unwrap_or_default();
+    // The journals allowed as of the resume token as well, whose memberships may have been deleted
+    // with them since
+    if let Actor::User(user_id) = &actor {
+      if !value.resume_token.is_empty() {
+        let query = history::Query {
+          entity: HashSet::from_iter([membership::TYPE.to_string()]),
+          entity_id: HashSet::from_iter([*user_id]),
+          ..Default::default()
+        };
+        let histories =
+          history::Root::find_after(db, query, sequence, None).await.map_err(map_err)?;
+        followed.extend(
+          histories
+            .iter()
+            .filter(|history| history.before.is_some())
+            .filter_map(history::Root::journal_id),
+        );
+      }
+    }
+
+    let watcher = Watcher {
+      db: self.db.clone(),
+      actor,
+      query,
+      journal_ids: parse_ids(&value.journal_id)?,
+      followed,
+      sequence,
+      interval: self.interval,
+      pending: VecDeque::default(),
+    };
+    // The stream ends after the first error
+    let stream = futures::stream::unfold(Some(watcher), |watcher| async move {
+      let mut watcher = watcher?;
+      match watcher.next().await {
+        Ok(event) => Some((Ok(event), Some(watcher))),
+        Err(status) => Some((Err(status), None)),
+      }
+    });
+
+    Ok(Response::new(Box::pin(stream)))
+  }
+}
@@ -0,0 +1,273 @@
+use crate::pb::entry_command::Command;
+use crate::pb::entry_service_server::EntryService;
+use crate::pb::{
+  EntriesResponse, Entry, EntryCommand, EntryCommandCreate, EntryCommandUpdate, EntryItem,
+  EntryQuery, EntryType, StateItem,
+};
+use crate::{
+  actor, map_err, not_found, parse_date, parse_decimal, parse_expected_versions, parse_id,
+  parse_ids, parse_timestamp, to_timestamp, transaction,
+};
+use backend_core::entity::{entry, Presentation, ReadRoot};
+use sea_orm::DatabaseConnection;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+impl From<entry::Type> for EntryType {
+  fn from(value: entry::Type) -> Self {
+    match value {
+      entry::Type::Record => EntryType::Record,
+      entry::Type::Check => EntryType::Check,
+    }
+  }
+}
+
+impl TryFrom<EntryType> for entry::Type {
+  type Error = Status;
+
+  fn try_from(value: EntryType) -> Result<Self, Self::Error> {
+    match value {
+      EntryType::Unspecified => Err(Status::invalid_argument("Unspecified entry type")),
+      EntryType::Record => Ok(entry::Type::Record),
+      EntryType::Check => Ok(entry::Type::Check),
+    }
+  }
+}
+
+/// The enums are plain integers in the messages
+pub(crate) fn parse_entry_type(value: i32) -> Result<entry::Type, Status> {
+  EntryType::try_from(value).map_err(|_| Status::invalid_argument("Invalid entry type"))?.try_into()
+}
+
+impl From<entry::Item> for EntryItem {
+  fn from(value: entry::Item) -> Self {
+    Self {
+      account: value.account.to_string(),
+      amount: value.amount.to_string(),
+      price: value.price.to_string(),
+    }
+  }
+}
+
+impl TryFrom<EntryItem> for entry::Item {
+  type Error = Status;
+
+  fn try_from(value: EntryItem) -> Result<Self, Self::Error> {
+    Ok(Self {
+      account: parse_id(&value.account)?,
+      amount: parse_decimal(&value.amount)?,
+      price: parse_decimal(&value.price)?,
+    })
+  }
+}
+
+impl From<entry::StateItem> for StateItem {
+  fn from(value: entry::StateItem) -> Self {
+    match value {
+      entry::StateItem::Valid(value) => {
+        Self { valid: true, left: value.to_string(), right: value.to_string() }
+      }
+      entry::StateItem::Invalid(left, right) => {
+        Self { valid: false, left: left.to_string(), right: right.to_string() }
+      }
+    }
+  }
+}
+
+impl From<Vec<entry::Presentation>> for EntriesResponse {
+  fn from(results: Vec<entry::Presentation>) -> Self {
+    Self { values: results.into_iter().map(|model| model.into()).collect() }
+  }
+}
+
+impl From<entry::Presentation> for Entry {
+  fn from(model: entry::Presentation) -> Self {
+    match model {
+      entry::Presentation::Record(model) => Self {
+        id: model.id.to_string(),
+        journal_id: model.journal_id.to_string(),
+        name: model.name,
+        description: model.description,
+        r#type: EntryType::Record.into(),
+        date: model.date.to_string(),
+        tags: Vec::from_iter(model.tags),
+        items: model.items.into_iter().map(Into::into).collect(),
+        payee_id: model.payee_id.map(|id| id.to_string()),
+        version: model.version,
+        created_date: to_timestamp(model.created_at),
+        updated_date: to_timestamp(model.updated_at),
+        state: Some(model.state.into()),
+        states: Default::default(),
+        reconciliation_id: model.reconciliation_id.map(|id| id.to_string()),
+      },
+      entry::Presentation::Check(model) => Self {
+        id: model.id.to_string(),
+        journal_id: model.journal_id.to_string(),
+        name: model.name,
+        description: model.description,
+        r#type: EntryType::Check.into(),
+        date: model.date.to_string(),
+        tags: Vec::from_iter(model.tags),
+        items: model.items.into_iter().map(Into::into).collect(),
+        payee_id: model.payee_id.map(|id| id.to_string()),
+        version: model.version,
+        created_date: to_timestamp(model.created_at),
+        updated_date: to_timestamp(model.updated_at),
+        state: None,
+        states: model
+          .state
+          .into_iter()
+          .map(|(account_id, state)| (account_id.to_string(), state.into()))
+          .collect(),
+        reconciliation_id: None,
+      },
+    }
+  }
+}
+
+impl TryFrom<EntryQuery> for entry::Query {
+  type Error = Status;
+
+  fn try_from(value: EntryQuery) -> Result<Self, Self::Error> {
+    Ok(Self {
+      id: parse_ids(&value.id)?,
+      journal_id: parse_ids(&value.journal_id)?,
+      account_id: parse_ids(&value.account_id)?,
+      name: HashSet::from_iter(value.name),
+      typ: value.r#type.map(parse_entry_type).transpose()?,
+      start: parse_date(&value.start)?,
+      end: parse_date(&value.end)?,
+      tags: HashSet::from_iter(value.tags),
+      full_text: value.full_text,
+      reconciled: value.reconciled,
+      payee_id: parse_ids(&value.payee_id)?,
+      updated_since: parse_timestamp(value.updated_since)?,
+    })
+  }
+}
+
+fn parse_items(values: Vec<EntryItem>) -> Result<Vec<entry::Item>, Status> {
+  values.into_iter().map(TryInto::try_into).collect()
+}
+
+impl TryFrom<EntryCommandCreate> for entry::CommandCreate {
+  type Error = Status;
+
+  fn try_from(value: EntryCommandCreate) -> Result<Self, Self::Error> {
+    Ok(Self {
+      journal_id: parse_id(&value.journal_id)?,
+      name: value.name,
+      description: value.description,
+      typ: parse_entry_type(value.r#type)?,
+      date: parse_date(&value.date)?.ok_or_else(|| Status::invalid_argument("Missing date"))?,
+      tags: HashSet::from_iter(value.tags),
+      items: parse_items(value.items)?,
+      payee_id: value.payee_id.as_deref().map(parse_id).transpose()?,
+    })
+  }
+}
+
+impl TryFrom<EntryCommandUpdate> for entry::CommandUpdate {
+  type Error = Status;
+
+  fn try_from(value: EntryCommandUpdate) -> Result<Self, Self::Error> {
+    let payee_id = match value.payee_id.as_deref() {
+      None => None,
+      Some("") => Some(None),
+      Some(payee_id) => Some(Some(parse_id(payee_id)?)),
+    };
+    Ok(Self {
+      id: parse_id(&value.id)?,
+      name: value.name,
+      description: value.description,
+      typ: value.r#type.map(parse_entry_type).transpose()?,
+      date: value.date.as_deref().map(parse_date).transpose()?.flatten(),
+      tags: value.tags.map(|tags| HashSet::from_iter(tags.values)),
+      items: parse_items(value.items)?,
+      payee_id,
+      expected_version: value.expected_version,
+    })
+  }
+}
+
+impl TryFrom<EntryCommand> for entry::Command {
+  type Error = Status;
+
+  fn try_from(value: EntryCommand) -> Result<Self, Self::Error> {
+    Ok(match value.command.ok_or_else(|| Status::invalid_argument("Missing command"))? {
+      Command::Create(command) => entry::Command::Create(command.try_into()?),
+      Command::Update(command) => entry::Command::Update(command.try_into()?),
+      Command::Delete(command) => entry::Command::Delete(entry::CommandDelete {
+        id: parse_ids(&command.id)?,
+        expected_version: parse_expected_versions(command.expected_version)?,
+      }),
+      Command::Batch(command) => entry::Command::Batch(entry::CommandBatch {
+        create: command.create.into_iter().map(TryInto::try_into).collect::<Result<_, _>>()?,
+        update: command.update.into_iter().map(TryInto::try_into).collect::<Result<_, _>>()?,
+        delete: parse_ids(&command.delete)?,
+      }),
+    })
+  }
+}
+
+#[derive(Debug)]
+pub struct EntryServiceImpl {
+  pub db: Arc<DatabaseConnection>,
+}
+
+#[tonic::async_trait]
+impl EntryService for EntryServiceImpl {
+  async fn find_all(
+    &self,
+    request: Request<EntryQuery>,
+  ) -> Result<Response<EntriesResponse>, Status> {
+    let actor = actor(&request)?;
+    let query = request.get_ref().clone().try_into()?;
+    let roots = entry::Root::find_all_as(self.db.as_ref(), actor, Some(query), None, None)
+      .await
+      .map_err(map_err)?;
+    let results =
+      entry::Presentation::from_roots(self.db.as_ref(), roots).await.map_err(map_err)?;
+
+    Ok(Response::new(results.into()))
+  }
+
+  async fn find_by_id(&self, request: Request<String>) -> Result<Response<Entry>, Status> {
+    let id = parse_id(request.get_ref())?;
+    let root = entry::Root::find_one_as(
+      self.db.as_ref(),
+      actor(&request)?,
+      Some(entry::Query { id: HashSet::from_iter([id]), ..Default::default() }),
+    )
+    .await
+    .map_err(map_err)?
+    .ok_or_else(|| not_found(entry::TYPE, id))?;
+
+    entry::Presentation::from_roots(self.db.as_ref(), vec![root])
+      .await
+      .map_err(map_err)?
+      .into_iter()
+      .next()
+      .map(|model| Response::new(model.into()))
+      .ok_or_else(|| not_found(entry::TYPE, id))
+  }
+
+  /// The presentations are returned, the same as the Tauri handlers
+  async fn handle(
+    &self,
+    request: Request<EntryCommand>,
+  ) -> Result<Response<EntriesResponse>, Status> {
+    let actor = actor(&request)?.clone();
+    let command = request.into_inner().try_into()?;
+    let results = transaction(self.db.as_ref(), |tx| {
+      Box::pin(async move {
+        let roots = entry::Root::handle(tx, &actor, command).await?;
+        entry::Presentation::from_roots(tx, roots).await
+      })
+    })
+    .await?;
+
+    Ok(Response::new(results.into()))
+  }
+}
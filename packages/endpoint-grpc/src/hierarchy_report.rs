@@ -0,0 +1,96 @@
+use crate::account::parse_account_type;
+use crate::entry::parse_entry_type;
+use crate::pb::hierarchy_report_service_server::HierarchyReportService;
+use crate::pb::{HierarchyReport, HierarchyReportQuery, HierarchyReportsResponse};
+use crate::{actor, map_err, not_found, parse_date, parse_ids};
+use backend_core::entity::{hierarchy_report, ReadRoot};
+use sea_orm::DatabaseConnection;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+impl From<Vec<hierarchy_report::Root>> for HierarchyReportsResponse {
+  fn from(results: Vec<hierarchy_report::Root>) -> Self {
+    Self { values: results.into_iter().map(|model| model.into()).collect() }
+  }
+}
+
+impl From<hierarchy_report::Root> for HierarchyReport {
+  fn from(model: hierarchy_report::Root) -> Self {
+    Self {
+      id: model.id(),
+      journal_id: model.journal_id.to_string(),
+      prefix: model.prefix,
+      name: model.name,
+      unit: model.unit,
+      depth: model.depth as u32,
+      value: model.value.to_string(),
+      values: model
+        .values
+        .into_iter()
+        .map(|(account_id, value)| (account_id.to_string(), value.to_string()))
+        .collect(),
+      children: model.children.into_iter().map(Into::into).collect(),
+    }
+  }
+}
+
+impl TryFrom<HierarchyReportQuery> for hierarchy_report::Query {
+  type Error = Status;
+
+  fn try_from(value: HierarchyReportQuery) -> Result<Self, Self::Error> {
+    Ok(Self {
+      id: HashSet::from_iter(value.id),
+      journal_id: parse_ids(&value.journal_id)?,
+      start: parse_date(&value.start)?,
+      end: parse_date(&value.end)?,
+      max_depth: value.max_depth.map(|depth| depth as usize),
+      account_type: value
+        .account_type
+        .into_iter()
+        .map(parse_account_type)
+        .collect::<Result<_, _>>()?,
+      account_tags: HashSet::from_iter(value.account_tags),
+      entry_tags: HashSet::from_iter(value.entry_tags),
+      entry_type: value.entry_type.map(parse_entry_type).transpose()?,
+    })
+  }
+}
+
+#[derive(Debug)]
+pub struct HierarchyReportServiceImpl {
+  pub db: Arc<DatabaseConnection>,
+}
+
+#[tonic::async_trait]
+impl HierarchyReportService for HierarchyReportServiceImpl {
+  async fn find_all(
+    &self,
+    request: Request<HierarchyReportQuery>,
+  ) -> Result<Response<HierarchyReportsResponse>, Status> {
+    let actor = actor(&request)?;
+    let query = request.get_ref().clone().try_into()?;
+    let results =
+      hierarchy_report::Root::find_all_as(self.db.as_ref(), actor, Some(query), None, None)
+        .await
+        .map_err(map_err)?;
+
+    Ok(Response::new(results.into()))
+  }
+
+  async fn find_by_id(
+    &self,
+    request: Request<String>,
+  ) -> Result<Response<HierarchyReport>, Status> {
+    let id = request.get_ref().clone();
+    hierarchy_report::Root::find_one_as(
+      self.db.as_ref(),
+      actor(&request)?,
+      Some(hierarchy_report::Query { id: HashSet::from_iter([id.clone()]), ..Default::default() }),
+    )
+    .await
+    .map_err(map_err)?
+    .map(|model| Response::new(model.into()))
+    .ok_or_else(|| not_found(hierarchy_report::TYPE, id))
+  }
+}
@@ -0,0 +1,151 @@
+use crate::pb::journal_command::Command;
+use crate::pb::journal_service_server::JournalService;
+use crate::pb::{
+  Journal, JournalCommand, JournalCommandCreate, JournalCommandUpdate, JournalQuery,
+  JournalsResponse,
+};
+use crate::{
+  actor, map_err, not_found, parse_expected_versions, parse_id, parse_ids, parse_timestamp,
+  to_timestamp, transaction,
+};
+use backend_core::entity::{journal, ReadRoot};
+use sea_orm::DatabaseConnection;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+impl From<Vec<journal::Root>> for JournalsResponse {
+  fn from(results: Vec<journal::Root>) -> Self {
+    Self { values: results.into_iter().map(|model| model.into()).collect() }
+  }
+}
+
+impl From<journal::Root> for Journal {
+  fn from(model: journal::Root) -> Self {
+    Self {
+      id: model.id.to_string(),
+      created_date: to_timestamp(model.created_at),
+      name: model.name,
+      description: model.description,
+      unit: model.unit,
+      tags: Vec::from_iter(model.tags),
+      updated_date: to_timestamp(model.updated_at),
+      version: model.version,
+    }
+  }
+}
+
+impl TryFrom<JournalQuery> for journal::Query {
+  type Error = Status;
+
+  fn try_from(value: JournalQuery) -> Result<Self, Self::Error> {
+    Ok(Self {
+      id: parse_ids(&value.id)?,
+      name: HashSet::from_iter(value.name),
+      unit: value.unit,
+      tags: HashSet::from_iter(value.tags),
+      full_text: value.full_text,
+      updated_since: parse_timestamp(value.updated_since)?,
+    })
+  }
+}
+
+impl From<JournalCommandCreate> for journal::CommandCreate {
+  fn from(value: JournalCommandCreate) -> Self {
+    Self {
+      name: value.name,
+      description: value.description,
+      unit: value.unit,
+      tags: HashSet::from_iter(value.tags),
+    }
+  }
+}
+
+impl TryFrom<JournalCommandUpdate> for journal::CommandUpdate {
+  type Error = Status;
+
+  fn try_from(value: JournalCommandUpdate) -> Result<Self, Self::Error> {
+    Ok(Self {
+      id: parse_id(&value.id)?,
+      name: value.name,
+      description: value.description,
+      unit: value.unit,
+      tags: value.tags.map(|tags| HashSet::from_iter(tags.values)),
+      expected_version: value.expected_version,
+    })
+  }
+}
+
+impl TryFrom<JournalCommand> for journal::Command {
+  type Error = Status;
+
+  fn try_from(value: JournalCommand) -> Result<Self, Self::Error> {
+    Ok(match value.command.ok_or_else(|| Status::invalid_argument("Missing command"))? {
+      Command::Create(command) => journal::Command::Create(command.into()),
+      Command::Update(command) => journal::Command::Update(command.try_into()?),
+      Command::Delete(command) => journal::Command::Delete(journal::CommandDelete {
+        id: parse_ids(&command.id)?,
+        expected_version: parse_expected_versions(command.expected_version)?,
+      }),
+      Command::Batch(command) => journal::Command::Batch(journal::CommandBatch {
+        create: command.create.into_iter().map(Into::into).collect(),
+        update: command.update.into_iter().map(TryInto::try_into).collect::<Result<_, _>>()?,
+        delete: parse_ids(&command.delete)?,
+      }),
+    })
+  }
+}
+
+#[derive(Debug)]
+pub struct JournalServiceImpl {
+  pub db: Arc<DatabaseConnection>,
+}
+
+#[tonic::async_trait]
+impl JournalService for JournalServiceImpl {
+  async fn find_all(
+    &self,
+    request: Request<JournalQuery>,
+  ) -> Result<Response<JournalsResponse>, Status> {
+    let actor = actor(&request)?;
+    let query = request.get_ref();
+    let results = journal::Root::find_all_as(
+      self.db.as_ref(),
+      actor,
+      Some(query.clone().try_into()?),
+      None,
+      None,
+    )
+    .await
+    .map_err(map_err)?;
+
+    Ok(Response::new(results.into()))
+  }
+
+  async fn find_by_id(&self, request: Request<String>) -> Result<Response<Journal>, Status> {
+    let id = parse_id(request.get_ref())?;
+    journal::Root::find_one_as(
+      self.db.as_ref(),
+      actor(&request)?,
+      Some(journal::Query { id: HashSet::from_iter([id]), ..Default::default() }),
+    )
+    .await
+    .map_err(map_err)?
+    .map(|model| Response::new(model.into()))
+    .ok_or_else(|| not_found(journal::TYPE, id))
+  }
+
+  async fn handle(
+    &self,
+    request: Request<JournalCommand>,
+  ) -> Result<Response<JournalsResponse>, Status> {
+    let actor = actor(&request)?.clone();
+    let command = request.into_inner().try_into()?;
+    let results = transaction(self.db.as_ref(), |tx| {
+      Box::pin(async move { journal::Root::handle(tx, &actor, command).await })
+    })
+    .await?;
+
+    Ok(Response::new(results.into()))
+  }
+}
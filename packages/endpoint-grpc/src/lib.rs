@@ -0,0 +1,108 @@
+// The errors are all `tonic::Status`, which is large but returned to the clients as it is
+#![allow(clippy::result_large_err)]
+
+pub mod account;
+pub mod auth;
+pub mod change;
+pub mod entry;
+pub mod hierarchy_report;
+pub mod journal;
+
+use backend_core::actor::Actor;
+use backend_core::entity::FIELD_ID;
+use backend_core::error::{ErrorNotFound, ProblemDetailDef};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sea_orm::{DatabaseConnection, DatabaseTransaction, TransactionError, TransactionTrait};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::time::SystemTime;
+use tonic::{Code, Request, Status};
+use uuid::Uuid;
+
+pub mod pb {
+  tonic::include_proto!("whiterabbit.journal");
+
+  pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("journal_descriptor");
+}
+
+/// Map the errors to the codes by the kinds, with the problem details in JSON as the details
+pub fn map_err(value: backend_core::Error) -> Status {
+  let code = match &value {
+    backend_core::Error::NotFound(_) => Code::NotFound,
+    backend_core::Error::ExistingEntity(_) => Code::AlreadyExists,
+    backend_core::Error::OutOfRange(_) | backend_core::Error::RequiredField(_) => {
+      Code::InvalidArgument
+    }
+    backend_core::Error::Conflict(_) => Code::Aborted,
+    backend_core::Error::Forbidden(_) => Code::PermissionDenied,
+    backend_core::Error::Internal(_) => Code::Internal,
+  };
+  let value: ProblemDetailDef = value.into();
+  let details = serde_json::to_string(&value).unwrap_or_default();
+  Status::with_details(code, value.detail, details.into())
+}
+
+/// Run the commands in a transaction, the same as the Tauri handlers
+async fn transaction<T, F>(db: &DatabaseConnection, callback: F) -> Result<T, Status>
+where
+  F: for<'c> FnOnce(
+      &'c DatabaseTransaction,
+    ) -> Pin<Box<dyn Future<Output = backend_core::Result<T>> + Send + 'c>>
+    + Send,
+  T: Send,
+{
+  db.transaction(callback).await.map_err(|err| match err {
+    TransactionError::Connection(err) => map_err(err.into()),
+    TransactionError::Transaction(err) => map_err(err),
+  })
+}
+
+/// The actor authenticated by the [`auth::AuthLayer`]
+fn actor<T>(request: &Request<T>) -> Result<&Actor, Status> {
+  request.extensions().get::<Actor>().ok_or_else(|| Status::unauthenticated("Not authenticated"))
+}
+
+fn not_found(entity: &str, id: impl ToString) -> Status {
+  map_err(backend_core::Error::NotFound(ErrorNotFound {
+    entity: entity.to_string(),
+    values: vec![(FIELD_ID.to_string(), id.to_string())],
+  }))
+}
+
+fn parse_id(value: &str) -> Result<Uuid, Status> {
+  value.parse().map_err(|_| Status::invalid_argument("Invalid UUID"))
+}
+
+fn parse_ids(values: &[String]) -> Result<HashSet<Uuid>, Status> {
+  values.iter().map(|value| parse_id(value)).collect()
+}
+
+fn parse_expected_versions(values: HashMap<String, i32>) -> Result<HashMap<Uuid, i32>, Status> {
+  values.into_iter().map(|(id, version)| Ok((parse_id(&id)?, version))).collect()
+}
+
+fn parse_decimal(value: &str) -> Result<Decimal, Status> {
+  value.parse().map_err(|_| Status::invalid_argument("Invalid decimal"))
+}
+
+/// Empty for no date
+fn parse_date(value: &str) -> Result<Option<NaiveDate>, Status> {
+  if value.is_empty() {
+    return Ok(None);
+  }
+  value.parse().map(Some).map_err(|_| Status::invalid_argument("Invalid date"))
+}
+
+fn parse_timestamp(value: Option<prost_types::Timestamp>) -> Result<Option<DateTime<Utc>>, Status> {
+  value
+    .map(SystemTime::try_from)
+    .transpose()
+    .map_err(|_| Status::invalid_argument("Invalid timestamp"))
+    .map(|value| value.map(Into::into))
+}
+
+fn to_timestamp(value: DateTime<Utc>) -> Option<prost_types::Timestamp> {
+  Some(SystemTime::from(value).into())
+}
@@ -0,0 +1,169 @@
+use backend_core::entity::MAX_NAME_LENGTH;
+use endpoint_grpc::account::AccountServiceImpl;
+use endpoint_grpc::entry::EntryServiceImpl;
+use endpoint_grpc::journal::JournalServiceImpl;
+use endpoint_grpc::pb::account_service_server::AccountService;
+use endpoint_grpc::pb::entry_service_server::EntryService;
+use endpoint_grpc::pb::journal_service_server::JournalService;
+use endpoint_grpc::pb::{
+  account_command, entry_command, journal_command, AccountCommand, AccountCommandCreate,
+  AccountType, EntryCommand, EntryCommandCreate, EntryCommandDelete, EntryCommandUpdate, EntryItem,
+  EntryType, JournalCommand, JournalCommandCreate, JournalCommandUpdate,
+};
+use std::sync::Arc;
+use test_suite::{create_user, request};
+use tonic::Code;
+
+#[tokio::test]
+pub async fn test_commands() -> anyhow::Result<()> {
+  let db = Arc::new(test_suite::init().await?);
+  let alice = create_user(db.as_ref(), "Alice").await?;
+  let bob = create_user(db.as_ref(), "Bob").await?;
+
+  let journals = JournalServiceImpl { db: db.clone() };
+  let command = |command| JournalCommand { command: Some(command) };
+  let create = JournalCommandCreate {
+    name: "Alice's Journal".to_string(),
+    description: String::default(),
+    unit: "USD".to_string(),
+    tags: vec!["personal".to_string()],
+  };
+  let journal = journals
+    .handle(request(&alice, command(journal_command::Command::Create(create.clone()))))
+    .await?
+    .into_inner()
+    .values
+    .remove(0);
+  assert_eq!("Alice's Journal", journal.name);
+
+  let status = journals
+    .handle(request(
+      &alice,
+      command(journal_command::Command::Create(JournalCommandCreate {
+        name: "a".repeat(MAX_NAME_LENGTH + 1),
+        ..create
+      })),
+    ))
+    .await
+    .unwrap_err();
+  assert_eq!(Code::InvalidArgument, status.code());
+  let status =
+    journals.handle(request(&alice, JournalCommand { command: None })).await.unwrap_err();
+  assert_eq!(Code::InvalidArgument, status.code());
+
+  let update = JournalCommandUpdate {
+    id: journal.id.clone(),
+    name: "Shared Journal".to_string(),
+    expected_version: Some(journal.version),
+    ..Default::default()
+  };
+  let status = journals
+    .handle(request(&bob, command(journal_command::Command::Update(update.clone()))))
+    .await
+    .unwrap_err();
+  assert_eq!(Code::PermissionDenied, status.code());
+  journals
+    .handle(request(&alice, command(journal_command::Command::Update(update.clone()))))
+    .await?;
+  let status = journals
+    .handle(request(&alice, command(journal_command::Command::Update(update))))
+    .await
+    .unwrap_err();
+  assert_eq!(Code::Aborted, status.code());
+
+  let accounts = AccountServiceImpl { db: db.clone() };
+  let mut ids = Vec::new();
+  for name in ["Assets::Cash", "Income::Salary"] {
+    let account = accounts
+      .handle(request(
+        &alice,
+        AccountCommand {
+          command: Some(account_command::Command::Create(AccountCommandCreate {
+            journal_id: journal.id.clone(),
+            name: name.to_string(),
+            unit: "USD".to_string(),
+            r#type: AccountType::Asset.into(),
+            ..Default::default()
+          })),
+        },
+      ))
+      .await?
+      .into_inner()
+      .values
+      .remove(0);
+    ids.push(account.id);
+  }
+
+  let entries = EntryServiceImpl { db: db.clone() };
+  let items = ids
+    .iter()
+    .map(|account| EntryItem {
+      account: account.clone(),
+      amount: "12.5".to_string(),
+      price: "1".to_string(),
+    })
+    .collect::<Vec<_>>();
+  let create = EntryCommandCreate {
+    journal_id: journal.id.clone(),
+    name: "Payday".to_string(),
+    r#type: EntryType::Record.into(),
+    date: "2023-01-01".to_string(),
+    items,
+    ..Default::default()
+  };
+  let status = entries
+    .handle(request(
+      &alice,
+      EntryCommand {
+        command: Some(entry_command::Command::Create(EntryCommandCreate {
+          date: "January".to_string(),
+          ..create.clone()
+        })),
+      },
+    ))
+    .await
+    .unwrap_err();
+  assert_eq!(Code::InvalidArgument, status.code());
+
+  let entry = entries
+    .handle(request(&alice, EntryCommand { command: Some(entry_command::Command::Create(create)) }))
+    .await?
+    .into_inner()
+    .values
+    .remove(0);
+  assert_eq!(2, entry.items.len());
+  assert!(entry.state.is_some());
+
+  let entry = entries
+    .handle(request(
+      &alice,
+      EntryCommand {
+        command: Some(entry_command::Command::Update(EntryCommandUpdate {
+          id: entry.id.clone(),
+          name: "Bonus Payment".to_string(),
+          date: Some("2023-02-01".to_string()),
+          expected_version: Some(entry.version),
+          ..Default::default()
+        })),
+      },
+    ))
+    .await?
+    .into_inner()
+    .values
+    .remove(0);
+  assert_eq!(("Bonus Payment", "2023-02-01"), (entry.name.as_str(), entry.date.as_str()));
+
+  let delete = EntryCommand {
+    command: Some(entry_command::Command::Delete(EntryCommandDelete {
+      id: vec![entry.id.clone()],
+      ..Default::default()
+    })),
+  };
+  let status = entries.handle(request(&bob, delete.clone())).await.unwrap_err();
+  assert_eq!(Code::PermissionDenied, status.code());
+  entries.handle(request(&alice, delete)).await?;
+  let status = entries.find_by_id(request(&alice, entry.id)).await.unwrap_err();
+  assert_eq!(Code::NotFound, status.code());
+
+  Ok(())
+}
@@ -0,0 +1,114 @@
+use backend_core::actor::Actor;
+use backend_core::entity::{account, entry, journal, user, ReadRoot};
+use endpoint_grpc::account::AccountServiceImpl;
+use endpoint_grpc::entry::EntryServiceImpl;
+use endpoint_grpc::hierarchy_report::HierarchyReportServiceImpl;
+use endpoint_grpc::journal::JournalServiceImpl;
+use endpoint_grpc::pb::account_service_server::AccountService;
+use endpoint_grpc::pb::entry_service_server::EntryService;
+use endpoint_grpc::pb::hierarchy_report_service_server::HierarchyReportService;
+use endpoint_grpc::pb::journal_service_server::JournalService;
+use endpoint_grpc::pb::{
+  AccountQuery, AccountType, EntryQuery, EntryType, HierarchyReportQuery, JournalQuery,
+};
+use std::collections::HashSet;
+use std::sync::Arc;
+use test_suite::request;
+use tonic::{Code, Request};
+
+#[tokio::test]
+pub async fn test_services() -> anyhow::Result<()> {
+  let db = Arc::new(test_suite::init().await?);
+  let system = Actor::system("tester");
+  let journal = journal::Root::find_one(db.as_ref(), None).await?.unwrap();
+
+  let service = JournalServiceImpl { db: db.clone() };
+  let journals = service.find_all(request(&system, JournalQuery::default())).await?.into_inner();
+  assert_eq!(3, journals.values.len());
+  let found = service.find_by_id(request(&system, journal.id.to_string())).await?.into_inner();
+  assert_eq!(journal.name, found.name);
+  let status = service.find_by_id(request(&system, "unknown".to_string())).await.unwrap_err();
+  assert_eq!(Code::InvalidArgument, status.code());
+  let status = service.find_by_id(Request::new(journal.id.to_string())).await.unwrap_err();
+  assert_eq!(Code::Unauthenticated, status.code());
+
+  let service = AccountServiceImpl { db: db.clone() };
+  let query = AccountQuery {
+    journal_id: vec![journal.id.to_string()],
+    r#type: Some(AccountType::Asset.into()),
+    ..Default::default()
+  };
+  let accounts = service.find_all(request(&system, query.clone())).await?.into_inner();
+  let expected = account::Root::find_all(
+    db.as_ref(),
+    Some(account::Query {
+      journal_id: HashSet::from_iter([journal.id]),
+      typ: Some(account::Type::Asset),
+      ..Default::default()
+    }),
+    None,
+    None,
+  )
+  .await?;
+  assert_eq!(expected.len(), accounts.values.len());
+  assert!(accounts.values.iter().all(|account| account.r#type == AccountType::Asset as i32));
+  let status = service
+    .find_all(request(&system, AccountQuery { r#type: Some(42), ..query }))
+    .await
+    .unwrap_err();
+  assert_eq!(Code::InvalidArgument, status.code());
+
+  let service = EntryServiceImpl { db: db.clone() };
+  let query = EntryQuery {
+    journal_id: vec![journal.id.to_string()],
+    r#type: Some(EntryType::Check.into()),
+    ..Default::default()
+  };
+  let entries = service.find_all(request(&system, query)).await?.into_inner();
+  assert!(entries.values.iter().all(|entry| entry.state.is_none() && !entry.states.is_empty()));
+  let record = entry::Root::find_one(
+    db.as_ref(),
+    Some(entry::Query { typ: Some(entry::Type::Record), ..Default::default() }),
+  )
+  .await?
+  .unwrap();
+  let found = service.find_by_id(request(&system, record.id.to_string())).await?.into_inner();
+  assert_eq!(record.items.len(), found.items.len());
+  assert!(found.state.is_some());
+
+  let service = HierarchyReportServiceImpl { db: db.clone() };
+  let query = HierarchyReportQuery {
+    journal_id: vec![journal.id.to_string()],
+    max_depth: Some(1),
+    ..Default::default()
+  };
+  let reports = service.find_all(request(&system, query.clone())).await?.into_inner();
+  assert!(!reports.values.is_empty());
+  let found =
+    service.find_by_id(request(&system, reports.values[0].id.clone())).await?.into_inner();
+  assert_eq!(reports.values[0].value, found.value);
+
+  // The users can only read the journals they are members of
+  let stranger = user::Root::handle(
+    db.as_ref(),
+    &system,
+    user::Command::Create(user::CommandCreate { name: "Stranger".to_string() }),
+  )
+  .await?
+  .remove(0);
+  let stranger = Actor::User(stranger.id);
+  let journals = JournalServiceImpl { db: db.clone() }
+    .find_all(request(&stranger, JournalQuery::default()))
+    .await?
+    .into_inner();
+  assert!(journals.values.is_empty());
+  let reports = service.find_all(request(&stranger, query)).await?.into_inner();
+  assert!(reports.values.is_empty());
+  let status = EntryServiceImpl { db: db.clone() }
+    .find_by_id(request(&stranger, record.id.to_string()))
+    .await
+    .unwrap_err();
+  assert_eq!(Code::NotFound, status.code());
+
+  Ok(())
+}
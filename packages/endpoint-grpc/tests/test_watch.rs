@@ -0,0 +1,224 @@
+use backend_core::entity::{account, journal};
+use endpoint_grpc::account::AccountServiceImpl;
+use endpoint_grpc::change::ChangeServiceImpl;
+use endpoint_grpc::journal::JournalServiceImpl;
+use endpoint_grpc::pb::account_service_server::AccountService;
+use endpoint_grpc::pb::change_event::Value;
+use endpoint_grpc::pb::change_service_server::ChangeService;
+use endpoint_grpc::pb::journal_service_server::JournalService;
+use endpoint_grpc::pb::{
+  account_command, journal_command, AccountCommand, AccountCommandCreate, AccountType, ChangeEvent,
+  ChangeType, JournalCommand, JournalCommandCreate, JournalCommandDelete, JournalCommandUpdate,
+  WatchQuery,
+};
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use std::sync::Arc;
+use std::time::Duration;
+use test_suite::{create_user, request};
+use tonic::{Code, Status};
+
+async fn next(
+  stream: &mut BoxStream<'static, Result<ChangeEvent, Status>>,
+) -> anyhow::Result<Option<ChangeEvent>> {
+  match tokio::time::timeout(Duration::from_millis(500), stream.next()).await {
+    Ok(event) => Ok(event.transpose()?),
+    Err(_) => Ok(None),
+  }
+}
+
+#[tokio::test]
+pub async fn test_watch() -> anyhow::Result<()> {
+  let db = Arc::new(test_suite::init().await?);
+  let alice = create_user(db.as_ref(), "Alice").await?;
+  let bob = create_user(db.as_ref(), "Bob").await?;
+
+  let journals = JournalServiceImpl { db: db.clone() };
+  let journal = journals
+    .handle(request(
+      &alice,
+      JournalCommand {
+        command: Some(journal_command::Command::Create(JournalCommandCreate {
+          name: "Watched Journal".to_string(),
+          unit: "USD".to_string(),
+          ..Default::default()
+        })),
+      },
+    ))
+    .await?
+    .into_inner()
+    .values
+    .remove(0);
+
+  let service = ChangeServiceImpl { db: db.clone(), interval: Duration::from_millis(10) };
+  let query = WatchQuery { journal_id: vec![journal.id.clone()], ..Default::default() };
+  let mut watched = service.watch(request(&alice, query.clone())).await?.into_inner();
+  let mut unrelated = service.watch(request(&bob, WatchQuery::default())).await?.into_inner();
+
+  let account = AccountServiceImpl { db: db.clone() }
+    .handle(request(
+      &alice,
+      AccountCommand {
+        command: Some(account_command::Command::Create(AccountCommandCreate {
+          journal_id: journal.id.clone(),
+          name: "Assets::Cash".to_string(),
+          unit: "USD".to_string(),
+          r#type: AccountType::Asset.into(),
+          ..Default::default()
+        })),
+      },
+    ))
+    .await?
+    .into_inner()
+    .values
+    .remove(0);
+  journals
+    .handle(request(
+      &alice,
+      JournalCommand {
+        command: Some(journal_command::Command::Update(JournalCommandUpdate {
+          id: journal.id.clone(),
+          name: "Renamed Journal".to_string(),
+          ..Default::default()
+        })),
+      },
+    ))
+    .await?;
+
+  let created = next(&mut watched).await?.unwrap();
+  assert_eq!(ChangeType::Created as i32, created.r#type);
+  assert_eq!(
+    (account::TYPE, account.id.as_str()),
+    (created.entity.as_str(), created.entity_id.as_str())
+  );
+  assert!(matches!(&created.value, Some(Value::Account(value)) if value.name == "Assets::Cash"));
+  let updated = next(&mut watched).await?.unwrap();
+  assert_eq!(ChangeType::Updated as i32, updated.r#type);
+  assert_eq!(journal::TYPE, updated.entity);
+  assert!(matches!(&updated.value, Some(Value::Journal(value)) if value.name == "Renamed Journal"));
+  assert_eq!(None, next(&mut watched).await?);
+  assert_eq!(None, next(&mut unrelated).await?);
+
+  // Reconnecting clients catch up with the changes after the last events received
+  let mut resumed = service
+    .watch(request(&alice, WatchQuery { resume_token: created.resume_token, ..query.clone() }))
+    .await?
+    .into_inner();
+  assert_eq!(Some(updated), next(&mut resumed).await?);
+
+  // The deletions are sent with the accounts cascaded, after the memberships are deleted
+  journals
+    .handle(request(
+      &alice,
+      JournalCommand {
+        command: Some(journal_command::Command::Delete(JournalCommandDelete {
+          id: vec![journal.id.clone()],
+          ..Default::default()
+        })),
+      },
+    ))
+    .await?;
+  let deleted = next(&mut watched).await?.unwrap();
+  assert_eq!(ChangeType::Deleted as i32, deleted.r#type);
+  assert_eq!(
+    (account::TYPE, account.id.as_str()),
+    (deleted.entity.as_str(), deleted.entity_id.as_str())
+  );
+  let deleted = next(&mut watched).await?.unwrap();
+  assert_eq!(ChangeType::Deleted as i32, deleted.r#type);
+  assert_eq!(
+    (journal::TYPE, journal.id.as_str()),
+    (deleted.entity.as_str(), deleted.entity_id.as_str())
+  );
+  assert_eq!(None, deleted.value);
+  assert_eq!(None, next(&mut watched).await?);
+  assert_eq!(None, next(&mut unrelated).await?);
+
+  let status = service
+    .watch(request(&alice, WatchQuery { resume_token: "unknown".to_string(), ..query }))
+    .await
+    .err()
+    .unwrap();
+  assert_eq!(Code::InvalidArgument, status.code());
+
+  Ok(())
+}
+
+#[tokio::test]
+pub async fn test_watch_resume_deleted() -> anyhow::Result<()> {
+  let db = Arc::new(test_suite::init().await?);
+  let alice = create_user(db.as_ref(), "Alice").await?;
+  let bob = create_user(db.as_ref(), "Bob").await?;
+
+  let journals = JournalServiceImpl { db: db.clone() };
+  let journal = journals
+    .handle(request(
+      &alice,
+      JournalCommand {
+        command: Some(journal_command::Command::Create(JournalCommandCreate {
+          name: "Deleted Journal".to_string(),
+          unit: "USD".to_string(),
+          ..Default::default()
+        })),
+      },
+    ))
+    .await?
+    .into_inner()
+    .values
+    .remove(0);
+
+  let service = ChangeServiceImpl { db: db.clone(), interval: Duration::from_millis(10) };
+  let mut watched = service.watch(request(&alice, WatchQuery::default())).await?.into_inner();
+  let account = AccountServiceImpl { db: db.clone() }
+    .handle(request(
+      &alice,
+      AccountCommand {
+        command: Some(account_command::Command::Create(AccountCommandCreate {
+          journal_id: journal.id.clone(),
+          name: "Assets::Cash".to_string(),
+          unit: "USD".to_string(),
+          r#type: AccountType::Asset.into(),
+          ..Default::default()
+        })),
+      },
+    ))
+    .await?
+    .into_inner()
+    .values
+    .remove(0);
+  let created = next(&mut watched).await?.unwrap();
+  assert_eq!(account.id, created.entity_id);
+  drop(watched);
+
+  // Deleted while the client is disconnected, along with the memberships
+  journals
+    .handle(request(
+      &alice,
+      JournalCommand {
+        command: Some(journal_command::Command::Delete(JournalCommandDelete {
+          id: vec![journal.id.clone()],
+          ..Default::default()
+        })),
+      },
+    ))
+    .await?;
+
+  let query = WatchQuery { resume_token: created.resume_token, ..Default::default() };
+  let mut resumed = service.watch(request(&alice, query.clone())).await?.into_inner();
+  let deleted = next(&mut resumed).await?.unwrap();
+  assert_eq!(
+    (ChangeType::Deleted as i32, account::TYPE, account.id.as_str()),
+    (deleted.r#type, deleted.entity.as_str(), deleted.entity_id.as_str())
+  );
+  let deleted = next(&mut resumed).await?.unwrap();
+  assert_eq!(
+    (ChangeType::Deleted as i32, journal::TYPE, journal.id.as_str()),
+    (deleted.r#type, deleted.entity.as_str(), deleted.entity_id.as_str())
+  );
+  assert_eq!(None, next(&mut resumed).await?);
+
+  let mut unrelated = service.watch(request(&bob, query)).await?.into_inner();
+  assert_eq!(None, next(&mut unrelated).await?);
+
+  Ok(())
+}
@@ -1,12 +1,38 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20220101_000001_create_table;
+mod m20220101_000002_create_table_account_balances;
+mod m20220101_000003_create_table_reconciliations;
+mod m20220101_000004_create_table_csv_profiles;
+mod m20220101_000005_create_table_imported_transactions;
+mod m20220101_000006_create_table_rules;
+mod m20220101_000007_create_table_payees;
+mod m20220101_000008_create_table_histories;
+mod m20220101_000009_add_column_versions;
+mod m20220101_000010_add_column_timestamps;
+mod m20220101_000011_create_table_memberships;
+mod m20220101_000012_create_table_api_tokens;
+mod m20220101_000013_add_column_history_sequences;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
   fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-    vec![Box::new(m20220101_000001_create_table::Migration)]
+    vec![
+      Box::new(m20220101_000001_create_table::Migration),
+      Box::new(m20220101_000002_create_table_account_balances::Migration),
+      Box::new(m20220101_000003_create_table_reconciliations::Migration),
+      Box::new(m20220101_000004_create_table_csv_profiles::Migration),
+      Box::new(m20220101_000005_create_table_imported_transactions::Migration),
+      Box::new(m20220101_000006_create_table_rules::Migration),
+      Box::new(m20220101_000007_create_table_payees::Migration),
+      Box::new(m20220101_000008_create_table_histories::Migration),
+      Box::new(m20220101_000009_add_column_versions::Migration),
+      Box::new(m20220101_000010_add_column_timestamps::Migration),
+      Box::new(m20220101_000011_create_table_memberships::Migration),
+      Box::new(m20220101_000012_create_table_api_tokens::Migration),
+      Box::new(m20220101_000013_add_column_history_sequences::Migration),
+    ]
   }
 }
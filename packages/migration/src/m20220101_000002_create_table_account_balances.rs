@@ -0,0 +1,112 @@
+use backend_core::entity::{account, account_balance};
+use sea_orm_migration::prelude::*;
+
+/// The running totals of the records by account and date, as the tables are at this migration. Like
+/// `account_balance::rebuild`, the sums are done in SQLite and read back into `Decimal`
+const BACKFILL: &str = r#"
+INSERT INTO account_balances (account_id, date, amount)
+SELECT account_id, date, SUM(value) OVER (PARTITION BY account_id ORDER BY date)
+FROM (
+  SELECT entry_items.account_id, entries.date, SUM(entry_items.amount * entry_items.price) AS value
+  FROM entry_items
+  INNER JOIN entries ON entries.id = entry_items.entry_id
+  WHERE entries.type = 'R'
+  GROUP BY entry_items.account_id, entries.date
+)
+"#;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    let table = Table::create()
+      .table(account_balance::Entity)
+      .col(ColumnDef::new(account_balance::Column::AccountId).uuid().not_null())
+      .col(ColumnDef::new(account_balance::Column::Date).date().not_null())
+      .col(ColumnDef::new(account_balance::Column::Amount).decimal().not_null())
+      .primary_key(
+        Index::create()
+          .name("pk-account_balances")
+          .col(account_balance::Column::AccountId)
+          .col(account_balance::Column::Date)
+          .primary(),
+      )
+      .foreign_key(
+        ForeignKeyCreateStatement::new()
+          .name("fk-account_balances-account_id")
+          .from_tbl(account_balance::Entity)
+          .from_col(account_balance::Column::AccountId)
+          .to_tbl(account::Entity)
+          .to_col(account::Column::Id)
+          .on_delete(ForeignKeyAction::Cascade)
+          .on_update(ForeignKeyAction::Cascade),
+      )
+      .to_owned();
+    manager.create_table(table).await?;
+
+    // Fill in the balances of the existing records, in plain SQL so that later changes of the
+    // entities do not change this migration
+    manager.get_connection().execute_unprepared(BACKFILL).await?;
+    Ok(())
+  }
+
+  async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    manager.drop_table(Table::drop().table(account_balance::Entity).to_owned()).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::Migrator;
+  use sea_orm_migration::prelude::*;
+  use sea_orm_migration::sea_orm::{ConnectionTrait, Database, Statement};
+
+  #[tokio::test]
+  async fn test_backfill() -> Result<(), DbErr> {
+    let db = Database::connect("sqlite::memory:").await?;
+    Migrator::up(&db, Some(1)).await?;
+    db.execute_unprepared(
+      r#"
+      INSERT INTO journals (id, name, description, unit) VALUES ('j', 'Journal', '', 'CNY');
+      INSERT INTO accounts (id, journal_id, name, description, unit, type)
+        VALUES ('a', 'j', 'Assets::Cash', '', 'CNY', 'A'), ('b', 'j', 'Income::Salary', '', 'CNY', 'I');
+      INSERT INTO entries (id, journal_id, name, description, type, date)
+        VALUES ('e1', 'j', 'Entry 1', '', 'R', '2024-01-01'), ('e2', 'j', 'Entry 2', '', 'R', '2024-01-03'),
+          ('e3', 'j', 'Entry 3', '', 'R', '2024-01-03'), ('c1', 'j', 'Check 1', '', 'C', '2024-01-02');
+      INSERT INTO entry_items (entry_id, account_id, amount, price)
+        VALUES ('e1', 'a', 10, 1), ('e1', 'b', -10, 1), ('e2', 'a', 5, 2), ('e2', 'b', -5, 2),
+          ('e3', 'a', -3, 1), ('e3', 'b', 3, 1), ('c1', 'a', 100, 1);
+      "#,
+    )
+    .await?;
+
+    Migrator::up(&db, Some(1)).await?;
+    let balances = db
+      .query_all(Statement::from_string(
+        db.get_database_backend(),
+        "SELECT account_id, date, amount FROM account_balances ORDER BY account_id, date",
+      ))
+      .await?
+      .into_iter()
+      .map(|row| {
+        let account_id: String = row.try_get("", "account_id")?;
+        let date: String = row.try_get("", "date")?;
+        let amount: f64 = row.try_get("", "amount")?;
+        Ok((account_id, date, amount))
+      })
+      .collect::<Result<Vec<_>, DbErr>>()?;
+    assert_eq!(
+      vec![
+        ("a".to_string(), "2024-01-01".to_string(), 10.0),
+        ("a".to_string(), "2024-01-03".to_string(), 17.0),
+        ("b".to_string(), "2024-01-01".to_string(), -10.0),
+        ("b".to_string(), "2024-01-03".to_string(), -17.0),
+      ],
+      balances
+    );
+
+    Ok(())
+  }
+}
@@ -0,0 +1,117 @@
+use backend_core::entity::{account, entry, reconciliation, reconciliation_entry};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+impl Migration {
+  async fn create_table_reconciliations(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let table = Table::create()
+      .table(reconciliation::Entity)
+      .col(ColumnDef::new(reconciliation::Column::Id).uuid().primary_key().not_null())
+      .col(ColumnDef::new(reconciliation::Column::AccountId).uuid().not_null())
+      .col(ColumnDef::new(reconciliation::Column::CheckId).uuid().not_null().unique_key())
+      .col(ColumnDef::new(reconciliation::Column::Date).date().not_null())
+      .col(ColumnDef::new(reconciliation::Column::Balance).decimal().not_null())
+      .foreign_key(
+        ForeignKeyCreateStatement::new()
+          .name("fk-reconciliations-account_id")
+          .from_tbl(reconciliation::Entity)
+          .from_col(reconciliation::Column::AccountId)
+          .to_tbl(account::Entity)
+          .to_col(account::Column::Id)
+          .on_delete(ForeignKeyAction::Cascade)
+          .on_update(ForeignKeyAction::Cascade),
+      )
+      .foreign_key(
+        ForeignKeyCreateStatement::new()
+          .name("fk-reconciliations-check_id")
+          .from_tbl(reconciliation::Entity)
+          .from_col(reconciliation::Column::CheckId)
+          .to_tbl(entry::Entity)
+          .to_col(entry::Column::Id)
+          .on_delete(ForeignKeyAction::Cascade)
+          .on_update(ForeignKeyAction::Cascade),
+      )
+      .to_owned();
+    manager.create_table(table).await?;
+
+    let index = Index::create()
+      .name("idx-reconciliations-account_id")
+      .table(reconciliation::Entity)
+      .col(reconciliation::Column::AccountId)
+      .to_owned();
+    manager.create_index(index).await?;
+
+    let index = Index::create()
+      .name("idx-reconciliations-date")
+      .table(reconciliation::Entity)
+      .col(reconciliation::Column::Date)
+      .to_owned();
+    manager.create_index(index).await?;
+
+    Ok(())
+  }
+
+  async fn create_table_reconciliation_entries(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let table = Table::create()
+      .table(reconciliation_entry::Entity)
+      .col(ColumnDef::new(reconciliation_entry::Column::ReconciliationId).uuid().not_null())
+      .col(ColumnDef::new(reconciliation_entry::Column::EntryId).uuid().not_null())
+      .primary_key(
+        Index::create()
+          .name("pk-reconciliation_entries")
+          .col(reconciliation_entry::Column::ReconciliationId)
+          .col(reconciliation_entry::Column::EntryId)
+          .primary(),
+      )
+      .foreign_key(
+        ForeignKeyCreateStatement::new()
+          .name("fk-reconciliation_entries-reconciliation_id")
+          .from_tbl(reconciliation_entry::Entity)
+          .from_col(reconciliation_entry::Column::ReconciliationId)
+          .to_tbl(reconciliation::Entity)
+          .to_col(reconciliation::Column::Id)
+          .on_delete(ForeignKeyAction::Cascade)
+          .on_update(ForeignKeyAction::Cascade),
+      )
+      .foreign_key(
+        ForeignKeyCreateStatement::new()
+          .name("fk-reconciliation_entries-entry_id")
+          .from_tbl(reconciliation_entry::Entity)
+          .from_col(reconciliation_entry::Column::EntryId)
+          .to_tbl(entry::Entity)
+          .to_col(entry::Column::Id)
+          .on_delete(ForeignKeyAction::Cascade)
+          .on_update(ForeignKeyAction::Cascade),
+      )
+      .to_owned();
+    manager.create_table(table).await?;
+
+    // An entry can only be reconciled once
+    let index = Index::create()
+      .name("idx-reconciliation_entries-entry_id")
+      .table(reconciliation_entry::Entity)
+      .col(reconciliation_entry::Column::EntryId)
+      .unique()
+      .to_owned();
+    manager.create_index(index).await?;
+
+    Ok(())
+  }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    Migration::create_table_reconciliations(manager).await?;
+    Migration::create_table_reconciliation_entries(manager).await?;
+
+    Ok(())
+  }
+
+  async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    manager.drop_table(Table::drop().table(reconciliation_entry::Entity).to_owned()).await?;
+    manager.drop_table(Table::drop().table(reconciliation::Entity).to_owned()).await
+  }
+}
@@ -0,0 +1,71 @@
+use backend_core::entity::{account, csv_profile, journal};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    let table = Table::create()
+      .table(csv_profile::Entity)
+      .col(ColumnDef::new(csv_profile::Column::Id).uuid().primary_key().not_null())
+      .col(ColumnDef::new(csv_profile::Column::JournalId).uuid().not_null())
+      .col(ColumnDef::new(csv_profile::Column::Name).string().not_null())
+      .col(ColumnDef::new(csv_profile::Column::Delimiter).string().not_null())
+      .col(ColumnDef::new(csv_profile::Column::DateColumn).string().not_null())
+      .col(ColumnDef::new(csv_profile::Column::DateFormat).string().not_null())
+      .col(ColumnDef::new(csv_profile::Column::AmountColumn).string())
+      .col(ColumnDef::new(csv_profile::Column::DebitColumn).string())
+      .col(ColumnDef::new(csv_profile::Column::CreditColumn).string())
+      .col(ColumnDef::new(csv_profile::Column::DescriptionColumn).string())
+      .col(ColumnDef::new(csv_profile::Column::DecimalSeparator).string().not_null())
+      .col(ColumnDef::new(csv_profile::Column::AccountId).uuid().not_null())
+      .col(ColumnDef::new(csv_profile::Column::CounterAccountId).uuid().not_null())
+      .foreign_key(
+        ForeignKeyCreateStatement::new()
+          .name("fk-csv_profiles-journal_id")
+          .from_tbl(csv_profile::Entity)
+          .from_col(csv_profile::Column::JournalId)
+          .to_tbl(journal::Entity)
+          .to_col(journal::Column::Id)
+          .on_delete(ForeignKeyAction::Cascade)
+          .on_update(ForeignKeyAction::Cascade),
+      )
+      .foreign_key(
+        ForeignKeyCreateStatement::new()
+          .name("fk-csv_profiles-account_id")
+          .from_tbl(csv_profile::Entity)
+          .from_col(csv_profile::Column::AccountId)
+          .to_tbl(account::Entity)
+          .to_col(account::Column::Id)
+          .on_delete(ForeignKeyAction::Cascade)
+          .on_update(ForeignKeyAction::Cascade),
+      )
+      .foreign_key(
+        ForeignKeyCreateStatement::new()
+          .name("fk-csv_profiles-counter_account_id")
+          .from_tbl(csv_profile::Entity)
+          .from_col(csv_profile::Column::CounterAccountId)
+          .to_tbl(account::Entity)
+          .to_col(account::Column::Id)
+          .on_delete(ForeignKeyAction::Cascade)
+          .on_update(ForeignKeyAction::Cascade),
+      )
+      .to_owned();
+    manager.create_table(table).await?;
+
+    let index = Index::create()
+      .name("idx-csv_profiles-journal_id-name")
+      .table(csv_profile::Entity)
+      .col(csv_profile::Column::JournalId)
+      .col(csv_profile::Column::Name)
+      .unique()
+      .to_owned();
+    manager.create_index(index).await
+  }
+
+  async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    manager.drop_table(Table::drop().table(csv_profile::Entity).to_owned()).await
+  }
+}
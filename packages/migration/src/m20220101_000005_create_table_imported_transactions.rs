@@ -0,0 +1,56 @@
+use backend_core::entity::{account, entry, imported_transaction};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    let table = Table::create()
+      .table(imported_transaction::Entity)
+      .col(ColumnDef::new(imported_transaction::Column::AccountId).uuid().not_null())
+      .col(ColumnDef::new(imported_transaction::Column::ExternalId).string().not_null())
+      .col(ColumnDef::new(imported_transaction::Column::EntryId).uuid().not_null())
+      .primary_key(
+        Index::create()
+          .name("pk-imported_transactions")
+          .col(imported_transaction::Column::AccountId)
+          .col(imported_transaction::Column::ExternalId)
+          .primary(),
+      )
+      .foreign_key(
+        ForeignKeyCreateStatement::new()
+          .name("fk-imported_transactions-account_id")
+          .from_tbl(imported_transaction::Entity)
+          .from_col(imported_transaction::Column::AccountId)
+          .to_tbl(account::Entity)
+          .to_col(account::Column::Id)
+          .on_delete(ForeignKeyAction::Cascade)
+          .on_update(ForeignKeyAction::Cascade),
+      )
+      .foreign_key(
+        ForeignKeyCreateStatement::new()
+          .name("fk-imported_transactions-entry_id")
+          .from_tbl(imported_transaction::Entity)
+          .from_col(imported_transaction::Column::EntryId)
+          .to_tbl(entry::Entity)
+          .to_col(entry::Column::Id)
+          .on_delete(ForeignKeyAction::Cascade)
+          .on_update(ForeignKeyAction::Cascade),
+      )
+      .to_owned();
+    manager.create_table(table).await?;
+
+    let index = Index::create()
+      .name("idx-imported_transactions-entry_id")
+      .table(imported_transaction::Entity)
+      .col(imported_transaction::Column::EntryId)
+      .to_owned();
+    manager.create_index(index).await
+  }
+
+  async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    manager.drop_table(Table::drop().table(imported_transaction::Entity).to_owned()).await
+  }
+}
@@ -0,0 +1,95 @@
+use backend_core::entity::{account, journal, rule, rule_tag, MAX_SHORT_TEXT_LENGTH};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    let table = Table::create()
+      .table(rule::Entity)
+      .col(ColumnDef::new(rule::Column::Id).uuid().primary_key().not_null())
+      .col(ColumnDef::new(rule::Column::JournalId).uuid().not_null())
+      .col(ColumnDef::new(rule::Column::Name).string().not_null())
+      .col(ColumnDef::new(rule::Column::NamePattern).string())
+      .col(ColumnDef::new(rule::Column::DescriptionPattern).string())
+      .col(ColumnDef::new(rule::Column::MinAmount).decimal())
+      .col(ColumnDef::new(rule::Column::MaxAmount).decimal())
+      .col(ColumnDef::new(rule::Column::AccountId).uuid())
+      .col(ColumnDef::new(rule::Column::CounterAccountId).uuid())
+      .col(ColumnDef::new(rule::Column::Rename).string())
+      .foreign_key(
+        ForeignKeyCreateStatement::new()
+          .name("fk-rules-journal_id")
+          .from_tbl(rule::Entity)
+          .from_col(rule::Column::JournalId)
+          .to_tbl(journal::Entity)
+          .to_col(journal::Column::Id)
+          .on_delete(ForeignKeyAction::Cascade)
+          .on_update(ForeignKeyAction::Cascade),
+      )
+      .foreign_key(
+        ForeignKeyCreateStatement::new()
+          .name("fk-rules-account_id")
+          .from_tbl(rule::Entity)
+          .from_col(rule::Column::AccountId)
+          .to_tbl(account::Entity)
+          .to_col(account::Column::Id)
+          .on_delete(ForeignKeyAction::Cascade)
+          .on_update(ForeignKeyAction::Cascade),
+      )
+      .foreign_key(
+        ForeignKeyCreateStatement::new()
+          .name("fk-rules-counter_account_id")
+          .from_tbl(rule::Entity)
+          .from_col(rule::Column::CounterAccountId)
+          .to_tbl(account::Entity)
+          .to_col(account::Column::Id)
+          .on_delete(ForeignKeyAction::Cascade)
+          .on_update(ForeignKeyAction::Cascade),
+      )
+      .to_owned();
+    manager.create_table(table).await?;
+
+    let index = Index::create()
+      .name("idx-rules-journal_id-name")
+      .table(rule::Entity)
+      .col(rule::Column::JournalId)
+      .col(rule::Column::Name)
+      .unique()
+      .to_owned();
+    manager.create_index(index).await?;
+
+    let table = Table::create()
+      .table(rule_tag::Entity)
+      .col(ColumnDef::new(rule_tag::Column::RuleId).uuid().not_null())
+      .col(
+        ColumnDef::new(rule_tag::Column::Tag).string_len(MAX_SHORT_TEXT_LENGTH as u32).not_null(),
+      )
+      .primary_key(
+        Index::create()
+          .name("pk-rule_tags")
+          .col(rule_tag::Column::RuleId)
+          .col(rule_tag::Column::Tag)
+          .primary(),
+      )
+      .foreign_key(
+        ForeignKeyCreateStatement::new()
+          .name("fk-rule_tags-rule_id")
+          .from_tbl(rule_tag::Entity)
+          .from_col(rule_tag::Column::RuleId)
+          .to_tbl(rule::Entity)
+          .to_col(rule::Column::Id)
+          .on_delete(ForeignKeyAction::Cascade)
+          .on_update(ForeignKeyAction::Cascade),
+      )
+      .to_owned();
+    manager.create_table(table).await
+  }
+
+  async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    manager.drop_table(Table::drop().table(rule_tag::Entity).to_owned()).await?;
+    manager.drop_table(Table::drop().table(rule::Entity).to_owned()).await
+  }
+}
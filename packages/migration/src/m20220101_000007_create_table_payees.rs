@@ -0,0 +1,149 @@
+use backend_core::entity::{
+  account, entry, journal, payee, payee_alias, payee_tag, MAX_NAME_LENGTH, MAX_SHORT_TEXT_LENGTH,
+};
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::EntityName;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+impl Migration {
+  async fn create_table_payees(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let table = Table::create()
+      .table(payee::Entity)
+      .col(ColumnDef::new(payee::Column::Id).uuid().primary_key().not_null())
+      .col(ColumnDef::new(payee::Column::JournalId).uuid().not_null())
+      .col(ColumnDef::new(payee::Column::Name).string_len(MAX_NAME_LENGTH as u32).not_null())
+      .col(ColumnDef::new(payee::Column::DefaultAccountId).uuid())
+      .foreign_key(
+        ForeignKeyCreateStatement::new()
+          .name("fk-payees-journal_id")
+          .from_tbl(payee::Entity)
+          .from_col(payee::Column::JournalId)
+          .to_tbl(journal::Entity)
+          .to_col(journal::Column::Id)
+          .on_delete(ForeignKeyAction::Cascade)
+          .on_update(ForeignKeyAction::Cascade),
+      )
+      .foreign_key(
+        ForeignKeyCreateStatement::new()
+          .name("fk-payees-default_account_id")
+          .from_tbl(payee::Entity)
+          .from_col(payee::Column::DefaultAccountId)
+          .to_tbl(account::Entity)
+          .to_col(account::Column::Id)
+          .on_delete(ForeignKeyAction::SetNull)
+          .on_update(ForeignKeyAction::Cascade),
+      )
+      .to_owned();
+    manager.create_table(table).await?;
+
+    let index = Index::create()
+      .name("idx-payees-journal_id-name")
+      .table(payee::Entity)
+      .col(payee::Column::JournalId)
+      .col(payee::Column::Name)
+      .unique()
+      .to_owned();
+    manager.create_index(index).await
+  }
+
+  async fn create_table_payee_aliases(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let table = Table::create()
+      .table(payee_alias::Entity)
+      .col(ColumnDef::new(payee_alias::Column::PayeeId).uuid().not_null())
+      .col(ColumnDef::new(payee_alias::Column::Alias).string_len(MAX_NAME_LENGTH as u32).not_null())
+      .primary_key(
+        Index::create()
+          .name("pk-payee_aliases")
+          .col(payee_alias::Column::PayeeId)
+          .col(payee_alias::Column::Alias)
+          .primary(),
+      )
+      .foreign_key(
+        ForeignKeyCreateStatement::new()
+          .name("fk-payee_aliases-payee_id")
+          .from_tbl(payee_alias::Entity)
+          .from_col(payee_alias::Column::PayeeId)
+          .to_tbl(payee::Entity)
+          .to_col(payee::Column::Id)
+          .on_delete(ForeignKeyAction::Cascade)
+          .on_update(ForeignKeyAction::Cascade),
+      )
+      .to_owned();
+    manager.create_table(table).await
+  }
+
+  async fn create_table_payee_tags(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let table = Table::create()
+      .table(payee_tag::Entity)
+      .col(ColumnDef::new(payee_tag::Column::PayeeId).uuid().not_null())
+      .col(
+        ColumnDef::new(payee_tag::Column::Tag).string_len(MAX_SHORT_TEXT_LENGTH as u32).not_null(),
+      )
+      .primary_key(
+        Index::create()
+          .name("pk-payee_tags")
+          .col(payee_tag::Column::PayeeId)
+          .col(payee_tag::Column::Tag)
+          .primary(),
+      )
+      .foreign_key(
+        ForeignKeyCreateStatement::new()
+          .name("fk-payee_tags-payee_id")
+          .from_tbl(payee_tag::Entity)
+          .from_col(payee_tag::Column::PayeeId)
+          .to_tbl(payee::Entity)
+          .to_col(payee::Column::Id)
+          .on_delete(ForeignKeyAction::Cascade)
+          .on_update(ForeignKeyAction::Cascade),
+      )
+      .to_owned();
+    manager.create_table(table).await
+  }
+
+  /// SQLite cannot add the foreign keys to the existing tables, so that the reference is part of
+  /// the column definition
+  async fn add_column_entry_payee_id(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let table = Table::alter()
+      .table(entry::Entity)
+      .add_column(ColumnDef::new(entry::Column::PayeeId).uuid().null().extra(format!(
+        "REFERENCES \"{}\" (\"{}\") ON DELETE SET NULL ON UPDATE CASCADE",
+        payee::Entity.table_name(),
+        payee::Column::Id.to_string()
+      )))
+      .to_owned();
+    manager.alter_table(table).await?;
+
+    let index = Index::create()
+      .name("idx-entries-payee_id")
+      .table(entry::Entity)
+      .col(entry::Column::PayeeId)
+      .to_owned();
+    manager.create_index(index).await
+  }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    Migration::create_table_payees(manager).await?;
+    Migration::create_table_payee_aliases(manager).await?;
+    Migration::create_table_payee_tags(manager).await?;
+    Migration::add_column_entry_payee_id(manager).await
+  }
+
+  async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    manager
+      .drop_index(Index::drop().name("idx-entries-payee_id").table(entry::Entity).to_owned())
+      .await?;
+    manager
+      .alter_table(
+        Table::alter().table(entry::Entity).drop_column(entry::Column::PayeeId).to_owned(),
+      )
+      .await?;
+    manager.drop_table(Table::drop().table(payee_tag::Entity).to_owned()).await?;
+    manager.drop_table(Table::drop().table(payee_alias::Entity).to_owned()).await?;
+    manager.drop_table(Table::drop().table(payee::Entity).to_owned()).await
+  }
+}
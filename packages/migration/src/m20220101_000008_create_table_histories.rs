@@ -0,0 +1,43 @@
+use backend_core::entity::history;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    let table = Table::create()
+      .table(history::Entity)
+      .col(ColumnDef::new(history::Column::Id).uuid().primary_key().not_null())
+      .col(ColumnDef::new(history::Column::CommandId).uuid().not_null())
+      .col(ColumnDef::new(history::Column::Entity).string().not_null())
+      .col(ColumnDef::new(history::Column::EntityId).uuid().not_null())
+      .col(ColumnDef::new(history::Column::Actor).string().not_null())
+      .col(ColumnDef::new(history::Column::Timestamp).timestamp_with_time_zone().not_null())
+      .col(ColumnDef::new(history::Column::Command).json().not_null())
+      .col(ColumnDef::new(history::Column::Before).json())
+      .col(ColumnDef::new(history::Column::After).json())
+      .to_owned();
+    manager.create_table(table).await?;
+
+    let index = Index::create()
+      .name("idx-histories-entity_id-timestamp")
+      .table(history::Entity)
+      .col(history::Column::EntityId)
+      .col(history::Column::Timestamp)
+      .to_owned();
+    manager.create_index(index).await?;
+
+    let index = Index::create()
+      .name("idx-histories-command_id")
+      .table(history::Entity)
+      .col(history::Column::CommandId)
+      .to_owned();
+    manager.create_index(index).await
+  }
+
+  async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    manager.drop_table(Table::drop().table(history::Entity).to_owned()).await
+  }
+}
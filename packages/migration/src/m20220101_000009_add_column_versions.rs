@@ -0,0 +1,41 @@
+use backend_core::entity::{account, entry, journal};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    let table = Table::alter()
+      .table(journal::Entity)
+      .add_column(ColumnDef::new(journal::Column::Version).integer().not_null().default(0))
+      .to_owned();
+    manager.alter_table(table).await?;
+
+    let table = Table::alter()
+      .table(account::Entity)
+      .add_column(ColumnDef::new(account::Column::Version).integer().not_null().default(0))
+      .to_owned();
+    manager.alter_table(table).await?;
+
+    let table = Table::alter()
+      .table(entry::Entity)
+      .add_column(ColumnDef::new(entry::Column::Version).integer().not_null().default(0))
+      .to_owned();
+    manager.alter_table(table).await
+  }
+
+  async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    let table = Table::alter().table(entry::Entity).drop_column(entry::Column::Version).to_owned();
+    manager.alter_table(table).await?;
+
+    let table =
+      Table::alter().table(account::Entity).drop_column(account::Column::Version).to_owned();
+    manager.alter_table(table).await?;
+
+    let table =
+      Table::alter().table(journal::Entity).drop_column(journal::Column::Version).to_owned();
+    manager.alter_table(table).await
+  }
+}
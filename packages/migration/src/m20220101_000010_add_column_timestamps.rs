@@ -0,0 +1,63 @@
+use backend_core::entity::{account, entry, journal};
+use chrono::{SecondsFormat, Utc};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    // The existing rows are taken as created and updated at the time migrated, in the same format
+    // as the ones saved
+    let now = Utc::now().to_rfc3339_opts(SecondsFormat::AutoSi, false);
+    add_column(manager, journal::Entity, journal::Column::CreatedAt, &now).await?;
+    add_column(manager, journal::Entity, journal::Column::UpdatedAt, &now).await?;
+    add_column(manager, account::Entity, account::Column::CreatedAt, &now).await?;
+    add_column(manager, account::Entity, account::Column::UpdatedAt, &now).await?;
+    add_column(manager, entry::Entity, entry::Column::CreatedAt, &now).await?;
+    add_column(manager, entry::Entity, entry::Column::UpdatedAt, &now).await?;
+
+    let index = Index::create()
+      .name("idx-entries-updated_at")
+      .table(entry::Entity)
+      .col(entry::Column::UpdatedAt)
+      .to_owned();
+    manager.create_index(index).await
+  }
+
+  async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    let index = Index::drop().name("idx-entries-updated_at").table(entry::Entity).to_owned();
+    manager.drop_index(index).await?;
+
+    drop_column(manager, entry::Entity, entry::Column::UpdatedAt).await?;
+    drop_column(manager, entry::Entity, entry::Column::CreatedAt).await?;
+    drop_column(manager, account::Entity, account::Column::UpdatedAt).await?;
+    drop_column(manager, account::Entity, account::Column::CreatedAt).await?;
+    drop_column(manager, journal::Entity, journal::Column::UpdatedAt).await?;
+    drop_column(manager, journal::Entity, journal::Column::CreatedAt).await
+  }
+}
+
+// Sqlite alters a column at a time
+async fn add_column(
+  manager: &SchemaManager<'_>,
+  table: impl IntoTableRef,
+  column: impl IntoIden,
+  default: &str,
+) -> Result<(), DbErr> {
+  let table = Table::alter()
+    .table(table)
+    .add_column(ColumnDef::new(column).timestamp_with_time_zone().not_null().default(default))
+    .to_owned();
+  manager.alter_table(table).await
+}
+
+async fn drop_column(
+  manager: &SchemaManager<'_>,
+  table: impl IntoTableRef,
+  column: impl IntoIden,
+) -> Result<(), DbErr> {
+  let table = Table::alter().table(table).drop_column(column).to_owned();
+  manager.alter_table(table).await
+}
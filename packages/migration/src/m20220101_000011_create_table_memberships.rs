@@ -0,0 +1,78 @@
+use backend_core::entity::{journal, membership, user, MAX_NAME_LENGTH};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+impl Migration {
+  async fn create_table_users(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let table = Table::create()
+      .table(user::Entity)
+      .col(ColumnDef::new(user::Column::Id).uuid().primary_key().not_null())
+      .col(
+        ColumnDef::new(user::Column::Name)
+          .string_len(MAX_NAME_LENGTH as u32)
+          .unique_key()
+          .not_null(),
+      )
+      .to_owned();
+    manager.create_table(table).await
+  }
+
+  async fn create_table_memberships(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let table = Table::create()
+      .table(membership::Entity)
+      .col(ColumnDef::new(membership::Column::JournalId).uuid().not_null())
+      .col(ColumnDef::new(membership::Column::UserId).uuid().not_null())
+      .col(ColumnDef::new(membership::Column::Role).string_len(1).not_null())
+      .primary_key(
+        Index::create()
+          .name("pk-memberships")
+          .col(membership::Column::JournalId)
+          .col(membership::Column::UserId)
+          .primary(),
+      )
+      .foreign_key(
+        ForeignKeyCreateStatement::new()
+          .name("fk-memberships-journal_id")
+          .from_tbl(membership::Entity)
+          .from_col(membership::Column::JournalId)
+          .to_tbl(journal::Entity)
+          .to_col(journal::Column::Id)
+          .on_delete(ForeignKeyAction::Cascade)
+          .on_update(ForeignKeyAction::Cascade),
+      )
+      .foreign_key(
+        ForeignKeyCreateStatement::new()
+          .name("fk-memberships-user_id")
+          .from_tbl(membership::Entity)
+          .from_col(membership::Column::UserId)
+          .to_tbl(user::Entity)
+          .to_col(user::Column::Id)
+          .on_delete(ForeignKeyAction::Cascade)
+          .on_update(ForeignKeyAction::Cascade),
+      )
+      .to_owned();
+    manager.create_table(table).await?;
+
+    let index = Index::create()
+      .name("idx-memberships-user_id")
+      .table(membership::Entity)
+      .col(membership::Column::UserId)
+      .to_owned();
+    manager.create_index(index).await
+  }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    Migration::create_table_users(manager).await?;
+    Migration::create_table_memberships(manager).await
+  }
+
+  async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    manager.drop_table(Table::drop().table(membership::Entity).to_owned()).await?;
+    manager.drop_table(Table::drop().table(user::Entity).to_owned()).await
+  }
+}
@@ -0,0 +1,41 @@
+use backend_core::entity::{api_token, user, MAX_NAME_LENGTH};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    let table = Table::create()
+      .table(api_token::Entity)
+      .col(ColumnDef::new(api_token::Column::Id).uuid().primary_key().not_null())
+      .col(ColumnDef::new(api_token::Column::UserId).uuid().not_null())
+      .col(ColumnDef::new(api_token::Column::Name).string_len(MAX_NAME_LENGTH as u32).not_null())
+      .col(ColumnDef::new(api_token::Column::Hash).string_len(64).unique_key().not_null())
+      .col(ColumnDef::new(api_token::Column::CreatedAt).timestamp_with_time_zone().not_null())
+      .foreign_key(
+        ForeignKeyCreateStatement::new()
+          .name("fk-api_tokens-user_id")
+          .from_tbl(api_token::Entity)
+          .from_col(api_token::Column::UserId)
+          .to_tbl(user::Entity)
+          .to_col(user::Column::Id)
+          .on_delete(ForeignKeyAction::Cascade)
+          .on_update(ForeignKeyAction::Cascade),
+      )
+      .to_owned();
+    manager.create_table(table).await?;
+
+    let index = Index::create()
+      .name("idx-api_tokens-user_id")
+      .table(api_token::Entity)
+      .col(api_token::Column::UserId)
+      .to_owned();
+    manager.create_index(index).await
+  }
+
+  async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    manager.drop_table(Table::drop().table(api_token::Entity).to_owned()).await
+  }
+}
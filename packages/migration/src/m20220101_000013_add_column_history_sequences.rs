@@ -0,0 +1,83 @@
+use backend_core::entity::history;
+use sea_orm_migration::prelude::*;
+
+// The existing records are numbered in the order they were read before, by the timestamps and
+// then the IDs
+const BACKFILL: &str = r#"
+UPDATE histories SET sequence = (
+  SELECT COUNT(*) FROM histories AS previous
+  WHERE previous.timestamp < histories.timestamp
+    OR (previous.timestamp = histories.timestamp AND previous.id <= histories.id)
+)
+"#;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+  async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    let table = Table::alter()
+      .table(history::Entity)
+      .add_column(ColumnDef::new(history::Column::Sequence).big_integer().not_null().default(0))
+      .to_owned();
+    manager.alter_table(table).await?;
+    manager.get_connection().execute_unprepared(BACKFILL).await?;
+
+    let index = Index::create()
+      .name("idx-histories-sequence")
+      .table(history::Entity)
+      .col(history::Column::Sequence)
+      .unique()
+      .to_owned();
+    manager.create_index(index).await
+  }
+
+  async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+    let index = Index::drop().name("idx-histories-sequence").table(history::Entity).to_owned();
+    manager.drop_index(index).await?;
+
+    let table =
+      Table::alter().table(history::Entity).drop_column(history::Column::Sequence).to_owned();
+    manager.alter_table(table).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::Migrator;
+  use sea_orm_migration::prelude::*;
+  use sea_orm_migration::sea_orm::{ConnectionTrait, Database, Statement};
+
+  #[tokio::test]
+  async fn test_backfill() -> Result<(), DbErr> {
+    let db = Database::connect("sqlite::memory:").await?;
+    Migrator::up(&db, Some(12)).await?;
+    db.execute_unprepared(
+      r#"
+      INSERT INTO histories (id, command_id, entity, entity_id, actor, timestamp, command)
+        VALUES ('h3', 'c2', 'Journal', 'j', 'alice', '2024-01-02T00:00:00Z', '{}'),
+          ('h2', 'c1', 'Account', 'a', 'alice', '2024-01-01T00:00:00Z', '{}'),
+          ('h1', 'c1', 'Journal', 'j', 'alice', '2024-01-01T00:00:00Z', '{}');
+      "#,
+    )
+    .await?;
+
+    Migrator::up(&db, Some(1)).await?;
+    let sequences = db
+      .query_all(Statement::from_string(
+        db.get_database_backend(),
+        "SELECT id, sequence FROM histories ORDER BY sequence",
+      ))
+      .await?
+      .into_iter()
+      .map(|row| Ok((row.try_get::<String>("", "id")?, row.try_get::<i64>("", "sequence")?)))
+      .collect::<Result<Vec<_>, DbErr>>()?;
+    assert_eq!(
+      vec![("h1".to_string(), 1), ("h2".to_string(), 2), ("h3".to_string(), 3)],
+      sequences
+    );
+
+    Ok(())
+  }
+}
@@ -0,0 +1,100 @@
+//! Compares `hierarchy_report` with the old way, which loads every entry into memory and sums the
+//! items in Rust, and checks both come to the same values. The plain records are read from the
+//! balance snapshots, while the ones filtered by the tags are summed up by `GROUP BY` in the
+//! database. Run with `cargo bench -p test-suite`.
+
+use backend_core::entity::{entry, hierarchy_report, journal, ReadRoot};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+const ENTRIES: usize = 5000;
+const ROUNDS: u32 = 10;
+
+async fn measure<F, Fut>(name: &str, f: F) -> anyhow::Result<Duration>
+where
+  F: Fn() -> Fut,
+  Fut: std::future::Future<Output = anyhow::Result<usize>>,
+{
+  let mut total = Duration::ZERO;
+  let mut size = 0;
+  for _ in 0..ROUNDS {
+    let now = Instant::now();
+    size = f().await?;
+    total += now.elapsed();
+  }
+  let average = total / ROUNDS;
+  println!("  {:<16} {:>10.2?} per round, {} values", name, average, size);
+  Ok(average)
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> anyhow::Result<()> {
+  std::env::set_var("RUST_LOG", "warn");
+  let db = test_suite::init().await?;
+  let journal = journal::Root::find_one(&db, None).await?.unwrap();
+  test_suite::seed_entries(&db, journal.id, ENTRIES).await?;
+  let journal_ids: HashSet<Uuid> = HashSet::from_iter([journal.id]);
+
+  let tags = HashSet::from_iter(["Seeded Tag 0".to_string()]);
+  let start = NaiveDate::from_ymd_opt(2000, 1, 1);
+  let cases = [
+    ("snapshots", HashSet::default(), None),
+    ("start", HashSet::default(), start),
+    ("tags", tags.clone(), None),
+    ("tags and start", tags, start),
+  ];
+
+  for (case, entry_tags, start) in cases {
+    let find_in_memory = || async {
+      let entries = entry::Root::find_all(
+        &db,
+        Some(entry::Query {
+          journal_id: journal_ids.clone(),
+          typ: Some(entry::Type::Record),
+          start,
+          tags: entry_tags.clone(),
+          ..Default::default()
+        }),
+        None,
+        None,
+      )
+      .await?;
+      let mut values = HashMap::<Uuid, Decimal>::new();
+      for item in entries.iter().flat_map(|entry| &entry.items) {
+        *values.entry(item.account).or_default() += item.amount * item.price;
+      }
+      anyhow::Ok(values)
+    };
+    let find_aggregated = || async {
+      let roots = hierarchy_report::Root::find_all(
+        &db,
+        Some(hierarchy_report::Query {
+          journal_id: journal_ids.clone(),
+          start,
+          entry_tags: entry_tags.clone(),
+          ..Default::default()
+        }),
+        None,
+        None,
+      )
+      .await?;
+      anyhow::Ok(roots.into_iter().flat_map(|root| root.values).collect::<HashMap<_, _>>())
+    };
+
+    println!("{}:", case);
+    let in_memory = measure("in-memory", || async { Ok(find_in_memory().await?.len()) }).await?;
+    let aggregated = measure("aggregated", || async { Ok(find_aggregated().await?.len()) }).await?;
+
+    // The accounts without any record are only in the report, with zero values
+    let expected = find_in_memory().await?;
+    for (account_id, value) in find_aggregated().await? {
+      assert_eq!(expected.get(&account_id).copied().unwrap_or_default(), value, "{}", case);
+    }
+
+    println!("  speedup: {:.1}x", in_memory.as_secs_f64() / aggregated.as_secs_f64());
+  }
+  Ok(())
+}
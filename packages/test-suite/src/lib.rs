@@ -1,8 +1,10 @@
 mod test_runner;
 
 pub use anyhow::Result;
+use backend_core::actor::Actor;
 use backend_core::entity::{
-  account, entry, journal, MAX_SHORT_TEXT_LENGTH, MAX_TAGS_LENGTH, MIN_SHORT_TEXT_LENGTH,
+  account, entry, journal, user, ReadRoot, MAX_SHORT_TEXT_LENGTH, MAX_TAGS_LENGTH,
+  MIN_SHORT_TEXT_LENGTH,
 };
 use fake::faker::chrono::en::Date;
 use fake::faker::company::en::CompanyName;
@@ -11,12 +13,13 @@ use fake::faker::finance::en::Bic;
 use fake::faker::lorem::en::{Paragraph, Words};
 use fake::Fake;
 use itertools::Itertools;
-use migration::sea_orm::{DbConn, Iterable};
+use migration::sea_orm::{ConnectionTrait, DbConn, Iterable};
 use migration::{Migrator, MigratorTrait};
 use rand::seq::{IteratorRandom, SliceRandom};
 use rand::Rng;
 use rust_decimal::Decimal;
 use std::collections::HashSet;
+use uuid::Uuid;
 
 fn gen_tags() -> HashSet<String> {
   Words(0..16)
@@ -114,6 +117,7 @@ pub async fn init() -> backend_core::Result<DbConn> {
             date: Date().fake(),
             tags: gen_tags(),
             items: gen_entry_items(accounts),
+            payee_id: None,
           })
           .collect();
 
@@ -126,6 +130,7 @@ pub async fn init() -> backend_core::Result<DbConn> {
             date: Date().fake(),
             tags: gen_tags(),
             items: gen_entry_items(accounts),
+            payee_id: None,
           })
         }
 
@@ -140,6 +145,55 @@ pub async fn init() -> backend_core::Result<DbConn> {
   Ok(db)
 }
 
+/// Create a user for the tests to act as
+pub async fn create_user(db: &impl ConnectionTrait, name: &str) -> backend_core::Result<Actor> {
+  let user = user::Root::handle(
+    db,
+    &Actor::system("admin"),
+    user::Command::Create(user::CommandCreate { name: name.to_string() }),
+  )
+  .await?
+  .remove(0);
+  Ok(Actor::User(user.id))
+}
+
+/// Wrap the message in a gRPC request made by the actor, as if authenticated
+pub fn request<T>(actor: &Actor, message: T) -> tonic::Request<T> {
+  let mut request = tonic::Request::new(message);
+  request.extensions_mut().insert(actor.clone());
+  request
+}
+
+/// Fill the journal with lots of random records, for the benchmarks. Each record is tagged with
+/// one of `Seeded Tag 0` to `Seeded Tag 3`
+pub async fn seed_entries(db: &DbConn, journal_id: Uuid, count: usize) -> backend_core::Result<()> {
+  let accounts = account::Root::find_all(
+    db,
+    Some(account::Query { journal_id: HashSet::from_iter([journal_id]), ..Default::default() }),
+    None,
+    None,
+  )
+  .await?;
+
+  for chunk in &(0..count).chunks(500) {
+    let commands = chunk
+      .map(|idx| entry::CommandCreate {
+        journal_id,
+        name: format!("Seeded Entry {}", idx),
+        description: "".to_string(),
+        typ: entry::Type::Record,
+        date: Date().fake(),
+        tags: HashSet::from_iter([format!("Seeded Tag {}", idx % 4)]),
+        items: gen_entry_items(&accounts),
+        payee_id: None,
+      })
+      .collect();
+    let _ = entry::Root::create(db, commands).await?;
+  }
+
+  Ok(())
+}
+
 #[macro_export]
 macro_rules! generate_tests {
   ($runner: ident; $package: ident; $( $func: ident ),*) => {